@@ -0,0 +1,237 @@
+use std::{
+    collections::HashSet,
+    fmt::Write as _,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use clap::Args;
+use indicatif::HumanBytes;
+use tokio::{
+    fs,
+    io::{self, AsyncWriteExt as _},
+};
+
+use super::Runner;
+use crate::{
+    context::{Context, dirs::ProjectDirs as _},
+    ext::tokio::path::PathExt as _,
+    store::{RefKind, Store},
+};
+
+const SECONDS_PER_DAY: u64 = 60 * 60 * 24;
+
+#[derive(Args)]
+pub(super) struct Cleanup {
+    #[arg(long, value_name = "DAYS")]
+    prune: Option<u64>,
+
+    #[arg(long, value_name = "BYTES")]
+    max_size: Option<u64>,
+
+    #[arg(short = 'n', long)]
+    dry_run: bool,
+}
+
+impl Runner for Cleanup {
+    async fn run_parallelly(self, context: Arc<Context>) -> anyhow::Result<()> {
+        let max_age_days = self.prune.unwrap_or(context.config.cleanup_max_age_days);
+
+        let max_age = Duration::from_secs(max_age_days.saturating_mul(SECONDS_PER_DAY));
+
+        let size_limit = self.max_size.or(context.config.cache_size_limit);
+
+        let mut output = String::new();
+
+        let referenced_sha256s = self.prune_refs(&context).await?;
+
+        let views = Self::load_views(&context).await?;
+
+        let mut blobs = context.store.blobs().await?;
+
+        blobs.sort_by_key(|blob| blob.modified);
+
+        let mut total_length = blobs
+            .iter()
+            .map(|blob| blob.length)
+            .fold(0, u64::saturating_add);
+
+        let mut freed_length = 0_u64;
+
+        let mut evicted_file_paths = HashSet::new();
+
+        let now = SystemTime::now();
+
+        for blob in blobs {
+            if !blob.is_incomplete && referenced_sha256s.contains(&blob.sha256) {
+                continue;
+            }
+
+            let age = now.duration_since(blob.modified).unwrap_or_default();
+
+            let is_expired = age >= max_age;
+
+            let is_over_limit = size_limit.is_some_and(|size_limit| total_length > size_limit);
+
+            if !is_expired && !is_over_limit {
+                continue;
+            }
+
+            let real_file_path = blob.file_path.realpath().await?;
+
+            self.remove(&blob.file_path, Some(blob.length), &mut output)
+                .await?;
+
+            if !self.dry_run {
+                let verified_file_path = Store::verified_file(&blob.file_path);
+
+                if let Err(err) = fs::remove_file(verified_file_path).await
+                    && err.kind() != io::ErrorKind::NotFound
+                {
+                    return Err(err.into());
+                }
+            }
+
+            total_length = total_length.saturating_sub(blob.length);
+
+            freed_length = freed_length.saturating_add(blob.length);
+
+            evicted_file_paths.insert(real_file_path);
+        }
+
+        for (link_path, real_file_path) in views {
+            let is_dangling = real_file_path
+                .is_none_or(|real_file_path| evicted_file_paths.contains(&real_file_path));
+
+            if is_dangling {
+                self.remove(&link_path, None, &mut output).await?;
+            }
+        }
+
+        if freed_length > 0 {
+            let freed_length = HumanBytes(freed_length);
+
+            if self.dry_run {
+                writeln!(
+                    output,
+                    "This operation would free approximately {freed_length} of disk space."
+                )?;
+            } else {
+                writeln!(
+                    output,
+                    "This operation has freed approximately {freed_length} of disk space."
+                )?;
+            }
+        }
+
+        let mut stdout = io::stdout();
+
+        stdout.write_all(output.as_bytes()).await?;
+
+        stdout.flush().await?;
+
+        Ok(())
+    }
+}
+
+impl Cleanup {
+    async fn prune_refs(&self, context: &Context) -> anyhow::Result<HashSet<String>> {
+        let mut referenced_sha256s = HashSet::new();
+
+        for store_ref in context.store.refs().await? {
+            let id = &store_ref.id;
+
+            let version = &store_ref.version;
+
+            let installed_dir_path = match store_ref.kind {
+                RefKind::Formula => context.homebrew_dirs.keg_dir(id, version),
+                RefKind::Cask => context.homebrew_dirs.staged_dir(id, version),
+            };
+
+            let is_installed = installed_dir_path.is_dir_exists_nofollow().await?;
+
+            let real_file_path = store_ref.link_path.realpath_or_none().await?;
+
+            if let (true, Some(real_file_path)) = (is_installed, real_file_path)
+                && let Some(sha256) = real_file_path.file_name()
+            {
+                let sha256 = sha256.to_string_lossy().into_owned();
+
+                referenced_sha256s.insert(sha256);
+
+                continue;
+            }
+
+            if !self.dry_run {
+                fs::remove_file(&store_ref.link_path).await?;
+            }
+        }
+
+        Ok(referenced_sha256s)
+    }
+
+    async fn load_views(context: &Context) -> anyhow::Result<Vec<(PathBuf, Option<PathBuf>)>> {
+        let cache_dir_path = context.homebrew_dirs.cache_dir();
+
+        let view_dir_paths = [
+            cache_dir_path.join("downloads"),
+            cache_dir_path.join("Cask"),
+            cache_dir_path,
+        ];
+
+        let mut views = Vec::new();
+
+        for view_dir_path in view_dir_paths {
+            let mut read_dir = match fs::read_dir(&view_dir_path).await {
+                Ok(read_dir) => read_dir,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err.into()),
+            };
+
+            while let Some(entry) = read_dir.next_entry().await? {
+                let link_path = entry.path();
+
+                if !link_path.is_link_exists_nofollow().await? {
+                    continue;
+                }
+
+                let real_file_path = link_path.realpath_or_none().await?;
+
+                views.push((link_path, real_file_path));
+            }
+        }
+
+        Ok(views)
+    }
+
+    async fn remove(
+        &self,
+        path: &Path,
+        length: Option<u64>,
+        output: &mut String,
+    ) -> anyhow::Result<()> {
+        let prefix = if self.dry_run {
+            "Would remove"
+        } else {
+            "Removing"
+        };
+
+        let path_display = path.display();
+
+        match length {
+            Some(length) => {
+                let length = HumanBytes(length);
+
+                writeln!(output, "{prefix}: {path_display} ({length})")?;
+            },
+            None => writeln!(output, "{prefix}: {path_display}")?,
+        }
+
+        if !self.dry_run {
+            fs::remove_file(path).await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+use std::{ffi::OsString, fmt::Write as _, sync::Arc};
+
+use anyhow::anyhow;
+use clap::{Args, Subcommand};
+use tokio::io::{self, AsyncWriteExt as _};
+
+use super::Runner;
+use crate::context::{
+    Context,
+    config::{self, ConfigEntry},
+};
+
+#[derive(Args)]
+pub(super) struct Config {
+    #[command(subcommand)]
+    action: Option<Action>,
+}
+
+impl Config {
+    pub(super) fn into_brew_args(self) -> Result<Vec<OsString>, Self> {
+        if self.action.is_some() {
+            return Err(self);
+        }
+
+        let brew_args = vec![OsString::from("config")];
+
+        Ok(brew_args)
+    }
+}
+
+#[derive(Subcommand)]
+enum Action {
+    Get {
+        key: String,
+
+        #[arg(long)]
+        show_origin: bool,
+    },
+    Set {
+        key: String,
+
+        value: String,
+
+        #[arg(long)]
+        project: bool,
+    },
+    #[command(visible_alias = "ls")]
+    List {
+        #[arg(long)]
+        show_origin: bool,
+    },
+}
+
+impl Runner for Config {
+    async fn run_parallelly(self, context: Arc<Context>) -> anyhow::Result<()> {
+        let mut output = String::new();
+
+        let Some(action) = self.action else {
+            return Ok(());
+        };
+
+        match action {
+            Action::Get {
+                key,
+                show_origin,
+            } => {
+                let entries = context.config.entries(&context.config_figment)?;
+
+                let Some(entry) = entries.into_iter().find(|entry| entry.key == key) else {
+                    let err = anyhow!(r#"Config "{key}" is not set"#);
+
+                    return Err(err);
+                };
+
+                if show_origin {
+                    Self::write_origin(&mut output, &entry)?;
+                }
+
+                writeln!(output, "{}", entry.value)?;
+            },
+            Action::Set {
+                key,
+                value,
+                project,
+            } => {
+                let config_file_path = if project {
+                    config::Config::project_file()?
+                } else {
+                    context.neobrew_dirs.config_file()
+                };
+
+                config::Config::set(&config_file_path, &key, &value)?;
+            },
+            Action::List {
+                show_origin,
+            } => {
+                let entries = context.config.entries(&context.config_figment)?;
+
+                for entry in entries {
+                    if show_origin {
+                        Self::write_origin(&mut output, &entry)?;
+                    }
+
+                    writeln!(output, "{} = {}", entry.key, entry.value)?;
+                }
+            },
+        }
+
+        let mut stdout = io::stdout();
+
+        stdout.write_all(output.as_bytes()).await?;
+
+        stdout.flush().await?;
+
+        Ok(())
+    }
+}
+
+impl Config {
+    fn write_origin(output: &mut String, entry: &ConfigEntry) -> anyhow::Result<()> {
+        let origin = entry.origin.as_deref().unwrap_or("Unknown");
+
+        write!(output, "{origin}\t")?;
+
+        Ok(())
+    }
+}
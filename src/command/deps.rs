@@ -0,0 +1,267 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write as _,
+    sync::Arc,
+};
+
+use clap::{Args, ValueEnum};
+use serde::Serialize;
+use tokio::io::{self, AsyncWriteExt as _};
+
+use super::Runner;
+use crate::{
+    context::Context,
+    package::{PackageExt as _, raw::formula::DependencyType, resolved::ResolvedPackage},
+    registries::{DependencyFilter, Registries},
+};
+
+#[expect(clippy::struct_excessive_bools)]
+#[derive(Args)]
+pub(super) struct Deps {
+    #[arg(value_name = "PACKAGE", required = true)]
+    packages: Vec<String>,
+
+    #[arg(long, conflicts_with_all = ["graph", "json"])]
+    tree: bool,
+
+    #[arg(long, value_name = "FORMAT", conflicts_with = "json")]
+    graph: Option<GraphFormat>,
+
+    #[arg(long)]
+    json: bool,
+
+    #[arg(long)]
+    include_build: bool,
+
+    #[arg(long)]
+    include_test: bool,
+
+    #[arg(long)]
+    with_optional: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum GraphFormat {
+    Dot,
+}
+
+impl Runner for Deps {
+    async fn run_parallelly(self, context: Arc<Context>) -> anyhow::Result<()> {
+        let dependency_filter = DependencyFilter {
+            include_build: self.include_build,
+            include_test: self.include_test,
+            include_optional: self.with_optional,
+        };
+
+        let registries = Registries::try_with_dependency_filter(dependency_filter, context).await?;
+
+        let mut roots = Vec::new();
+
+        for package in &self.packages {
+            let resolved_package = registries.resolve_one(Arc::from(package.as_str())).await?;
+
+            roots.push(resolved_package);
+        }
+
+        let mut nodes = BTreeMap::new();
+
+        for root in &roots {
+            Node::collect(root, &mut nodes);
+        }
+
+        let mut output = String::new();
+
+        if self.tree {
+            for root in &roots {
+                writeln!(output, "{}", root.id())?;
+
+                Self::write_tree(root, "", &mut output)?;
+
+                writeln!(output)?;
+            }
+        } else if let Some(GraphFormat::Dot) = self.graph {
+            Self::write_dot(&nodes, &mut output)?;
+        } else if self.json {
+            let nodes = nodes.into_values().collect::<Vec<_>>();
+
+            let nodes = serde_json::to_string_pretty(&nodes)?;
+
+            writeln!(output, "{nodes}")?;
+        } else {
+            let ids = nodes
+                .values()
+                .flat_map(|node| node.dependencies.iter())
+                .map(|edge| edge.name.as_str())
+                .collect::<BTreeSet<_>>();
+
+            for id in ids {
+                writeln!(output, "{id}")?;
+            }
+        }
+
+        let mut stdout = io::stdout();
+
+        stdout.write_all(output.as_bytes()).await?;
+
+        stdout.flush().await?;
+
+        Ok(())
+    }
+}
+
+impl Deps {
+    fn write_tree(
+        resolved_package: &ResolvedPackage,
+        prefix: &str,
+        output: &mut String,
+    ) -> anyhow::Result<()> {
+        let edges = Node::edges_of(resolved_package);
+
+        let mut edges = edges.into_iter().peekable();
+
+        while let Some((dependency, dependency_type)) = edges.next() {
+            let is_last = edges.peek().is_none();
+
+            let (branch, indent) = if is_last {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+
+            let id = dependency.id();
+
+            write!(output, "{prefix}{branch}{id}")?;
+
+            if dependency_type != DependencyType::Runtime {
+                write!(output, " [{}]", dependency_type.as_str())?;
+            }
+
+            writeln!(output)?;
+
+            let prefix = format!("{prefix}{indent}");
+
+            Self::write_tree(&dependency, &prefix, output)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_dot(nodes: &BTreeMap<String, Node>, output: &mut String) -> anyhow::Result<()> {
+        writeln!(output, "digraph dependencies {{")?;
+
+        for node in nodes.values() {
+            let name = &node.name;
+
+            if node.is_cask {
+                writeln!(output, r#"  "{name}" [shape=box];"#)?;
+            } else {
+                writeln!(output, r#"  "{name}";"#)?;
+            }
+        }
+
+        for node in nodes.values() {
+            let name = &node.name;
+
+            for edge in &node.dependencies {
+                let dependency_name = &edge.name;
+
+                if edge.dependency_type == DependencyType::Runtime {
+                    writeln!(output, r#"  "{name}" -> "{dependency_name}";"#)?;
+                } else {
+                    let dependency_type = edge.dependency_type.as_str();
+
+                    let attributes = format!(r#"[style=dashed, label="{dependency_type}"]"#);
+
+                    writeln!(output, r#"  "{name}" -> "{dependency_name}" {attributes};"#)?;
+                }
+            }
+        }
+
+        writeln!(output, "}}")?;
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct Node {
+    name: String,
+    version: String,
+    is_cask: bool,
+    dependencies: Vec<Edge>,
+}
+
+#[derive(Serialize)]
+struct Edge {
+    name: String,
+    dependency_type: DependencyType,
+}
+
+impl Node {
+    fn collect(resolved_package: &ResolvedPackage, nodes: &mut BTreeMap<String, Self>) {
+        let id = resolved_package.id();
+
+        if nodes.contains_key(id) {
+            return;
+        }
+
+        let edges = Self::edges_of(resolved_package);
+
+        let dependencies = edges
+            .iter()
+            .map(|(dependency, dependency_type)| Edge {
+                name: dependency.id().to_owned(),
+                dependency_type: *dependency_type,
+            })
+            .collect();
+
+        let node = Self {
+            name: id.to_owned(),
+            version: resolved_package.version().to_owned(),
+            is_cask: matches!(resolved_package, ResolvedPackage::Cask(_)),
+            dependencies,
+        };
+
+        nodes.insert(id.to_owned(), node);
+
+        for (dependency, _) in &edges {
+            Self::collect(dependency, nodes);
+        }
+    }
+
+    fn edges_of(resolved_package: &ResolvedPackage) -> Vec<(ResolvedPackage, DependencyType)> {
+        let mut edges = match resolved_package {
+            ResolvedPackage::Formula(resolved_formula) => resolved_formula
+                .dependencies()
+                .iter()
+                .map(|dependency| {
+                    let dependency_type = resolved_formula.dependency_type(dependency);
+
+                    let dependency = ResolvedPackage::Formula(Arc::clone(dependency));
+
+                    (dependency, dependency_type)
+                })
+                .collect::<Vec<_>>(),
+            ResolvedPackage::Cask(resolved_cask) => {
+                let dependencies = resolved_cask
+                    .dependencies()
+                    .iter()
+                    .map(|dependency| ResolvedPackage::Cask(Arc::clone(dependency)));
+
+                let formula_dependencies = resolved_cask
+                    .formula_dependencies()
+                    .iter()
+                    .map(|dependency| ResolvedPackage::Formula(Arc::clone(dependency)));
+
+                dependencies
+                    .chain(formula_dependencies)
+                    .map(|dependency| (dependency, DependencyType::Runtime))
+                    .collect()
+            },
+        };
+
+        edges.sort_by(|(left, _), (right, _)| left.id().cmp(right.id()));
+
+        edges
+    }
+}
@@ -0,0 +1,104 @@
+use std::{ffi::OsString, fmt::Write as _, sync::Arc};
+
+use anyhow::anyhow;
+use clap::Args;
+use tokio::io::{self, AsyncWriteExt as _};
+
+use super::Runner;
+use crate::context::Context;
+
+#[derive(Args)]
+pub(super) struct Doctor {
+    #[arg(long)]
+    repair: bool,
+
+    #[arg(long, requires = "repair")]
+    dry_run: bool,
+
+    #[arg(
+        trailing_var_arg = true,
+        allow_hyphen_values = true,
+        conflicts_with = "repair"
+    )]
+    brew_args: Vec<OsString>,
+}
+
+impl Doctor {
+    pub(super) fn into_brew_args(self) -> Result<Vec<OsString>, Self> {
+        if self.repair {
+            return Err(self);
+        }
+
+        let brew_args = [OsString::from("doctor")]
+            .into_iter()
+            .chain(self.brew_args)
+            .collect();
+
+        Ok(brew_args)
+    }
+}
+
+impl Runner for Doctor {
+    async fn run_parallelly(self, context: Arc<Context>) -> anyhow::Result<()> {
+        let stale_journals = context.journal.load_stale().await?;
+
+        let stale_journal_count = stale_journals.len();
+
+        let mut output = String::new();
+
+        for stale_journal in stale_journals {
+            let journal_pstr = stale_journal.file_path().to_string_lossy();
+            let journal_pstr = journal_pstr.into_owned();
+
+            let change_count = stale_journal.change_count();
+
+            let is_committed = stale_journal.is_committed();
+
+            if self.dry_run {
+                writeln!(
+                    output,
+                    r#"Interrupted transaction "{journal_pstr}" has {change_count} changes"#,
+                )?;
+
+                continue;
+            }
+
+            stale_journal.repair().await?;
+
+            if is_committed {
+                writeln!(
+                    output,
+                    r#"Completed transaction "{journal_pstr}" ({change_count} changes kept)"#,
+                )?;
+            } else {
+                writeln!(
+                    output,
+                    r#"Rolled back transaction "{journal_pstr}" ({change_count} changes undone)"#,
+                )?;
+            }
+        }
+
+        if stale_journal_count == 0 {
+            writeln!(output, "No interrupted transactions found.")?;
+        }
+
+        let mut stdout = io::stdout();
+
+        stdout.write_all(output.as_bytes()).await?;
+
+        stdout.flush().await?;
+
+        if stale_journal_count > 0 && self.dry_run {
+            let bin_name = env!("CARGO_PKG_METADATA_NEOBREW_BIN_NAME");
+
+            let err = anyhow!(
+                "Found {stale_journal_count} interrupted transactions, run `{bin_name} doctor \
+                 --repair` to recover"
+            );
+
+            return Err(err);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,492 @@
+use std::{
+    fmt::Write as _,
+    io::{self as std_io, IsTerminal as _},
+    path::Path,
+    sync::Arc,
+};
+
+use anyhow::Context as _;
+use async_walkdir::WalkDir;
+use chrono::{DateTime, Local};
+use clap::Args;
+use futures::stream::StreamExt as _;
+use tokio::{
+    fs,
+    io::{self, AsyncWriteExt as _},
+};
+
+use super::Runner;
+use crate::{
+    context::Context,
+    ext::tokio::path::PathExt as _,
+    package::{
+        PackageExt as _,
+        installed::{InstalledPackageExt as _, cask::InstalledCask, formula::InstalledFormula},
+        raw::{RawPackage, cask::RawCask, formula::RawFormula},
+        resolved::ResolvedPackage,
+    },
+    registries::Registries,
+};
+
+#[derive(Args)]
+pub(super) struct Info {
+    #[arg(value_name = "PACKAGE", required = true)]
+    packages: Vec<String>,
+
+    #[arg(long, visible_alias = "formulae", conflicts_with = "cask")]
+    formula: bool,
+
+    #[arg(long, visible_alias = "casks")]
+    cask: bool,
+}
+
+impl Runner for Info {
+    async fn run_parallelly(self, context: Arc<Context>) -> anyhow::Result<()> {
+        let mut output = String::new();
+
+        for package in &self.packages {
+            let raw_package = self.load(package, &context).await?;
+
+            if !output.is_empty() {
+                writeln!(output)?;
+            }
+
+            match &raw_package {
+                RawPackage::Formula(raw_formula) => {
+                    Self::describe_formula(&mut output, raw_formula, &context).await?;
+                },
+                RawPackage::Cask(raw_cask) => {
+                    Self::describe_cask(&mut output, raw_cask, &context).await?;
+                },
+            }
+        }
+
+        let mut stdout = io::stdout();
+
+        stdout.write_all(output.as_bytes()).await?;
+
+        stdout.flush().await?;
+
+        Ok(())
+    }
+}
+
+impl Info {
+    async fn load(&self, package: &str, context: &Arc<Context>) -> anyhow::Result<RawPackage> {
+        if let Some(raw_package) = self.load_cached(package, context).await? {
+            return Ok(raw_package);
+        }
+
+        let registries = Registries::try_new(Arc::clone(context)).await?;
+
+        let resolved_package = registries.resolve_one(Arc::from(package)).await?;
+
+        let raw_package = match resolved_package {
+            ResolvedPackage::Formula(resolved_formula) => {
+                let tap = resolved_formula.tap();
+
+                let id = resolved_formula.id();
+
+                let raw_formula = RawFormula::load_cached(Some(tap), id, context).await?;
+
+                raw_formula.map(RawPackage::Formula)
+            },
+            ResolvedPackage::Cask(resolved_cask) => {
+                let tap = resolved_cask.tap();
+
+                let id = resolved_cask.id();
+
+                let raw_cask = RawCask::load_cached(Some(tap), id, context).await?;
+
+                raw_cask.map(RawPackage::Cask)
+            },
+        };
+
+        let raw_package =
+            raw_package.with_context(|| format!(r#"Package "{package}" not found"#))?;
+
+        Ok(raw_package)
+    }
+
+    async fn load_cached(
+        &self,
+        package: &str,
+        context: &Context,
+    ) -> anyhow::Result<Option<RawPackage>> {
+        if self.formula {
+            let raw_formula = RawFormula::load_cached(None, package, context).await?;
+
+            return Ok(raw_formula.map(RawPackage::Formula));
+        }
+
+        if self.cask {
+            let raw_cask = RawCask::load_cached(None, package, context).await?;
+
+            return Ok(raw_cask.map(RawPackage::Cask));
+        }
+
+        RawPackage::load_cached(package, context).await
+    }
+
+    async fn describe_formula(
+        output: &mut String,
+        raw_formula: &RawFormula,
+        context: &Context,
+    ) -> anyhow::Result<()> {
+        let id = raw_formula.id();
+
+        let version = raw_formula.version();
+
+        let bottled = match raw_formula.bottle_tag(context)? {
+            Some(_) => " (bottled)",
+            None => "",
+        };
+
+        let head = match raw_formula.head_version() {
+            Some(_) => ", HEAD",
+            None => "",
+        };
+
+        let keg_only = if raw_formula.is_keg_only() {
+            " [keg-only]"
+        } else {
+            ""
+        };
+
+        writeln!(
+            output,
+            "==> {id}: stable {version}{bottled}{head}{keg_only}"
+        )?;
+
+        if let Some(desc) = raw_formula.desc() {
+            writeln!(output, "{desc}")?;
+        }
+
+        if let Some(homepage) = raw_formula.homepage() {
+            writeln!(output, "{homepage}")?;
+        }
+
+        let installed_formulae = InstalledFormula::load_many(id, context).await?;
+
+        if installed_formulae.is_empty() {
+            writeln!(output, "Not installed")?;
+        } else {
+            writeln!(output, "Installed")?;
+        }
+
+        let linked_keg_path = context
+            .homebrew_dirs
+            .linked_keg_prefix_link(id)
+            .realpath_or_none()
+            .await?;
+
+        for installed_formula in &installed_formulae {
+            let keg_dir_path = context
+                .homebrew_dirs
+                .keg_dir(id, installed_formula.version_revision());
+
+            let (file_count, size) = Self::disk_usage(&keg_dir_path).await?;
+
+            let size = Self::disk_usage_readable(size);
+
+            let keg_dir_pstr = keg_dir_path.to_string_lossy();
+
+            let is_linked = match &linked_keg_path {
+                Some(linked_keg_path) => *linked_keg_path == keg_dir_path.realpath().await?,
+                None => false,
+            };
+
+            let linked = if is_linked { " *" } else { "" };
+
+            writeln!(
+                output,
+                "{keg_dir_pstr} ({file_count} files, {size}){linked}"
+            )?;
+
+            if let Some(installed_at) = installed_formula.time().and_then(Self::format_time) {
+                writeln!(
+                    output,
+                    "  Poured from bottle using the formulae.brew.sh API on {installed_at}"
+                )?;
+            }
+        }
+
+        if let Some(source_url) =
+            Self::source_url(raw_formula.tap(), raw_formula.ruby_source_path())
+        {
+            writeln!(output, "From: {source_url}")?;
+        }
+
+        if let Some(license) = raw_formula.license() {
+            writeln!(output, "License: {license}")?;
+        }
+
+        let build_dependencies = raw_formula.build_dependencies();
+
+        let dependencies = raw_formula.dependencies();
+
+        if !build_dependencies.is_empty() || !dependencies.is_empty() {
+            writeln!(output, "==> Dependencies")?;
+
+            if !build_dependencies.is_empty() {
+                let build_dependencies =
+                    Self::describe_formula_dependencies(build_dependencies, context).await?;
+
+                writeln!(output, "Build: {build_dependencies}")?;
+            }
+
+            if !dependencies.is_empty() {
+                let dependencies =
+                    Self::describe_formula_dependencies(dependencies, context).await?;
+
+                writeln!(output, "Required: {dependencies}")?;
+            }
+        }
+
+        if let Some(caveats) = raw_formula.caveats() {
+            let caveats = Self::replace_placeholders(caveats, context);
+
+            writeln!(output, "==> Caveats")?;
+            writeln!(output, "{}", caveats.trim_end())?;
+        }
+
+        Ok(())
+    }
+
+    async fn describe_cask(
+        output: &mut String,
+        raw_cask: &RawCask,
+        context: &Context,
+    ) -> anyhow::Result<()> {
+        let id = raw_cask.id();
+
+        let version = raw_cask.version();
+
+        writeln!(output, "==> {id}: {version}")?;
+
+        if let Some(homepage) = raw_cask.homepage() {
+            writeln!(output, "{homepage}")?;
+        }
+
+        let installed_casks = InstalledCask::load_many(id, context).await?;
+
+        if installed_casks.is_empty() {
+            writeln!(output, "Not installed")?;
+        } else {
+            writeln!(output, "Installed")?;
+        }
+
+        for installed_cask in &installed_casks {
+            let staged_dir_path = context
+                .homebrew_dirs
+                .staged_dir(id, installed_cask.version());
+
+            let (_, size) = Self::disk_usage(&staged_dir_path).await?;
+
+            let size = Self::disk_usage_readable(size);
+
+            let staged_dir_pstr = staged_dir_path.to_string_lossy();
+
+            writeln!(output, "{staged_dir_pstr} ({size})")?;
+
+            if let Some(installed_at) = installed_cask.time().and_then(Self::format_time) {
+                writeln!(
+                    output,
+                    "  Installed using the formulae.brew.sh API on {installed_at}"
+                )?;
+            }
+        }
+
+        if let Some(source_url) = Self::source_url(raw_cask.tap(), raw_cask.ruby_source_path()) {
+            writeln!(output, "From: {source_url}")?;
+        }
+
+        writeln!(output, "==> Name")?;
+
+        let names = raw_cask.names();
+
+        if names.is_empty() {
+            writeln!(output, "None")?;
+        }
+
+        for name in names {
+            writeln!(output, "{name}")?;
+        }
+
+        writeln!(output, "==> Description")?;
+
+        let desc = raw_cask.desc().unwrap_or("None");
+
+        writeln!(output, "{desc}")?;
+
+        let formula_dependencies = raw_cask.formula_dependencies();
+
+        let dependencies = raw_cask.dependencies();
+
+        if !formula_dependencies.is_empty() || !dependencies.is_empty() {
+            writeln!(output, "==> Dependencies")?;
+
+            let formula_dependencies =
+                Self::describe_formula_dependencies(formula_dependencies, context).await?;
+
+            let dependencies = Self::describe_cask_dependencies(dependencies, context).await?;
+
+            let all_dependencies = [formula_dependencies, dependencies]
+                .into_iter()
+                .filter(|dependencies| !dependencies.is_empty())
+                .collect::<Vec<_>>();
+            let all_dependencies = all_dependencies.join(", ");
+
+            writeln!(output, "{all_dependencies}")?;
+        }
+
+        if let Some(caveats) = raw_cask.caveats() {
+            let caveats = Self::replace_placeholders(caveats, context);
+
+            writeln!(output, "==> Caveats")?;
+            writeln!(output, "{}", caveats.trim_end())?;
+        }
+
+        Ok(())
+    }
+
+    async fn describe_formula_dependencies(
+        dependencies: &[String],
+        context: &Context,
+    ) -> anyhow::Result<String> {
+        let mut described_dependencies = Vec::new();
+
+        for dependency in dependencies {
+            let installed_formulae = InstalledFormula::load_many(dependency, context).await?;
+
+            let is_installed = !installed_formulae.is_empty();
+
+            let described_dependency = Self::describe_dependency(dependency, is_installed);
+
+            described_dependencies.push(described_dependency);
+        }
+
+        let described_dependencies = described_dependencies.join(", ");
+
+        Ok(described_dependencies)
+    }
+
+    async fn describe_cask_dependencies(
+        dependencies: &[String],
+        context: &Context,
+    ) -> anyhow::Result<String> {
+        let mut described_dependencies = Vec::new();
+
+        for dependency in dependencies {
+            let installed_casks = InstalledCask::load_many(dependency, context).await?;
+
+            let is_installed = !installed_casks.is_empty();
+
+            let described_dependency = Self::describe_dependency(dependency, is_installed);
+
+            described_dependencies.push(described_dependency);
+        }
+
+        let described_dependencies = described_dependencies.join(", ");
+
+        Ok(described_dependencies)
+    }
+
+    fn describe_dependency(dependency: &str, is_installed: bool) -> String {
+        if !std_io::stdout().is_terminal() {
+            return dependency.to_owned();
+        }
+
+        if is_installed {
+            format!("{dependency} ✔")
+        } else {
+            format!("{dependency} ✘")
+        }
+    }
+
+    fn source_url(tap: &str, ruby_source_path: Option<&str>) -> Option<String> {
+        let ruby_source_path = ruby_source_path?;
+
+        let (user, repo) = tap.split_once('/')?;
+
+        let user = match user {
+            "homebrew" => "Homebrew",
+            user => user,
+        };
+
+        let source_url =
+            format!("https://github.com/{user}/homebrew-{repo}/blob/HEAD/{ruby_source_path}");
+
+        Some(source_url)
+    }
+
+    fn replace_placeholders(text: &str, context: &Context) -> String {
+        let prefix_dir_path = context.homebrew_dirs.prefix_dir();
+        let prefix_dir_pstr = prefix_dir_path.to_string_lossy();
+
+        let cellar_dir_path = context.homebrew_dirs.cellar_dir();
+        let cellar_dir_pstr = cellar_dir_path.to_string_lossy();
+
+        text.replace("$HOMEBREW_PREFIX", &prefix_dir_pstr)
+            .replace("$HOMEBREW_CELLAR", &cellar_dir_pstr)
+    }
+
+    fn format_time(time: i64) -> Option<String> {
+        let time = DateTime::from_timestamp(time, 0)?;
+        let time = time.with_timezone(&Local);
+        let time = time.format("%Y-%m-%d at %H:%M:%S");
+        let time = time.to_string();
+
+        Some(time)
+    }
+
+    async fn disk_usage(dir_path: &Path) -> anyhow::Result<(u64, u64)> {
+        let mut file_count = 0_u64;
+
+        let mut size = 0_u64;
+
+        let mut dir_entries = WalkDir::new(dir_path);
+
+        while let Some(dir_entry) = dir_entries.next().await {
+            let dir_entry = dir_entry?;
+
+            let dir_entry_path = dir_entry.path();
+
+            let metadata = fs::symlink_metadata(&dir_entry_path).await?;
+
+            if metadata.is_dir() {
+                continue;
+            }
+
+            file_count = file_count.saturating_add(1);
+
+            size = size.saturating_add(metadata.len());
+        }
+
+        Ok((file_count, size))
+    }
+
+    #[expect(clippy::cast_precision_loss)]
+    fn disk_usage_readable(size: u64) -> String {
+        const KILOBYTE: u64 = 1 << 10;
+        const MEGABYTE: u64 = 1 << 20;
+        const GIGABYTE: u64 = 1 << 30;
+
+        let (unit_size, unit) = match size {
+            GIGABYTE.. => (GIGABYTE, "GB"),
+            MEGABYTE.. => (MEGABYTE, "MB"),
+            KILOBYTE.. => (KILOBYTE, "KB"),
+            _ => return format!("{size}B"),
+        };
+
+        let readable_size = size as f64 / unit_size as f64;
+        let readable_size = format!("{readable_size:.1}");
+
+        let readable_size = match readable_size.strip_suffix(".0") {
+            Some(readable_size) => readable_size,
+            None => &readable_size,
+        };
+
+        format!("{readable_size}{unit}")
+    }
+}
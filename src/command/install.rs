@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use anyhow::{Context as _, anyhow};
 use clap::Args;
 use indicatif::{MultiProgress, ProgressBar};
 use tokio::task::JoinSet;
@@ -9,7 +10,14 @@
     context::Context,
     package::{
         PackageExt as _,
+        installed::{
+            InstalledPackage,
+            InstalledPackageExt,
+            cask::InstalledCask,
+            formula::InstalledFormula,
+        },
         prepared::{PreparedPackage, PreparedPackageExt as _},
+        resolved::{ResolvedPackage, ResolvedPackageExt as _},
     },
     pipeline::{
         Connector as _,
@@ -22,20 +30,36 @@
         },
         pull_connector::extractor::Extractor,
         push_connector::{hasher::Hasher, progressor::Progressor, writer::Writer},
-        sensor_operator::{artifactor::Artifactor, relocator::Relocator},
+        schedule::Schedule,
+        sensor_operator::{artifactor::Artifactor, receipter::Receipter, relocator::Relocator},
     },
-    registries::Registries,
+    registries::{DependencyFilter, Registries},
 };
 
 #[derive(Args)]
 pub(super) struct Install {
     #[arg(value_name = "PACKAGE")]
     packages: Vec<String>,
+
+    #[arg(long)]
+    include_build: bool,
+
+    #[arg(long)]
+    include_test: bool,
+
+    #[arg(long)]
+    with_optional: bool,
 }
 
 impl Runner for Install {
     async fn run_parallelly(self, context: Arc<Context>) -> anyhow::Result<()> {
-        let installation = Installation::prepare(self.packages, context);
+        let dependency_filter = DependencyFilter {
+            include_build: self.include_build || context.config.install.include_build,
+            include_test: self.include_test || context.config.install.include_test,
+            include_optional: self.with_optional || context.config.install.with_optional,
+        };
+
+        let installation = Installation::prepare(self.packages, dependency_filter, false, context);
 
         installation.start().await?;
 
@@ -43,9 +67,13 @@ async fn run_parallelly(self, context: Arc<Context>) -> anyhow::Result<()> {
     }
 }
 
-struct Installation {
+pub(super) struct Installation {
     packages: Vec<String>,
 
+    dependency_filter: DependencyFilter,
+
+    keeps_requested: bool,
+
     multi_pb: MultiProgress,
 
     context: Arc<Context>,
@@ -53,10 +81,19 @@ struct Installation {
 
 impl Installation {
     #[expect(clippy::let_and_return)]
-    fn prepare(packages: Vec<String>, context: Arc<Context>) -> Arc<Self> {
+    pub(super) fn prepare(
+        packages: Vec<String>,
+        dependency_filter: DependencyFilter,
+        keeps_requested: bool,
+        context: Arc<Context>,
+    ) -> Arc<Self> {
         let this = Self {
             packages,
 
+            dependency_filter,
+
+            keeps_requested,
+
             multi_pb: MultiProgress::new(),
 
             context,
@@ -66,7 +103,7 @@ fn prepare(packages: Vec<String>, context: Arc<Context>) -> Arc<Self> {
         this
     }
 
-    async fn start(self: Arc<Self>) -> anyhow::Result<()> {
+    pub(super) async fn start(self: Arc<Self>) -> anyhow::Result<()> {
         if self.packages.is_empty() {
             return Ok(());
         }
@@ -77,10 +114,18 @@ async fn start(self: Arc<Self>) -> anyhow::Result<()> {
     }
 
     async fn run_many(self: Arc<Self>) -> anyhow::Result<()> {
-        let registries = Registries::try_new(Arc::clone(&self.context)).await?;
+        let registries = Registries::try_with_dependency_filter(
+            self.dependency_filter,
+            Arc::clone(&self.context),
+        )
+        .await?;
 
         let resolved_packages = registries.resolve(&self.packages).await?;
 
+        if self.keeps_requested {
+            self.restore_requested(&resolved_packages).await?;
+        }
+
         let max_id_length = resolved_packages
             .iter()
             .map(|resolved_package| resolved_package.id().len())
@@ -143,41 +188,173 @@ async fn run_many(self: Arc<Self>) -> anyhow::Result<()> {
             })
             .collect::<anyhow::Result<Vec<_>>>()?;
 
+        if self.context.config.offline {
+            self.ensure_downloads_cached(&prepared_packages).await?;
+        }
+
+        self.run_scheduled(prepared_packages, pbs).await?;
+
+        Ok(())
+    }
+
+    async fn run_scheduled(
+        self: Arc<Self>,
+        prepared_packages: Vec<PreparedPackage>,
+        pbs: Vec<ProgressBar>,
+    ) -> anyhow::Result<()> {
+        let schedule = Schedule::new(&prepared_packages);
+        let schedule = Arc::new(schedule);
+
+        let scheduled_packages = prepared_packages.into_iter().zip(pbs).collect();
+        let scheduled_packages = schedule.order(scheduled_packages);
+
         let mut set = JoinSet::new();
 
-        for (prepared_package, pb) in prepared_packages.into_iter().zip(pbs) {
+        let mut errs = Vec::new();
+
+        for (prepared_package, pb) in scheduled_packages {
+            if self.context.cancellation.is_cancelled() {
+                break;
+            }
+
             while set.len() >= self.context.concurrency_limit {
-                if let Some(res) = set.join_next().await {
-                    res??;
+                if let Some(res) = set.join_next().await
+                    && let Err(err) = res?
+                {
+                    errs.push(err);
                 }
             }
 
             let this = Arc::clone(&self);
 
-            set.spawn({
+            let schedule = Arc::clone(&schedule);
+
+            let tasks = &self.context.tasks;
+
+            set.spawn(tasks.track_future({
                 async move {
-                    this.run_one(prepared_package, pb).await?;
+                    let id = prepared_package.id().to_owned();
+
+                    let res = this
+                        .run_one(prepared_package, pb, Arc::clone(&schedule))
+                        .await;
 
-                    anyhow::Ok(())
+                    match &res {
+                        Ok(()) => schedule.mark_linked(&id),
+                        Err(_) => schedule.mark_failed(&id),
+                    }
+
+                    res.with_context(|| format!(r#"Package "{id}" was not installed"#))
                 }
-            });
+            }));
         }
 
         while let Some(res) = set.join_next().await {
-            res??;
+            if let Err(err) = res? {
+                errs.push(err);
+            }
+        }
+
+        if errs.len() > 1 {
+            let errs = errs
+                .iter()
+                .map(|err| format!("  {err:#}"))
+                .collect::<Vec<_>>();
+            let errs = errs.join("\n");
+
+            let err = anyhow!("Failed to install packages:\n{errs}");
+
+            return Err(err);
+        }
+
+        if let Some(err) = errs.pop() {
+            return Err(err);
         }
 
         Ok(())
     }
 
+    async fn restore_requested(&self, resolved_packages: &[ResolvedPackage]) -> anyhow::Result<()> {
+        for resolved_package in resolved_packages {
+            let id = resolved_package.id();
+
+            let is_requested = match resolved_package {
+                ResolvedPackage::Formula(_) => {
+                    let installed_formulae = InstalledFormula::load_many(id, &self.context).await?;
+
+                    installed_formulae
+                        .iter()
+                        .any(InstalledPackageExt::is_requested)
+                },
+                ResolvedPackage::Cask(_) => {
+                    let installed_casks = InstalledCask::load_many(id, &self.context).await?;
+
+                    installed_casks
+                        .iter()
+                        .any(InstalledPackageExt::is_requested)
+                },
+            };
+
+            resolved_package.set_is_requested(is_requested);
+        }
+
+        Ok(())
+    }
+
+    async fn ensure_downloads_cached(
+        &self,
+        prepared_packages: &[PreparedPackage],
+    ) -> anyhow::Result<()> {
+        let mut missing_downloads = Vec::new();
+
+        for prepared_package in prepared_packages {
+            if !prepared_package.is_compatible() {
+                continue;
+            }
+
+            let is_installed = prepared_package.is_installed(&self.context).await?;
+
+            let is_up_to_date = prepared_package.is_up_to_date(&self.context).await?;
+
+            if is_installed && is_up_to_date {
+                continue;
+            }
+
+            let Some(file_path) = prepared_package.missing_download(&self.context).await? else {
+                continue;
+            };
+
+            let id = prepared_package.id();
+
+            let version = prepared_package.version();
+
+            let file_path = file_path.display();
+
+            missing_downloads.push(format!(r#"  {id} {version}: "{file_path}""#));
+        }
+
+        if missing_downloads.is_empty() {
+            return Ok(());
+        }
+
+        let missing_downloads = missing_downloads.join("\n");
+
+        let err = anyhow!("Downloads are not cached for offline use:\n{missing_downloads}");
+
+        Err(err)
+    }
+
     async fn run_one(
         &self,
         prepared_package: PreparedPackage,
         pb: ProgressBar,
+        schedule: Arc<Schedule>,
     ) -> anyhow::Result<()> {
         let is_compatible = prepared_package.is_compatible();
 
         if !is_compatible {
+            schedule.mark_failed(prepared_package.id());
+
             pb.set_prefix("Incompatible");
 
             pb.finish();
@@ -197,23 +374,42 @@ async fn run_one(
             return Ok(());
         }
 
+        let previous_installed_packages = if is_installed && !is_up_to_date {
+            InstalledPackage::load_many(prepared_package.id(), &self.context).await?
+        } else {
+            Vec::new()
+        };
+
         pb.set_prefix("Preparing");
 
         let (prepared_package, stream) = prepared_package.with_download(&self.context).await?;
 
-        Pipeline::build(prepared_package, pb.clone(), Arc::clone(&self.context))
-            .with_pb()
-            .fanout(Hasher)
-            .fanout(Writer.fanout(DmgExtractor).fanout(PkgExtractor))
-            .fanout(
-                Extractor
-                    .fanout(Relocator.fanout(Linker))
-                    .fanout(Artifactor),
-            )
-            .run_concurrently(stream)
+        self.uninstall_artifacts(&previous_installed_packages)
             .await?;
 
+        Pipeline::build(
+            prepared_package,
+            pb.clone(),
+            schedule,
+            Arc::clone(&self.context),
+        )
+        .with_pb()
+        .fanout(Hasher)
+        .fanout(Writer.fanout(DmgExtractor).fanout(PkgExtractor))
+        .fanout(
+            Extractor
+                .fanout(Relocator.fanout(Linker))
+                .fanout(Artifactor)
+                .fanout(Receipter),
+        )
+        .run_concurrently(stream)
+        .await?;
+
         if is_installed && !is_up_to_date {
+            pb.set_prefix("Cleaning");
+
+            self.clean_up(previous_installed_packages).await?;
+
             pb.set_prefix("Upgraded");
         } else {
             pb.set_prefix("Installed");
@@ -223,4 +419,44 @@ async fn run_one(
 
         Ok(())
     }
+
+    async fn uninstall_artifacts(
+        &self,
+        installed_packages: &[InstalledPackage],
+    ) -> anyhow::Result<()> {
+        for installed_package in installed_packages {
+            if let InstalledPackage::Cask(installed_cask) = installed_package {
+                Artifactor.uninstall(installed_cask, &self.context).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn clean_up(&self, installed_packages: Vec<InstalledPackage>) -> anyhow::Result<()> {
+        for installed_package in installed_packages {
+            let id = installed_package.id();
+
+            match &installed_package {
+                InstalledPackage::Formula(installed_formula) => {
+                    Linker.unlink(installed_formula, &self.context).await?;
+
+                    let version_revision = installed_formula.version_revision();
+
+                    let keg_dir_path = self.context.homebrew_dirs.keg_dir(id, version_revision);
+
+                    self.context.journal.remove(&keg_dir_path).await?;
+                },
+                InstalledPackage::Cask(installed_cask) => {
+                    let version = installed_cask.version();
+
+                    let staged_dir_path = self.context.homebrew_dirs.staged_dir(id, version);
+
+                    self.context.journal.remove(&staged_dir_path).await?;
+                },
+            }
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,204 @@
+use std::{
+    fmt::Write as _,
+    io::{self as std_io, IsTerminal as _},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::anyhow;
+use async_walkdir::WalkDir;
+use clap::Args;
+use futures::stream::StreamExt as _;
+use tokio::io::{self, AsyncWriteExt as _};
+
+use super::Runner;
+use crate::{
+    context::Context,
+    ext::tokio::path::PathExt as _,
+    package::{PackageExt as _, installed::InstalledPackage},
+    receipt::RECEIPT_FILE_NAME,
+};
+
+#[expect(clippy::struct_excessive_bools)]
+#[derive(Args)]
+pub(super) struct List {
+    #[arg(value_name = "PACKAGE")]
+    packages: Vec<String>,
+
+    #[arg(long, visible_alias = "formulae", conflicts_with = "cask")]
+    formula: bool,
+
+    #[arg(long, visible_alias = "casks")]
+    cask: bool,
+
+    #[arg(long)]
+    versions: bool,
+
+    #[arg(long, conflicts_with = "cask")]
+    pinned: bool,
+
+    #[arg(short = '1')]
+    single_column: bool,
+}
+
+impl Runner for List {
+    async fn run_parallelly(self, context: Arc<Context>) -> anyhow::Result<()> {
+        let installed_packages = self.load(&context).await?;
+
+        let mut formula_entries = Vec::<(String, Vec<String>)>::new();
+        let mut cask_entries = Vec::<(String, Vec<String>)>::new();
+
+        for installed_package in &installed_packages {
+            let id = installed_package.id();
+
+            let (entries, version) = match installed_package {
+                InstalledPackage::Formula(installed_formula) => {
+                    if self.cask {
+                        continue;
+                    }
+
+                    if self.pinned {
+                        let pinned_keg_prefix_link_path =
+                            context.homebrew_dirs.pinned_keg_prefix_link(id);
+
+                        if !pinned_keg_prefix_link_path
+                            .is_link_exists_nofollow()
+                            .await?
+                        {
+                            continue;
+                        }
+                    }
+
+                    (&mut formula_entries, installed_formula.version_revision())
+                },
+                InstalledPackage::Cask(installed_cask) => {
+                    if self.formula || self.pinned {
+                        continue;
+                    }
+
+                    (&mut cask_entries, installed_cask.version())
+                },
+            };
+
+            match entries.last_mut() {
+                Some((last_id, versions)) if last_id == id => versions.push(version.to_owned()),
+                _ => entries.push((id.to_owned(), vec![version.to_owned()])),
+            }
+        }
+
+        let mut output = String::new();
+
+        if self.versions {
+            for (id, versions) in formula_entries.iter().chain(&cask_entries) {
+                let versions = versions.join(" ");
+
+                writeln!(output, "{id} {versions}")?;
+            }
+        } else if !self.packages.is_empty() {
+            for installed_package in &installed_packages {
+                let dir_path = match installed_package {
+                    InstalledPackage::Formula(installed_formula) => context
+                        .homebrew_dirs
+                        .keg_dir(installed_formula.id(), installed_formula.version_revision()),
+                    InstalledPackage::Cask(installed_cask) => context
+                        .homebrew_dirs
+                        .staged_dir(installed_cask.id(), installed_cask.version()),
+                };
+
+                let file_paths = Self::list_files(&dir_path).await?;
+
+                for file_path in file_paths {
+                    let file_pstr = file_path.to_string_lossy();
+
+                    writeln!(output, "{file_pstr}")?;
+                }
+            }
+        } else {
+            let should_show_headers = std_io::stdout().is_terminal()
+                && !self.single_column
+                && !formula_entries.is_empty()
+                && !cask_entries.is_empty();
+
+            if should_show_headers {
+                writeln!(output, "==> Formulae")?;
+            }
+
+            for (id, _) in &formula_entries {
+                writeln!(output, "{id}")?;
+            }
+
+            if should_show_headers {
+                writeln!(output)?;
+                writeln!(output, "==> Casks")?;
+            }
+
+            for (id, _) in &cask_entries {
+                writeln!(output, "{id}")?;
+            }
+        }
+
+        let mut stdout = io::stdout();
+
+        stdout.write_all(output.as_bytes()).await?;
+
+        stdout.flush().await?;
+
+        Ok(())
+    }
+}
+
+impl List {
+    async fn load(&self, context: &Context) -> anyhow::Result<Vec<InstalledPackage>> {
+        if self.packages.is_empty() {
+            let installed_packages = InstalledPackage::load_all(context).await?;
+
+            return Ok(installed_packages);
+        }
+
+        let mut installed_packages = Vec::new();
+
+        for package in &self.packages {
+            let installed_packages_of_one = InstalledPackage::load_many(package, context).await?;
+
+            if installed_packages_of_one.is_empty() {
+                let err = anyhow!(r#"Package "{package}" is not installed"#);
+
+                return Err(err);
+            }
+
+            installed_packages.extend(installed_packages_of_one);
+        }
+
+        Ok(installed_packages)
+    }
+
+    async fn list_files(dir_path: &Path) -> anyhow::Result<Vec<PathBuf>> {
+        let mut file_paths = Vec::new();
+
+        let mut dir_entries = WalkDir::new(dir_path);
+
+        while let Some(dir_entry) = dir_entries.next().await {
+            let dir_entry = dir_entry?;
+
+            let dir_entry_path = dir_entry.path();
+
+            if dir_entry_path.is_dir_exists_nofollow().await? {
+                continue;
+            }
+
+            let Ok(relative_path) = dir_entry_path.strip_prefix(dir_path) else {
+                continue;
+            };
+
+            if relative_path.starts_with(".brew") || relative_path == Path::new(RECEIPT_FILE_NAME) {
+                continue;
+            }
+
+            file_paths.push(dir_entry_path);
+        }
+
+        file_paths.sort();
+
+        Ok(file_paths)
+    }
+}
@@ -1,7 +1,16 @@
+mod cleanup;
+mod config;
+mod deps;
+mod doctor;
+mod info;
 mod install;
+mod list;
+mod outdated;
 mod uninstall;
+mod upgrade;
+mod uses;
 
-use std::{ffi::OsString, sync::Arc};
+use std::{ffi::OsString, pin::pin, sync::Arc};
 
 use clap::{
     ColorChoice,
@@ -16,9 +25,21 @@
 use clap_verbosity_flag::{Verbosity, VerbosityFilter};
 use enum_dispatch::enum_dispatch;
 use proc_exit::{WithCodeResultExt as _, sysexits::ToSysexitsResultExt as _};
-use tokio::process::Command;
-
-use self::{install::Install, uninstall::Uninstall};
+use tokio::{process::Command, signal};
+
+use self::{
+    cleanup::Cleanup,
+    config::Config,
+    deps::Deps,
+    doctor::Doctor,
+    info::Info,
+    install::Install,
+    list::List,
+    outdated::Outdated,
+    uninstall::Uninstall,
+    upgrade::Upgrade,
+    uses::Uses,
+};
 use crate::context::Context;
 
 #[derive(Parser)]
@@ -67,6 +88,12 @@ pub struct Cli {
         },
     )]
     color: ColorChoice,
+
+    #[arg(long, global = true)]
+    offline: bool,
+
+    #[arg(long, global = true)]
+    verify_cache: bool,
 }
 
 impl Cli {
@@ -90,58 +117,120 @@ impl Commands {
     async fn run(self, context: Context) -> proc_exit::ExitResult {
         match self {
             Self::Internal(internal) => {
+                let internal = match internal.into_brew_args() {
+                    Ok(brew_args) => return Self::run_brew(brew_args, &context).await,
+                    Err(internal) => internal,
+                };
+
                 let context = Arc::new(context);
 
-                let result = internal.run_parallelly(context).await;
+                let run = internal.run_parallelly(Arc::clone(&context));
+                let mut run = pin!(run);
 
-                result.with_code(proc_exit::sysexits::SOFTWARE_ERR)?;
+                #[expect(clippy::disallowed_macros)]
+                let result = tokio::select! {
+                    biased;
 
-                proc_exit::Code::SUCCESS.ok()
-            },
-            Self::External(args) => {
-                let mut brew = Command::new("brew");
+                    result = signal::ctrl_c() => {
+                        result.with_code(proc_exit::sysexits::OS_ERR)?;
 
-                brew.args(args)
-                    .env("HOMEBREW_NO_ANALYTICS", "1")
-                    .env("HOMEBREW_NO_ENV_HINTS", "1");
+                        context.cancellation.cancel();
 
-                match context.config.verbosity_filter {
-                    VerbosityFilter::Debug => {
-                        brew.env("HOMEBREW_DEBUG", "1");
+                        run.await
                     },
-                    VerbosityFilter::Info => {
-                        brew.env("HOMEBREW_VERBOSE", "1");
-                    },
-                    _ => {},
+
+                    result = &mut run => result,
+                };
+
+                context.tasks.close();
+                context.tasks.wait().await;
+
+                if context.cancellation.is_cancelled() {
+                    let result = context.journal.roll_back().await;
+
+                    result.with_code(proc_exit::sysexits::SOFTWARE_ERR)?;
+
+                    return proc_exit::bash::SIGINT.ok();
                 }
 
-                match context.config.color_choice {
-                    ColorChoice::Never => {
-                        brew.env("HOMEBREW_NO_COLOR", "1");
-                    },
-                    ColorChoice::Always => {
-                        brew.env("HOMEBREW_COLOR", "1");
+                let result = match result {
+                    Ok(()) => context.journal.commit().await,
+                    Err(err) => match context.journal.roll_back().await {
+                        Ok(()) => Err(err),
+                        Err(rollback_err) => Err(rollback_err.context(err)),
                     },
-                    ColorChoice::Auto => {},
-                }
+                };
+
+                result.with_code(proc_exit::sysexits::SOFTWARE_ERR)?;
+
+                proc_exit::Code::SUCCESS.ok()
+            },
+            Self::External(args) => Self::run_brew(args, &context).await,
+        }
+    }
 
-                let brew = brew.status().await;
+    async fn run_brew(args: Vec<OsString>, context: &Context) -> proc_exit::ExitResult {
+        let mut brew = Command::new("brew");
 
-                let exit_status = brew.to_sysexits()?;
+        brew.args(args)
+            .env("HOMEBREW_NO_ANALYTICS", "1")
+            .env("HOMEBREW_NO_ENV_HINTS", "1");
 
-                proc_exit::Code::from_status(exit_status).ok()?;
+        match context.config.verbosity_filter {
+            VerbosityFilter::Debug => {
+                brew.env("HOMEBREW_DEBUG", "1");
+            },
+            VerbosityFilter::Info => {
+                brew.env("HOMEBREW_VERBOSE", "1");
+            },
+            _ => {},
+        }
 
-                proc_exit::Code::SUCCESS.ok()
+        match context.config.color_choice {
+            ColorChoice::Never => {
+                brew.env("HOMEBREW_NO_COLOR", "1");
             },
+            ColorChoice::Always => {
+                brew.env("HOMEBREW_COLOR", "1");
+            },
+            ColorChoice::Auto => {},
         }
+
+        let brew = brew.status().await;
+
+        let exit_status = brew.to_sysexits()?;
+
+        proc_exit::Code::from_status(exit_status).ok()?;
+
+        proc_exit::Code::SUCCESS.ok()
     }
 }
 
 #[derive(Subcommand)]
 #[enum_dispatch]
 enum Internal {
+    Cleanup(Cleanup),
+    Config(Config),
+    Deps(Deps),
+    Doctor(Doctor),
+    Info(Info),
     Install(Install),
+    #[command(visible_alias = "ls")]
+    List(List),
+    Outdated(Outdated),
     Uninstall(Uninstall),
+    Upgrade(Upgrade),
+    Uses(Uses),
+}
+
+impl Internal {
+    fn into_brew_args(self) -> Result<Vec<OsString>, Self> {
+        match self {
+            Self::Config(config) => config.into_brew_args().map_err(Self::Config),
+            Self::Doctor(doctor) => doctor.into_brew_args().map_err(Self::Doctor),
+            internal => Err(internal),
+        }
+    }
 }
 
 #[enum_dispatch(Internal)]
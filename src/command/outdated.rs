@@ -0,0 +1,321 @@
+use std::{
+    fmt::Write as _,
+    io::{self as std_io, IsTerminal as _},
+    sync::Arc,
+};
+
+use anyhow::anyhow;
+use clap::Args;
+use futures::future;
+use serde::Serialize;
+use tokio::io::{self, AsyncWriteExt as _};
+
+use super::Runner;
+use crate::{
+    context::Context,
+    ext::tokio::path::PathExt as _,
+    package::{
+        PackageExt as _,
+        installed::{InstalledPackage, InstalledPackageExt as _},
+    },
+    registries::{self, Registries},
+};
+
+#[derive(Args)]
+pub(super) struct Outdated {
+    #[arg(value_name = "PACKAGE")]
+    packages: Vec<String>,
+
+    #[arg(long, visible_alias = "formulae", conflicts_with = "cask")]
+    formula: bool,
+
+    #[arg(long, visible_alias = "casks")]
+    cask: bool,
+
+    #[arg(long)]
+    json: bool,
+}
+
+impl Runner for Outdated {
+    async fn run_parallelly(self, context: Arc<Context>) -> anyhow::Result<()> {
+        let outdated_packages = OutdatedPackage::load_many(&self.packages, &context).await?;
+
+        let (outdated_formulae, outdated_casks) = outdated_packages
+            .into_iter()
+            .filter(|outdated_package| {
+                (!self.formula || !outdated_package.is_cask)
+                    && (!self.cask || outdated_package.is_cask)
+            })
+            .partition::<Vec<_>, _>(|outdated_package| !outdated_package.is_cask);
+
+        let mut output = String::new();
+
+        if self.json {
+            let report = Report {
+                formulae: outdated_formulae
+                    .into_iter()
+                    .map(FormulaReport::from)
+                    .collect(),
+                casks: outdated_casks.into_iter().map(CaskReport::from).collect(),
+            };
+
+            let report = serde_json::to_string_pretty(&report)?;
+
+            writeln!(output, "{report}")?;
+        } else {
+            let is_verbose = std_io::stdout().is_terminal();
+
+            for outdated_package in outdated_formulae.iter().chain(&outdated_casks) {
+                let id = &outdated_package.id;
+
+                if !is_verbose {
+                    writeln!(output, "{id}")?;
+
+                    continue;
+                }
+
+                let installed_versions = outdated_package.installed_versions.join(", ");
+
+                let current_version = &outdated_package.current_version;
+
+                write!(output, "{id} ({installed_versions}) < {current_version}")?;
+
+                if let Some(pinned_version) = &outdated_package.pinned_version {
+                    write!(output, " [pinned at {pinned_version}]")?;
+                }
+
+                writeln!(output)?;
+            }
+        }
+
+        let mut stdout = io::stdout();
+
+        stdout.write_all(output.as_bytes()).await?;
+
+        stdout.flush().await?;
+
+        Ok(())
+    }
+}
+
+pub(super) struct OutdatedPackage {
+    pub(super) id: String,
+    pub(super) tap: Option<String>,
+    pub(super) installed_versions: Vec<String>,
+    pub(super) current_version: String,
+    pub(super) pinned_version: Option<String>,
+    pub(super) is_cask: bool,
+}
+
+impl OutdatedPackage {
+    pub(super) fn package(&self) -> String {
+        let tap = self.tap.as_deref();
+
+        let package = if self.is_cask {
+            registries::qualify_cask(tap, &self.id)
+        } else {
+            registries::qualify_formula(tap, &self.id)
+        };
+
+        package.into_owned()
+    }
+
+    pub(super) async fn load_many(
+        packages: &[String],
+        context: &Arc<Context>,
+    ) -> anyhow::Result<Vec<Self>> {
+        let installed_packages = Self::load_installed(packages, context).await?;
+
+        let mut entries = Vec::<(String, Vec<String>, bool, Option<String>)>::new();
+
+        for installed_package in &installed_packages {
+            let id = installed_package.id();
+
+            let tap = installed_package.tap();
+
+            let (version, is_cask) = match installed_package {
+                InstalledPackage::Formula(installed_formula) => {
+                    (installed_formula.version_revision(), false)
+                },
+                InstalledPackage::Cask(installed_cask) => (installed_cask.version(), true),
+            };
+
+            match entries.last_mut() {
+                Some((last_id, versions, last_is_cask, _))
+                    if last_id == id && *last_is_cask == is_cask =>
+                {
+                    versions.push(version.to_owned());
+                },
+                _ => entries.push((
+                    id.to_owned(),
+                    vec![version.to_owned()],
+                    is_cask,
+                    tap.map(str::to_owned),
+                )),
+            }
+        }
+
+        if entries.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let registries = Registries::try_new(Arc::clone(context)).await?;
+
+        let current_version_futs = entries.iter().map(async |(id, _, is_cask, tap)| {
+            let tap = tap.as_deref();
+
+            let current_version = if *is_cask {
+                let package = registries::qualify_cask(tap, id);
+                let package = Arc::from(package.as_ref());
+
+                let resolved_cask = registries.resolve_cask(package).await?;
+
+                resolved_cask.version().to_owned()
+            } else {
+                let package = registries::qualify_formula(tap, id);
+                let package = Arc::from(package.as_ref());
+
+                let resolved_formula = registries.resolve_formula(package).await?;
+
+                resolved_formula.version_revision().into_owned()
+            };
+
+            anyhow::Ok(current_version)
+        });
+
+        let current_version_results = future::join_all(current_version_futs).await;
+
+        let mut this = Vec::new();
+
+        for ((id, installed_versions, is_cask, tap), current_version_res) in
+            entries.into_iter().zip(current_version_results)
+        {
+            let current_version = match current_version_res {
+                Ok(current_version) => current_version,
+                Err(err) => {
+                    let message = format!(r#"Package "{id}" was skipped: {err:#}"#);
+
+                    registries::notify(&message).await?;
+
+                    continue;
+                },
+            };
+
+            if installed_versions.contains(&current_version) {
+                continue;
+            }
+
+            let pinned_version = match context.config.pins.get(&id) {
+                Some(pinned_version) => Some(pinned_version.clone()),
+                None if is_cask => None,
+                None => Self::load_pinned_version(&id, context).await?,
+            };
+
+            let outdated_package = Self {
+                id,
+                tap,
+                installed_versions,
+                current_version,
+                pinned_version,
+                is_cask,
+            };
+
+            this.push(outdated_package);
+        }
+
+        Ok(this)
+    }
+
+    async fn load_installed(
+        packages: &[String],
+        context: &Context,
+    ) -> anyhow::Result<Vec<InstalledPackage>> {
+        if packages.is_empty() {
+            let installed_packages = InstalledPackage::load_all(context).await?;
+
+            return Ok(installed_packages);
+        }
+
+        let mut installed_packages = Vec::new();
+
+        for package in packages {
+            let installed_packages_of_one = InstalledPackage::load_many(package, context).await?;
+
+            if installed_packages_of_one.is_empty() {
+                let err = anyhow!(r#"Package "{package}" is not installed"#);
+
+                return Err(err);
+            }
+
+            installed_packages.extend(installed_packages_of_one);
+        }
+
+        Ok(installed_packages)
+    }
+
+    async fn load_pinned_version(id: &str, context: &Context) -> anyhow::Result<Option<String>> {
+        let pinned_keg_prefix_link_path = context.homebrew_dirs.pinned_keg_prefix_link(id);
+
+        if !pinned_keg_prefix_link_path
+            .is_link_exists_nofollow()
+            .await?
+        {
+            return Ok(None);
+        }
+
+        let Some(pinned_keg_dir_path) = pinned_keg_prefix_link_path.realpath_or_none().await?
+        else {
+            return Ok(None);
+        };
+
+        let pinned_version = pinned_keg_dir_path
+            .file_name()
+            .map(|pinned_version| pinned_version.to_string_lossy().into_owned());
+
+        Ok(pinned_version)
+    }
+}
+
+#[derive(Serialize)]
+struct Report {
+    formulae: Vec<FormulaReport>,
+    casks: Vec<CaskReport>,
+}
+
+#[derive(Serialize)]
+struct FormulaReport {
+    name: String,
+    installed_versions: Vec<String>,
+    current_version: String,
+    pinned: bool,
+    pinned_version: Option<String>,
+}
+
+impl From<OutdatedPackage> for FormulaReport {
+    fn from(outdated_package: OutdatedPackage) -> Self {
+        Self {
+            name: outdated_package.id,
+            installed_versions: outdated_package.installed_versions,
+            current_version: outdated_package.current_version,
+            pinned: outdated_package.pinned_version.is_some(),
+            pinned_version: outdated_package.pinned_version,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct CaskReport {
+    name: String,
+    installed_versions: Vec<String>,
+    current_version: String,
+}
+
+impl From<OutdatedPackage> for CaskReport {
+    fn from(outdated_package: OutdatedPackage) -> Self {
+        Self {
+            name: outdated_package.id,
+            installed_versions: outdated_package.installed_versions,
+            current_version: outdated_package.current_version,
+        }
+    }
+}
@@ -1,10 +1,21 @@
 use std::sync::Arc;
 
+use anyhow::anyhow;
 use clap::Args;
+use indicatif::{MultiProgress, ProgressBar};
 use tokio::task::JoinSet;
 
 use super::Runner;
-use crate::{context::Context, package::resolved::ResolvedPackage, registries::Registries};
+use crate::{
+    context::Context,
+    ext::tokio::path::PathExt as _,
+    package::{PackageExt as _, installed::InstalledPackage},
+    pipeline::{
+        action_operator::linker::Linker,
+        push_connector::progressor::Progressor,
+        sensor_operator::artifactor::Artifactor,
+    },
+};
 
 #[derive(Args)]
 pub(super) struct Uninstall {
@@ -14,24 +25,134 @@ pub(super) struct Uninstall {
 
 impl Runner for Uninstall {
     async fn run_parallelly(self, context: Arc<Context>) -> anyhow::Result<()> {
+        let uninstallation = Uninstallation::prepare(self.packages, context);
+
+        uninstallation.start().await?;
+
+        Ok(())
+    }
+}
+
+struct Uninstallation {
+    packages: Vec<String>,
+
+    multi_pb: MultiProgress,
+
+    context: Arc<Context>,
+}
+
+impl Uninstallation {
+    #[expect(clippy::let_and_return)]
+    fn prepare(packages: Vec<String>, context: Arc<Context>) -> Arc<Self> {
+        let this = Self {
+            packages,
+
+            multi_pb: MultiProgress::new(),
+
+            context,
+        };
+        let this = Arc::new(this);
+
+        this
+    }
+
+    async fn start(self: Arc<Self>) -> anyhow::Result<()> {
         if self.packages.is_empty() {
             return Ok(());
         }
 
-        let resolved_packages = self.resolve_packages(Arc::clone(&context)).await?;
+        self.run_many().await?;
+
+        Ok(())
+    }
+
+    async fn run_many(self: Arc<Self>) -> anyhow::Result<()> {
+        let mut installed_packages_list = Vec::new();
+
+        for package in &self.packages {
+            let installed_packages = InstalledPackage::load_many(package, &self.context).await?;
+
+            if installed_packages.is_empty() {
+                let err = anyhow!(r#"Package "{package}" is not installed"#);
+
+                return Err(err);
+            }
+
+            installed_packages_list.push(installed_packages);
+        }
+
+        let max_id_length = installed_packages_list
+            .iter()
+            .flatten()
+            .map(|installed_package| installed_package.id().len())
+            .max();
+
+        let max_version_length = installed_packages_list
+            .iter()
+            .flatten()
+            .map(|installed_package| installed_package.version().len())
+            .max();
 
         let mut set = JoinSet::new();
 
-        for _resolved_package in resolved_packages {
-            while set.len() >= context.concurrency_limit {
+        for installed_packages in installed_packages_list {
+            if self.context.cancellation.is_cancelled() {
+                break;
+            }
+
+            #[cfg(debug_assertions)]
+            let pbs = installed_packages
+                .iter()
+                .map(|installed_package| {
+                    let pb = Progressor::create(
+                        &self.multi_pb,
+                        installed_package.id(),
+                        installed_package.version(),
+                        max_id_length,
+                        max_version_length,
+                    )?;
+
+                    pb.set_prefix("Uninstalling");
+
+                    anyhow::Ok(pb)
+                })
+                .try_collect::<Vec<_>>()?;
+
+            #[cfg(not(debug_assertions))]
+            let pbs = installed_packages
+                .iter()
+                .map(|installed_package| {
+                    let pb = Progressor::create(
+                        &self.multi_pb,
+                        installed_package.id(),
+                        installed_package.version(),
+                        max_id_length,
+                        max_version_length,
+                    )?;
+
+                    pb.set_prefix("Uninstalling");
+
+                    anyhow::Ok(pb)
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+
+            while set.len() >= self.context.concurrency_limit {
                 if let Some(res) = set.join_next().await {
                     res??;
                 }
             }
 
-            let _context = Arc::clone(&context);
+            let this = Arc::clone(&self);
 
-            set.spawn(async { anyhow::Ok(()) });
+            let tasks = &self.context.tasks;
+
+            set.spawn(tasks.track_future({
+                async move {
+                    this.run_one(installed_packages, pbs).await?;
+
+                    anyhow::Ok(())
+                }
+            }));
         }
 
         while let Some(res) = set.join_next().await {
@@ -40,14 +161,53 @@ async fn run_parallelly(self, context: Arc<Context>) -> anyhow::Result<()> {
 
         Ok(())
     }
-}
 
-impl Uninstall {
-    async fn resolve_packages(self, context: Arc<Context>) -> anyhow::Result<Vec<ResolvedPackage>> {
-        let registries = Registries::try_new(context).await?;
+    async fn run_one(
+        &self,
+        installed_packages: Vec<InstalledPackage>,
+        pbs: Vec<ProgressBar>,
+    ) -> anyhow::Result<()> {
+        let mut package_dir_path = None;
+
+        for (installed_package, pb) in installed_packages.into_iter().zip(pbs) {
+            let id = installed_package.id();
+
+            match &installed_package {
+                InstalledPackage::Formula(installed_formula) => {
+                    Linker.unlink(installed_formula, &self.context).await?;
+
+                    let version_revision = installed_formula.version_revision();
 
-        let resolved_packages = registries.resolve(&self.packages).await?;
+                    let keg_dir_path = self.context.homebrew_dirs.keg_dir(id, version_revision);
 
-        Ok(resolved_packages)
+                    self.context.journal.remove(&keg_dir_path).await?;
+
+                    let rack_dir_path = self.context.homebrew_dirs.rack_dir(id);
+
+                    package_dir_path = Some(rack_dir_path);
+                },
+                InstalledPackage::Cask(installed_cask) => {
+                    Artifactor.uninstall(installed_cask, &self.context).await?;
+
+                    let cask_dir_path = self.context.homebrew_dirs.cask_dir(id);
+
+                    package_dir_path = Some(cask_dir_path);
+                },
+            }
+
+            pb.set_prefix("Uninstalled");
+
+            pb.finish();
+        }
+
+        let Some(package_dir_path) = package_dir_path else {
+            return Ok(());
+        };
+
+        if package_dir_path.is_dir_exists_nofollow().await? {
+            self.context.journal.remove(&package_dir_path).await?;
+        }
+
+        Ok(())
     }
 }
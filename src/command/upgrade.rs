@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use clap::Args;
+
+use super::{Runner, install::Installation, outdated::OutdatedPackage};
+use crate::{context::Context, registries::DependencyFilter};
+
+#[derive(Args)]
+pub(super) struct Upgrade {
+    #[arg(value_name = "PACKAGE")]
+    packages: Vec<String>,
+
+    #[arg(long, visible_alias = "formulae", conflicts_with = "cask")]
+    formula: bool,
+
+    #[arg(long, visible_alias = "casks")]
+    cask: bool,
+}
+
+impl Runner for Upgrade {
+    async fn run_parallelly(self, context: Arc<Context>) -> anyhow::Result<()> {
+        let outdated_packages = OutdatedPackage::load_many(&self.packages, &context).await?;
+
+        let packages = outdated_packages
+            .into_iter()
+            .filter(|outdated_package| {
+                (!self.formula || !outdated_package.is_cask)
+                    && (!self.cask || outdated_package.is_cask)
+                    && outdated_package.pinned_version.is_none()
+            })
+            .map(|outdated_package| outdated_package.package())
+            .collect::<Vec<_>>();
+
+        let dependency_filter = DependencyFilter::default();
+
+        let installation = Installation::prepare(packages, dependency_filter, true, context);
+
+        installation.start().await?;
+
+        Ok(())
+    }
+}
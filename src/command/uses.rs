@@ -0,0 +1,137 @@
+use std::{collections::BTreeSet, fmt::Write as _, sync::Arc};
+
+use anyhow::anyhow;
+use clap::Args;
+use tokio::io::{self, AsyncWriteExt as _};
+
+use super::Runner;
+use crate::{
+    context::Context,
+    package::{
+        PackageExt as _,
+        installed::{InstalledPackage, InstalledPackageExt as _},
+    },
+    registries,
+};
+
+#[derive(Args)]
+pub(super) struct Uses {
+    #[arg(value_name = "PACKAGE", required = true)]
+    packages: Vec<String>,
+
+    #[arg(long)]
+    installed: bool,
+
+    #[arg(long)]
+    recursive: bool,
+}
+
+impl Runner for Uses {
+    async fn run_parallelly(self, context: Arc<Context>) -> anyhow::Result<()> {
+        if !self.installed {
+            let err = anyhow!("Only installed dependents are supported, pass `--installed`");
+
+            return Err(err);
+        }
+
+        let queries = self
+            .packages
+            .iter()
+            .map(|package| {
+                let (tap, name) = match package.rsplit_once('/') {
+                    Some((tap, name)) => (Some(tap), name),
+                    None => (None, package.as_str()),
+                };
+
+                let formula_id = registries::qualify_formula(tap, name);
+
+                let cask_id = registries::qualify_cask(tap, name);
+
+                (formula_id, cask_id)
+            })
+            .collect::<Vec<_>>();
+
+        let installed_packages = InstalledPackage::load_all(&context).await?;
+
+        let mut dependents = BTreeSet::new();
+
+        for installed_package in &installed_packages {
+            let id = installed_package.id();
+
+            let tap = installed_package.tap();
+
+            let (formula_dependency_ids, cask_dependency_ids) = match installed_package {
+                InstalledPackage::Formula(installed_formula) => {
+                    let formula_id = registries::qualify_formula(tap, id);
+
+                    if queries.iter().any(|(query_id, _)| *query_id == formula_id) {
+                        continue;
+                    }
+
+                    let formula_dependency_ids = installed_formula
+                        .runtime_dependencies()
+                        .iter()
+                        .filter(|runtime_dependency| {
+                            self.recursive || runtime_dependency.declared_directly
+                        })
+                        .map(|runtime_dependency| runtime_dependency.full_name.as_str())
+                        .collect::<BTreeSet<_>>();
+
+                    (formula_dependency_ids, BTreeSet::new())
+                },
+                InstalledPackage::Cask(installed_cask) => {
+                    let cask_id = registries::qualify_cask(tap, id);
+
+                    if queries.iter().any(|(_, query_id)| *query_id == cask_id) {
+                        continue;
+                    }
+
+                    let runtime_dependencies = installed_cask.runtime_dependencies();
+
+                    let formula_dependency_ids = runtime_dependencies
+                        .formula
+                        .iter()
+                        .filter(|runtime_dependency| {
+                            self.recursive || runtime_dependency.declared_directly
+                        })
+                        .map(|runtime_dependency| runtime_dependency.full_name.as_str())
+                        .collect::<BTreeSet<_>>();
+
+                    let cask_dependency_ids = runtime_dependencies
+                        .cask
+                        .iter()
+                        .filter(|runtime_dependency| {
+                            self.recursive || runtime_dependency.declared_directly
+                        })
+                        .map(|runtime_dependency| runtime_dependency.full_name.as_str())
+                        .collect::<BTreeSet<_>>();
+
+                    (formula_dependency_ids, cask_dependency_ids)
+                },
+            };
+
+            let is_dependent = queries.iter().all(|(formula_id, cask_id)| {
+                formula_dependency_ids.contains(formula_id.as_ref())
+                    || cask_dependency_ids.contains(cask_id.as_ref())
+            });
+
+            if is_dependent {
+                dependents.insert(id);
+            }
+        }
+
+        let mut output = String::new();
+
+        for dependent in dependents {
+            writeln!(output, "{dependent}")?;
+        }
+
+        let mut stdout = io::stdout();
+
+        stdout.write_all(output.as_bytes()).await?;
+
+        stdout.flush().await?;
+
+        Ok(())
+    }
+}
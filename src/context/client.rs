@@ -0,0 +1,101 @@
+use std::fs;
+
+use anyhow::Context as _;
+use oci_client::client::{Certificate, CertificateEncoding, ClientConfig};
+use reqwest::{NoProxy, Proxy};
+
+use super::config::Config;
+
+const PEM_END_MARKER: &str = "-----END CERTIFICATE-----";
+
+pub(super) fn build_client(config: &Config) -> anyhow::Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder()
+        .user_agent(&config.user_agent)
+        .connect_timeout(config.connect_timeout)
+        .no_proxy();
+
+    if let Some(read_timeout) = config.read_timeout {
+        builder = builder.read_timeout(read_timeout);
+    }
+
+    let no_proxy = config.no_proxy.as_deref().and_then(NoProxy::from_string);
+
+    if let Some(http_proxy) = &config.http_proxy {
+        let proxy = Proxy::http(http_proxy)?;
+        let proxy = proxy.no_proxy(no_proxy.clone());
+
+        builder = builder.proxy(proxy);
+    }
+
+    if let Some(https_proxy) = &config.https_proxy {
+        let proxy = Proxy::https(https_proxy)?;
+        let proxy = proxy.no_proxy(no_proxy);
+
+        builder = builder.proxy(proxy);
+    }
+
+    if let Some(ca_bundle) = load_ca_bundle(config)? {
+        let certs = reqwest::Certificate::from_pem_bundle(&ca_bundle)?;
+
+        builder = builder.tls_certs_merge(certs);
+    }
+
+    let client = builder.build()?;
+
+    Ok(client)
+}
+
+pub(super) fn build_oci_client(config: &Config) -> anyhow::Result<oci_client::Client> {
+    let extra_root_certificates = load_ca_bundle(config)?
+        .map(|ca_bundle| split_pem_bundle(&ca_bundle))
+        .unwrap_or_default();
+
+    let user_agent = config.user_agent.clone();
+    let user_agent = String::leak(user_agent);
+
+    let client_config = ClientConfig {
+        extra_root_certificates,
+
+        http_proxy: config.http_proxy.clone(),
+        https_proxy: config.https_proxy.clone(),
+        no_proxy: config.no_proxy.clone(),
+
+        user_agent,
+
+        connect_timeout: Some(config.connect_timeout),
+        read_timeout: config.read_timeout,
+
+        ..ClientConfig::default()
+    };
+
+    let client = oci_client::Client::new(client_config);
+
+    Ok(client)
+}
+
+fn load_ca_bundle(config: &Config) -> anyhow::Result<Option<Vec<u8>>> {
+    let Some(ca_bundle_file_path) = &config.ca_bundle_file else {
+        return Ok(None);
+    };
+
+    let ca_bundle = fs::read(ca_bundle_file_path).with_context(|| {
+        let ca_bundle_file_path = ca_bundle_file_path.display();
+
+        format!(r#"Failed to read CA bundle "{ca_bundle_file_path}""#)
+    })?;
+
+    Ok(Some(ca_bundle))
+}
+
+fn split_pem_bundle(ca_bundle: &[u8]) -> Vec<Certificate> {
+    let ca_bundle = String::from_utf8_lossy(ca_bundle);
+
+    ca_bundle
+        .split_inclusive(PEM_END_MARKER)
+        .filter(|pem| pem.contains(PEM_END_MARKER))
+        .map(|pem| Certificate {
+            encoding: CertificateEncoding::Pem,
+            data: pem.trim().as_bytes().to_owned(),
+        })
+        .collect()
+}
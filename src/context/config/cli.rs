@@ -11,6 +11,10 @@ pub(super) struct CliConfig {
     verbosity: Option<Verbosity>,
 
     color: Option<ColorChoice>,
+
+    offline: Option<bool>,
+
+    verify_cache: Option<bool>,
 }
 
 impl CliConfig {
@@ -34,9 +38,19 @@ pub(super) fn from_arg_matches(matches: &ArgMatches) -> Self {
             .flatten()
             .copied();
 
+        let is_offline_from_cli = is_from_cli("offline");
+
+        let offline = is_offline_from_cli.then(|| matches.get_flag("offline"));
+
+        let is_verify_cache_from_cli = is_from_cli("verify_cache");
+
+        let verify_cache = is_verify_cache_from_cli.then(|| matches.get_flag("verify_cache"));
+
         Self {
             verbosity,
             color,
+            offline,
+            verify_cache,
         }
     }
 }
@@ -53,9 +67,15 @@ fn data(&self) -> figment::Result<Map<Profile, Dict>> {
 
         let color_choice = self.color.map(|val| val.to_string()).map(Value::from);
 
+        let offline = self.offline.map(Value::from);
+
+        let verify_cache = self.verify_cache.map(Value::from);
+
         let dict = [
             verbosity_filter.map(|val| ("verbosity_filter", val)),
             color_choice.map(|val| ("color_choice", val)),
+            offline.map(|val| ("offline", val)),
+            verify_cache.map(|val| ("verify_cache", val)),
         ];
         let dict = dict
             .into_iter()
@@ -0,0 +1,89 @@
+use std::{
+    fs,
+    io,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context as _;
+use figment::{
+    Metadata,
+    Profile,
+    Source,
+    value::{Dict, Map, Value},
+};
+use toml::Table;
+
+use super::ProviderConfig;
+
+pub(super) struct FileConfig {
+    metadata_name: &'static str,
+
+    file_path: PathBuf,
+
+    table: Table,
+}
+
+impl FileConfig {
+    pub(super) const USER_METADATA_NAME: &str = "Neobrew config file";
+
+    pub(super) const PROJECT_METADATA_NAME: &str = "Project config file";
+
+    pub(super) fn from_file(
+        metadata_name: &'static str,
+        file_path: PathBuf,
+    ) -> anyhow::Result<Self> {
+        let table = load_table(&file_path)?;
+
+        let this = Self {
+            metadata_name,
+
+            file_path,
+
+            table,
+        };
+
+        Ok(this)
+    }
+}
+
+impl ProviderConfig for FileConfig {
+    const METADATA_NAME: &str = "Config file";
+
+    fn metadata(&self) -> Metadata {
+        let source = Source::File(self.file_path.clone());
+
+        Metadata::from(self.metadata_name, source)
+    }
+
+    fn data(&self) -> figment::Result<Map<Profile, Dict>> {
+        table_data(&self.table)
+    }
+}
+
+pub(super) fn load_table(file_path: &Path) -> anyhow::Result<Table> {
+    let content = match fs::read_to_string(file_path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Table::new()),
+        Err(err) => return Err(err.into()),
+    };
+
+    let table = toml::from_str(&content).with_context(|| {
+        let file_path = file_path.display();
+
+        format!(r#"Config file "{file_path}" is invalid"#)
+    })?;
+
+    Ok(table)
+}
+
+#[expect(clippy::result_large_err)]
+fn table_data(table: &Table) -> figment::Result<Map<Profile, Dict>> {
+    let dict = match Value::serialize(table)? {
+        Value::Dict(_, dict) => dict,
+        _ => Dict::new(),
+    };
+
+    let map = Map::from([(Profile::Default, dict)]);
+
+    Ok(map)
+}
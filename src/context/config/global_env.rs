@@ -1,3 +1,5 @@
+use std::env;
+
 use clap::ColorChoice;
 use figment::{
     Profile,
@@ -26,15 +28,57 @@ pub(super) struct GlobalEnvConfig {
     #[serde(default)]
     #[serde_as(as = "NoneAsEmptyString")]
     clicolor: Option<String>,
+
+    #[serde(default)]
+    #[serde_as(as = "NoneAsEmptyString")]
+    docker_config: Option<String>,
+
+    #[serde(default)]
+    #[serde_as(as = "NoneAsEmptyString")]
+    netrc: Option<String>,
+
+    #[serde(default)]
+    #[serde_as(as = "NoneAsEmptyString")]
+    ssl_cert_file: Option<String>,
+
+    #[serde(skip)]
+    http_proxy: Option<String>,
+
+    #[serde(skip)]
+    https_proxy: Option<String>,
+
+    #[serde(skip)]
+    no_proxy: Option<String>,
 }
 
 impl EnvConfig for GlobalEnvConfig {
     const ENV_PREFIX: &str = "";
+
+    fn from_env() -> anyhow::Result<Self> {
+        let mut this = Self::default_from_env()?;
+
+        this.http_proxy = Self::var(&["http_proxy", "HTTP_PROXY", "all_proxy", "ALL_PROXY"]);
+
+        this.https_proxy = Self::var(&["https_proxy", "HTTPS_PROXY", "all_proxy", "ALL_PROXY"]);
+
+        this.no_proxy = Self::var(&["no_proxy", "NO_PROXY"]);
+
+        Ok(this)
+    }
+}
+
+impl GlobalEnvConfig {
+    fn var(keys: &[&str]) -> Option<String> {
+        keys.iter()
+            .filter_map(|key| env::var(key).ok())
+            .find(|val| !val.is_empty())
+    }
 }
 
 impl ProviderConfig for GlobalEnvConfig {
     const METADATA_NAME: &str = "Global environment variable(s)";
 
+    #[expect(clippy::similar_names)]
     fn data(&self) -> figment::Result<Map<Profile, Dict>> {
         let no_color = &self.no_color;
         let no_color = no_color.as_deref();
@@ -56,7 +100,27 @@ fn data(&self) -> figment::Result<Map<Profile, Dict>> {
         };
         let color_choice = color_choice.map(|val| val.to_string()).map(Value::from);
 
-        let dict = [color_choice.map(|val| ("color_choice", val))];
+        let docker_config = self.docker_config.clone().map(Value::from);
+
+        let netrc = self.netrc.clone().map(Value::from);
+
+        let ssl_cert_file = self.ssl_cert_file.clone().map(Value::from);
+
+        let http_proxy = self.http_proxy.clone().map(Value::from);
+
+        let https_proxy = self.https_proxy.clone().map(Value::from);
+
+        let no_proxy = self.no_proxy.clone().map(Value::from);
+
+        let dict = [
+            color_choice.map(|val| ("color_choice", val)),
+            docker_config.map(|val| ("docker_config_dir", val)),
+            netrc.map(|val| ("netrc_file", val)),
+            ssl_cert_file.map(|val| ("ca_bundle_file", val)),
+            http_proxy.map(|val| ("http_proxy", val)),
+            https_proxy.map(|val| ("https_proxy", val)),
+            no_proxy.map(|val| ("no_proxy", val)),
+        ];
         let dict = dict
             .into_iter()
             .flatten()
@@ -7,7 +7,7 @@
 };
 use indoc::formatdoc;
 use serde::{Deserialize, Deserializer};
-use serde_with::{DeserializeAs, serde_as};
+use serde_with::{DeserializeAs, NoneAsEmptyString, serde_as};
 
 use super::{EnvConfig, ProviderConfig};
 
@@ -28,6 +28,34 @@ pub(super) struct HomebrewEnvConfig {
 
     #[serde_as(as = "Option<HomebrewBoolFromStr>")]
     color: Option<bool>,
+
+    #[serde(default)]
+    #[serde_as(as = "NoneAsEmptyString")]
+    api_domain: Option<String>,
+
+    #[serde(default)]
+    #[serde_as(as = "NoneAsEmptyString")]
+    bottle_domain: Option<String>,
+
+    #[serde(default)]
+    #[serde_as(as = "NoneAsEmptyString")]
+    artifact_domain: Option<String>,
+
+    #[serde(default)]
+    #[serde_as(as = "NoneAsEmptyString")]
+    github_packages_user: Option<String>,
+
+    #[serde(default)]
+    #[serde_as(as = "NoneAsEmptyString")]
+    github_packages_token: Option<String>,
+
+    #[serde(default)]
+    #[serde_as(as = "NoneAsEmptyString")]
+    docker_registry_token: Option<String>,
+
+    curl_retries: Option<u32>,
+
+    cleanup_max_age_days: Option<u64>,
 }
 
 impl EnvConfig for HomebrewEnvConfig {
@@ -97,9 +125,33 @@ fn data(&self) -> figment::Result<Map<Profile, Dict>> {
         };
         let color_choice = color_choice.map(|val| val.to_string()).map(Value::from);
 
+        let api_domain = self.api_domain.clone().map(Value::from);
+
+        let bottle_domain = self.bottle_domain.clone().map(Value::from);
+
+        let artifact_domain = self.artifact_domain.clone().map(Value::from);
+
+        let github_packages_user = self.github_packages_user.clone().map(Value::from);
+
+        let github_packages_token = self.github_packages_token.clone().map(Value::from);
+
+        let docker_registry_token = self.docker_registry_token.clone().map(Value::from);
+
+        let retries = self.curl_retries.map(Value::from);
+
+        let cleanup_max_age_days = self.cleanup_max_age_days.map(Value::from);
+
         let dict = [
             verbosity_filter.map(|val| ("verbosity_filter", val)),
             color_choice.map(|val| ("color_choice", val)),
+            api_domain.map(|val| ("api_domain", val)),
+            bottle_domain.map(|val| ("bottle_domain", val)),
+            artifact_domain.map(|val| ("artifact_domain", val)),
+            github_packages_user.map(|val| ("github_packages_user", val)),
+            github_packages_token.map(|val| ("github_packages_token", val)),
+            docker_registry_token.map(|val| ("docker_registry_token", val)),
+            retries.map(|val| ("retries", val)),
+            cleanup_max_age_days.map(|val| ("cleanup_max_age_days", val)),
         ];
         let dict = dict
             .into_iter()
@@ -113,7 +165,7 @@ fn data(&self) -> figment::Result<Map<Profile, Dict>> {
     }
 }
 
-struct HomebrewBoolFromStr;
+pub(super) struct HomebrewBoolFromStr;
 
 impl<'de> DeserializeAs<'de, bool> for HomebrewBoolFromStr {
     fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
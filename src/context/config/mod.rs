@@ -1,8 +1,18 @@
 mod cli;
+mod file;
 mod global_env;
 pub(super) mod homebrew_env;
 mod neobrew_env;
 
+use std::{
+    collections::BTreeMap,
+    env,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::{Context as _, anyhow};
 use clap::{ArgMatches, ColorChoice};
 use clap_verbosity_flag::{Verbosity, VerbosityFilter};
 use figment::{
@@ -11,18 +21,29 @@
     Profile,
     Provider,
     providers::Serialized,
-    value::{Dict, Map},
+    value::{Dict, Map, Value},
 };
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
-use serde_with::{DisplayFromStr, serde_as};
+use serde_with::{DisplayFromStr, DurationSeconds, serde_as};
 
 use self::{
     cli::CliConfig,
+    file::FileConfig,
     global_env::GlobalEnvConfig,
     homebrew_env::HomebrewEnvConfig,
     neobrew_env::NeobrewEnvConfig,
 };
 
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+
+const DEFAULT_RETRIES: u32 = 5;
+
+const DEFAULT_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+const DEFAULT_CLEANUP_MAX_AGE_DAYS: u64 = 120;
+
+const PROJECT_CONFIG_FILE_NAME: &str = ".nbrew.toml";
+
 #[serde_as]
 #[derive(Serialize, Deserialize)]
 pub struct Config {
@@ -30,6 +51,63 @@ pub struct Config {
 
     #[serde_as(as = "DisplayFromStr")]
     pub(crate) color_choice: ColorChoice,
+
+    pub(crate) offline: bool,
+
+    pub(crate) verify_cache: bool,
+
+    pub(crate) allow_unsigned_api: bool,
+
+    pub(crate) api_domain: Option<String>,
+    pub(crate) bottle_domain: Option<String>,
+    pub(crate) artifact_domain: Option<String>,
+
+    #[serde(skip_serializing)]
+    pub(crate) github_packages_user: Option<String>,
+    #[serde(skip_serializing)]
+    pub(crate) github_packages_token: Option<String>,
+    #[serde(skip_serializing)]
+    pub(crate) docker_registry_token: Option<String>,
+
+    pub(crate) docker_config_dir: Option<PathBuf>,
+    pub(crate) netrc_file: Option<PathBuf>,
+
+    pub(crate) http_proxy: Option<String>,
+    pub(crate) https_proxy: Option<String>,
+    pub(crate) no_proxy: Option<String>,
+
+    pub(crate) ca_bundle_file: Option<PathBuf>,
+
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub(crate) connect_timeout: Duration,
+    #[serde_as(as = "Option<DurationSeconds<u64>>")]
+    pub(crate) read_timeout: Option<Duration>,
+
+    pub(crate) retries: u32,
+
+    pub(crate) user_agent: String,
+
+    pub(crate) concurrency_limit: Option<usize>,
+
+    pub(crate) install: InstallConfig,
+
+    pub(crate) pins: BTreeMap<String, String>,
+
+    pub(crate) cleanup_max_age_days: u64,
+    pub(crate) cache_size_limit: Option<u64>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub(crate) struct InstallConfig {
+    pub(crate) include_build: bool,
+    pub(crate) include_test: bool,
+    pub(crate) with_optional: bool,
+}
+
+pub(crate) struct ConfigEntry {
+    pub(crate) key: String,
+    pub(crate) value: String,
+    pub(crate) origin: Option<String>,
 }
 
 impl Default for Config {
@@ -38,22 +116,71 @@ fn default() -> Self {
             verbosity_filter: <Verbosity>::default().filter(),
 
             color_choice: ColorChoice::default(),
+
+            offline: false,
+
+            verify_cache: false,
+
+            allow_unsigned_api: false,
+
+            api_domain: None,
+            bottle_domain: None,
+            artifact_domain: None,
+
+            github_packages_user: None,
+            github_packages_token: None,
+            docker_registry_token: None,
+
+            docker_config_dir: None,
+            netrc_file: None,
+
+            http_proxy: None,
+            https_proxy: None,
+            no_proxy: None,
+
+            ca_bundle_file: None,
+
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            read_timeout: None,
+
+            retries: DEFAULT_RETRIES,
+
+            user_agent: DEFAULT_USER_AGENT.to_owned(),
+
+            concurrency_limit: None,
+
+            install: InstallConfig::default(),
+
+            pins: BTreeMap::new(),
+
+            cleanup_max_age_days: DEFAULT_CLEANUP_MAX_AGE_DAYS,
+            cache_size_limit: None,
         }
     }
 }
 
 impl Config {
-    pub(super) fn load(matches: &ArgMatches) -> anyhow::Result<Self> {
-        let this = Self::figment(matches)?;
-        let this: Self = this.extract()?;
+    pub(super) fn load(figment: &Figment) -> anyhow::Result<Self> {
+        let this: Self = figment.extract()?;
 
         Ok(this)
     }
 
-    fn figment(matches: &ArgMatches) -> anyhow::Result<Figment> {
+    pub(super) fn figment(
+        matches: &ArgMatches,
+        user_config_file_path: PathBuf,
+    ) -> anyhow::Result<Figment> {
         let default_config_provider = Self::default();
         let default_config_provider = default_config_provider.into_provider();
 
+        let user_file_config_provider =
+            FileConfig::from_file(FileConfig::USER_METADATA_NAME, user_config_file_path)?;
+        let user_file_config_provider = user_file_config_provider.into_provider();
+
+        let project_file_config_provider =
+            FileConfig::from_file(FileConfig::PROJECT_METADATA_NAME, Self::project_file()?)?;
+        let project_file_config_provider = project_file_config_provider.into_provider();
+
         let global_env_config_provider = GlobalEnvConfig::from_env()?;
         let global_env_config_provider = global_env_config_provider.into_provider();
 
@@ -68,6 +195,8 @@ fn figment(matches: &ArgMatches) -> anyhow::Result<Figment> {
 
         let figment = Figment::new()
             .merge(default_config_provider)
+            .merge(user_file_config_provider)
+            .merge(project_file_config_provider)
             .merge(global_env_config_provider)
             .merge(homebrew_env_config_provider)
             .merge(neobrew_env_config_provider)
@@ -76,8 +205,152 @@ fn figment(matches: &ArgMatches) -> anyhow::Result<Figment> {
         Ok(figment)
     }
 
-    fn into_provider(self) -> Serialized<Self> {
-        Serialized::defaults(self)
+    pub(crate) fn project_file() -> anyhow::Result<PathBuf> {
+        let current_dir_path = env::current_dir()?;
+
+        let project_file_path = current_dir_path
+            .ancestors()
+            .map(|dir_path| dir_path.join(PROJECT_CONFIG_FILE_NAME))
+            .find(|file_path| file_path.is_file())
+            .unwrap_or_else(|| current_dir_path.join(PROJECT_CONFIG_FILE_NAME));
+
+        Ok(project_file_path)
+    }
+
+    pub(crate) fn entries(&self, figment: &Figment) -> anyhow::Result<Vec<ConfigEntry>> {
+        let keys = match Value::serialize(self)? {
+            Value::Dict(_, dict) => dict
+                .into_iter()
+                .flat_map(|(key, value)| match value {
+                    Value::Dict(_, dict) => dict
+                        .into_keys()
+                        .map(|sub_key| format!("{key}.{sub_key}"))
+                        .collect(),
+                    Value::Empty(..) => Vec::new(),
+                    _ => vec![key],
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        let mut entries = Vec::new();
+
+        for key in keys {
+            let Some(value) = Self::find_value(figment, &key) else {
+                continue;
+            };
+
+            let origin = figment.get_metadata(value.tag()).map(|metadata| {
+                let name = &metadata.name;
+
+                match &metadata.source {
+                    Some(source) => format!("{name} ({source})"),
+                    None => name.clone().into_owned(),
+                }
+            });
+
+            let value = serde_json::to_string(&value)?;
+
+            let entry = ConfigEntry {
+                key,
+                value,
+                origin,
+            };
+
+            entries.push(entry);
+        }
+
+        Ok(entries)
+    }
+
+    fn find_value(figment: &Figment, key: &str) -> Option<Value> {
+        let (key, sub_key) = match key.split_once('.') {
+            Some((key, sub_key)) => (key, Some(sub_key)),
+            None => (key, None),
+        };
+
+        let value = figment.find_value(key).ok()?;
+
+        let value = match (value, sub_key) {
+            (value, None) => value,
+            (Value::Dict(_, mut dict), Some(sub_key)) => dict.remove(sub_key)?,
+            (_, Some(_)) => return None,
+        };
+
+        if matches!(value, Value::Empty(..)) {
+            return None;
+        }
+
+        Some(value)
+    }
+
+    pub(crate) fn set(config_file_path: &Path, key: &str, value: &str) -> anyhow::Result<()> {
+        let (key, sub_key) = match key.split_once('.') {
+            Some((key, sub_key)) => (key, Some(sub_key)),
+            None => (key, None),
+        };
+
+        let default_config = Value::serialize(Self::default())?;
+
+        let default_value = default_config.find_ref(key);
+
+        let is_known_key = match (default_value, sub_key) {
+            (Some(Value::Dict(_, dict)), Some(sub_key)) => {
+                key == "pins" || dict.contains_key(sub_key)
+            },
+            (Some(Value::Dict(..)), None) | (None, _) | (Some(_), Some(_)) => false,
+            (Some(_), None) => true,
+        };
+
+        let full_key = sub_key.map_or_else(|| key.to_owned(), |sub_key| format!("{key}.{sub_key}"));
+
+        if !is_known_key {
+            let err = anyhow!(r#"Config "{full_key}" is unknown"#);
+
+            return Err(err);
+        }
+
+        let value = toml::from_str::<toml::Table>(&format!("value = {value}"))
+            .ok()
+            .and_then(|mut table| table.remove("value"))
+            .unwrap_or_else(|| toml::Value::String(value.to_owned()));
+
+        let mut table = file::load_table(config_file_path)?;
+
+        match sub_key {
+            Some(sub_key) => {
+                let sub_table = table
+                    .entry(key)
+                    .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+
+                let toml::Value::Table(sub_table) = sub_table else {
+                    let err = anyhow!(r#"Config "{key}" is not a table"#);
+
+                    return Err(err);
+                };
+
+                sub_table.insert(sub_key.to_owned(), value);
+            },
+            None => {
+                table.insert(key.to_owned(), value);
+            },
+        }
+
+        Figment::new()
+            .merge(Self::default().into_provider())
+            .merge(Serialized::defaults(&table))
+            .extract::<Self>()
+            .with_context(|| format!(r#"Config "{full_key}" has an invalid value"#))?;
+
+        let content = toml::to_string_pretty(&table)?;
+
+        if let Some(config_dir_path) = config_file_path.parent() {
+            fs::create_dir_all(config_dir_path)?;
+        }
+
+        fs::write(config_file_path, content)?;
+
+        Ok(())
     }
 
     pub fn verbosity_filter(&self) -> &VerbosityFilter {
@@ -85,6 +358,16 @@ pub fn verbosity_filter(&self) -> &VerbosityFilter {
     }
 }
 
+impl ProviderConfig for Config {
+    const METADATA_NAME: &str = "Default value(s)";
+
+    fn data(&self) -> figment::Result<Map<Profile, Dict>> {
+        let serialized = Serialized::defaults(self);
+
+        serialized.data()
+    }
+}
+
 struct FigmentProvider<ProviderConf>(ProviderConf);
 
 impl<ProviderConf: ProviderConfig> Provider for FigmentProvider<ProviderConf> {
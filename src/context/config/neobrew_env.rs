@@ -5,9 +5,9 @@
     value::{Dict, Map, Value},
 };
 use serde::Deserialize;
-use serde_with::{DisplayFromStr, serde_as};
+use serde_with::{DisplayFromStr, NoneAsEmptyString, serde_as};
 
-use super::{EnvConfig, ProviderConfig};
+use super::{EnvConfig, ProviderConfig, homebrew_env::HomebrewBoolFromStr};
 
 #[serde_as]
 #[derive(Deserialize)]
@@ -16,6 +16,31 @@ pub(super) struct NeobrewEnvConfig {
 
     #[serde_as(as = "Option<DisplayFromStr>")]
     color_choice: Option<ColorChoice>,
+
+    #[serde_as(as = "Option<HomebrewBoolFromStr>")]
+    offline: Option<bool>,
+
+    #[serde_as(as = "Option<HomebrewBoolFromStr>")]
+    verify_cache: Option<bool>,
+
+    #[serde_as(as = "Option<HomebrewBoolFromStr>")]
+    allow_unsigned_api: Option<bool>,
+
+    #[serde(default)]
+    #[serde_as(as = "NoneAsEmptyString")]
+    ca_bundle: Option<String>,
+
+    connect_timeout: Option<u64>,
+
+    read_timeout: Option<u64>,
+
+    retries: Option<u32>,
+
+    #[serde(default)]
+    #[serde_as(as = "NoneAsEmptyString")]
+    user_agent: Option<String>,
+
+    cache_size_limit: Option<u64>,
 }
 
 impl EnvConfig for NeobrewEnvConfig {
@@ -33,9 +58,36 @@ fn data(&self) -> figment::Result<Map<Profile, Dict>> {
             .map(|val| val.to_string())
             .map(Value::from);
 
+        let offline = self.offline.map(Value::from);
+
+        let verify_cache = self.verify_cache.map(Value::from);
+
+        let allow_unsigned_api = self.allow_unsigned_api.map(Value::from);
+
+        let ca_bundle = self.ca_bundle.clone().map(Value::from);
+
+        let connect_timeout = self.connect_timeout.map(Value::from);
+
+        let read_timeout = self.read_timeout.map(Value::from);
+
+        let retries = self.retries.map(Value::from);
+
+        let user_agent = self.user_agent.clone().map(Value::from);
+
+        let cache_size_limit = self.cache_size_limit.map(Value::from);
+
         let dict = [
             verbosity_filter.map(|val| ("verbosity_filter", val)),
             color_choice.map(|val| ("color_choice", val)),
+            offline.map(|val| ("offline", val)),
+            verify_cache.map(|val| ("verify_cache", val)),
+            allow_unsigned_api.map(|val| ("allow_unsigned_api", val)),
+            ca_bundle.map(|val| ("ca_bundle_file", val)),
+            connect_timeout.map(|val| ("connect_timeout", val)),
+            read_timeout.map(|val| ("read_timeout", val)),
+            retries.map(|val| ("retries", val)),
+            user_agent.map(|val| ("user_agent", val)),
+            cache_size_limit.map(|val| ("cache_size_limit", val)),
         ];
         let dict = dict
             .into_iter()
@@ -0,0 +1,442 @@
+use std::{collections::HashMap, path::PathBuf, process::Stdio};
+
+use anyhow::Context as _;
+use base64::{Engine as _, engine::general_purpose};
+use oci_client::secrets::RegistryAuth;
+use reqwest::RequestBuilder;
+use serde::Deserialize;
+use tokio::{
+    fs,
+    io::{self, AsyncWriteExt as _},
+    process::Command,
+    sync::Mutex,
+};
+use url::Url;
+
+use super::config::Config;
+
+const GITHUB_PACKAGES_HOST: &str = "ghcr.io";
+
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+enum Credential {
+    Basic {
+        username: String,
+        password: String,
+    },
+    Bearer {
+        token: String,
+    },
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DockerConfig {
+    #[serde(default)]
+    auths: HashMap<String, DockerAuth>,
+
+    #[serde(default)]
+    cred_helpers: HashMap<String, String>,
+
+    creds_store: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct DockerAuth {
+    auth: Option<String>,
+
+    #[serde(rename = "identitytoken")]
+    identity_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct DockerHelperCredential {
+    username: String,
+    secret: String,
+}
+
+pub(crate) struct Credentials {
+    github_packages_user: Option<String>,
+    github_packages_token: Option<String>,
+    docker_registry_token: Option<String>,
+
+    docker_config_file_path: Option<PathBuf>,
+    netrc_file_path: Option<PathBuf>,
+
+    mirror_hosts: Vec<String>,
+
+    cache: Mutex<HashMap<String, Option<Credential>>>,
+}
+
+impl Credentials {
+    pub(super) fn new(config: &Config) -> Self {
+        let home_dir_path = etcetera::home_dir().ok();
+
+        let docker_config_file_path = config
+            .docker_config_dir
+            .clone()
+            .or_else(|| home_dir_path.as_ref().map(|path| path.join(".docker")))
+            .map(|path| path.join("config.json"));
+
+        let netrc_file_path = config
+            .netrc_file
+            .clone()
+            .or_else(|| home_dir_path.as_ref().map(|path| path.join(".netrc")));
+
+        let mirror_hosts = [
+            &config.api_domain,
+            &config.bottle_domain,
+            &config.artifact_domain,
+        ]
+        .into_iter()
+        .flatten()
+        .filter_map(|domain| Url::parse(domain).ok())
+        .filter_map(|url| url.host_str().map(ToOwned::to_owned))
+        .collect();
+
+        Self {
+            github_packages_user: config.github_packages_user.clone(),
+            github_packages_token: config.github_packages_token.clone(),
+            docker_registry_token: config.docker_registry_token.clone(),
+
+            docker_config_file_path,
+            netrc_file_path,
+
+            mirror_hosts,
+
+            cache: Mutex::default(),
+        }
+    }
+
+    pub(crate) async fn registry_auth(&self, registry: &str) -> anyhow::Result<RegistryAuth> {
+        let registry_auth = match self.lookup(registry).await? {
+            Some(Credential::Basic {
+                username,
+                password,
+            }) => RegistryAuth::Basic(username, password),
+            Some(Credential::Bearer {
+                token,
+            }) => RegistryAuth::Bearer(token),
+            None => RegistryAuth::Anonymous,
+        };
+
+        Ok(registry_auth)
+    }
+
+    pub(crate) async fn authorize(
+        &self,
+        req: RequestBuilder,
+        url: &str,
+    ) -> anyhow::Result<RequestBuilder> {
+        let url = Url::parse(url)?;
+
+        let Some(host) = url.host_str() else {
+            return Ok(req);
+        };
+
+        let req = match self.lookup(host).await? {
+            Some(Credential::Basic {
+                username,
+                password,
+            }) => req.basic_auth(username, Some(password)),
+            Some(Credential::Bearer {
+                token,
+            }) => req.bearer_auth(token),
+            None => req,
+        };
+
+        Ok(req)
+    }
+
+    async fn lookup(&self, host: &str) -> anyhow::Result<Option<Credential>> {
+        let mut cache = self.cache.lock().await;
+
+        if let Some(credential) = cache.get(host) {
+            return Ok(credential.clone());
+        }
+
+        let credential = match self.env_credential(host) {
+            Some(credential) => Some(credential),
+            None => match self.docker_config_credential(host).await? {
+                Some(credential) => Some(credential),
+                None => self.netrc_credential(host).await?,
+            },
+        };
+
+        cache.insert(host.to_owned(), credential.clone());
+
+        Ok(credential)
+    }
+
+    fn env_credential(&self, host: &str) -> Option<Credential> {
+        if host != GITHUB_PACKAGES_HOST {
+            return None;
+        }
+
+        if let (Some(username), Some(password)) =
+            (&self.github_packages_user, &self.github_packages_token)
+        {
+            let credential = Credential::Basic {
+                username: username.clone(),
+                password: password.clone(),
+            };
+
+            return Some(credential);
+        }
+
+        let token = self.docker_registry_token.clone()?;
+
+        Some(Credential::Bearer {
+            token,
+        })
+    }
+
+    async fn docker_config_credential(&self, host: &str) -> anyhow::Result<Option<Credential>> {
+        let Some(docker_config_file_path) = &self.docker_config_file_path else {
+            return Ok(None);
+        };
+
+        let content = match fs::read(docker_config_file_path).await {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        let docker_config: DockerConfig = serde_json::from_slice(&content).with_context(|| {
+            let docker_config_file_path = docker_config_file_path.display();
+
+            format!(r#"Docker config "{docker_config_file_path}" is invalid"#)
+        })?;
+
+        let helper = docker_config
+            .cred_helpers
+            .get(host)
+            .or(docker_config.creds_store.as_ref());
+
+        if let Some(helper) = helper
+            && let Some(credential) = Self::docker_helper_credential(helper, host).await?
+        {
+            return Ok(Some(credential));
+        }
+
+        let docker_auth = docker_config
+            .auths
+            .into_iter()
+            .find(|(auth_host, _)| docker_auth_host(auth_host) == host)
+            .map(|(_, docker_auth)| docker_auth);
+
+        let Some(docker_auth) = docker_auth else {
+            return Ok(None);
+        };
+
+        if let Some(auth) = docker_auth.auth {
+            let auth = general_purpose::STANDARD.decode(auth)?;
+            let auth = String::from_utf8(auth)?;
+
+            if let Some((username, password)) = auth.split_once(':') {
+                let credential = Credential::Basic {
+                    username: username.to_owned(),
+                    password: password.to_owned(),
+                };
+
+                return Ok(Some(credential));
+            }
+        }
+
+        let credential = docker_auth.identity_token.map(|token| Credential::Bearer {
+            token,
+        });
+
+        Ok(credential)
+    }
+
+    async fn docker_helper_credential(
+        helper: &str,
+        host: &str,
+    ) -> anyhow::Result<Option<Credential>> {
+        let mut docker_credential = Command::new(format!("docker-credential-{helper}"));
+
+        docker_credential.arg("get");
+
+        docker_credential
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        let mut docker_credential = match docker_credential.spawn() {
+            Ok(docker_credential) => docker_credential,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        if let Some(mut stdin) = docker_credential.stdin.take() {
+            stdin.write_all(host.as_bytes()).await?;
+        }
+
+        let docker_credential = docker_credential.wait_with_output().await?;
+
+        if !docker_credential.status.success() {
+            return Ok(None);
+        }
+
+        let docker_helper_credential: DockerHelperCredential =
+            serde_json::from_slice(&docker_credential.stdout)?;
+
+        let credential = Credential::Basic {
+            username: docker_helper_credential.username,
+            password: docker_helper_credential.secret,
+        };
+
+        Ok(Some(credential))
+    }
+
+    async fn netrc_credential(&self, host: &str) -> anyhow::Result<Option<Credential>> {
+        let Some(netrc_file_path) = &self.netrc_file_path else {
+            return Ok(None);
+        };
+
+        let content = match fs::read_to_string(netrc_file_path).await {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        let allows_default = self
+            .mirror_hosts
+            .iter()
+            .any(|mirror_host| mirror_host == host);
+
+        let credential = netrc_credential(&content, host, allows_default);
+
+        Ok(credential)
+    }
+}
+
+fn docker_auth_host(auth_host: &str) -> &str {
+    let auth_host = auth_host
+        .strip_prefix("https://")
+        .or_else(|| auth_host.strip_prefix("http://"))
+        .unwrap_or(auth_host);
+
+    auth_host
+        .split_once('/')
+        .map_or(auth_host, |(auth_host, _)| auth_host)
+}
+
+// The `default` entry would otherwise hand its password to every host that is asked about, so
+// it only applies to the configured mirrors.
+fn netrc_credential(content: &str, host: &str, allows_default: bool) -> Option<Credential> {
+    let mut tokens = content.split_whitespace();
+
+    let mut machine = None;
+    let mut login = None;
+    let mut password = None;
+
+    while let Some(token) = tokens.next() {
+        match token {
+            "machine" | "default" => {
+                if machine == Some(host)
+                    && let Some(password) = password
+                {
+                    return Some(netrc_entry_credential(login, password));
+                }
+
+                machine = if token == "default" {
+                    allows_default.then_some(host)
+                } else {
+                    tokens.next()
+                };
+
+                login = None;
+                password = None;
+            },
+            "login" => login = tokens.next(),
+            "password" => password = tokens.next(),
+            _ => {},
+        }
+    }
+
+    if machine == Some(host)
+        && let Some(password) = password
+    {
+        return Some(netrc_entry_credential(login, password));
+    }
+
+    None
+}
+
+fn netrc_entry_credential(login: Option<&str>, password: &str) -> Credential {
+    let username = login.unwrap_or_default();
+
+    Credential::Basic {
+        username: username.to_owned(),
+        password: password.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NETRC: &str = "
+        machine api.example.com
+            login alice
+            password secret
+        machine bottles.example.com password token
+        default login anonymous password guest
+    ";
+
+    fn basic(username: &str, password: &str) -> Credential {
+        Credential::Basic {
+            username: username.to_owned(),
+            password: password.to_owned(),
+        }
+    }
+
+    #[test]
+    fn matches_machine_entry() {
+        let credential = netrc_credential(NETRC, "api.example.com", false);
+
+        assert_eq!(credential, Some(basic("alice", "secret")));
+    }
+
+    #[test]
+    fn defaults_missing_login_to_empty_username() {
+        let credential = netrc_credential(NETRC, "bottles.example.com", false);
+
+        assert_eq!(credential, Some(basic("", "token")));
+    }
+
+    #[test]
+    fn ignores_default_for_other_hosts() {
+        let credential = netrc_credential(NETRC, "evil.example.com", false);
+
+        assert_eq!(credential, None);
+    }
+
+    #[test]
+    fn uses_default_for_mirror_hosts() {
+        let credential = netrc_credential(NETRC, "mirror.example.com", true);
+
+        assert_eq!(credential, Some(basic("anonymous", "guest")));
+    }
+
+    #[test]
+    fn prefers_machine_entry_over_default() {
+        let credential = netrc_credential(NETRC, "api.example.com", true);
+
+        assert_eq!(credential, Some(basic("alice", "secret")));
+    }
+
+    #[test]
+    fn skips_machine_entry_without_password() {
+        let credential = netrc_credential(
+            "machine api.example.com login alice",
+            "api.example.com",
+            false,
+        );
+
+        assert_eq!(credential, None);
+    }
+}
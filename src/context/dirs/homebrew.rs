@@ -131,7 +131,19 @@ pub(crate) fn linked_keg_prefix_link(&self, id: &str) -> PathBuf {
         linked_keg_dir.join(id)
     }
 
-    fn caskroom_dir(&self) -> PathBuf {
+    fn pinned_keg_dir(&self) -> PathBuf {
+        let prefix_dir = self.prefix_dir();
+
+        prefix_dir.join("var/homebrew/pinned")
+    }
+
+    pub(crate) fn pinned_keg_prefix_link(&self, id: &str) -> PathBuf {
+        let pinned_keg_dir = self.pinned_keg_dir();
+
+        pinned_keg_dir.join(id)
+    }
+
+    pub(crate) fn caskroom_dir(&self) -> PathBuf {
         let prefix_dir = self.prefix_dir();
 
         prefix_dir.join("Caskroom")
@@ -149,6 +161,58 @@ pub(crate) fn staged_dir(&self, id: &str, version: &str) -> PathBuf {
         cask_dir.join(version)
     }
 
+    pub(crate) fn metadata_dir(&self, id: &str) -> PathBuf {
+        let cask_dir = self.cask_dir(id);
+
+        cask_dir.join(".metadata")
+    }
+
+    fn api_dir(&self) -> PathBuf {
+        let cache_dir = self.cache_dir();
+
+        cache_dir.join("api")
+    }
+
+    pub(crate) fn formula_jws_json_file(&self) -> PathBuf {
+        let api_dir = self.api_dir();
+
+        api_dir.join("formula.jws.json")
+    }
+
+    pub(crate) fn cask_jws_json_file(&self) -> PathBuf {
+        let api_dir = self.api_dir();
+
+        api_dir.join("cask.jws.json")
+    }
+
+    pub(crate) fn formula_tap_migrations_jws_json_file(&self) -> PathBuf {
+        let api_dir = self.api_dir();
+
+        api_dir.join("formula_tap_migrations.jws.json")
+    }
+
+    pub(crate) fn cask_tap_migrations_jws_json_file(&self) -> PathBuf {
+        let api_dir = self.api_dir();
+
+        api_dir.join("cask_tap_migrations.jws.json")
+    }
+
+    pub(crate) fn formula_json_file(&self, id: &str) -> PathBuf {
+        let api_dir = self.api_dir();
+
+        let file_name = format!("{id}.json");
+
+        api_dir.join("formula").join(file_name)
+    }
+
+    pub(crate) fn cask_json_file(&self, id: &str) -> PathBuf {
+        let api_dir = self.api_dir();
+
+        let file_name = format!("{id}.json");
+
+        api_dir.join("cask").join(file_name)
+    }
+
     pub(crate) fn repository_dir(&self) -> PathBuf {
         let prefix_dir = self.prefix_dir();
 
@@ -167,6 +231,20 @@ pub(crate) fn library_dir(&self) -> PathBuf {
         repository_dir.join("Library")
     }
 
+    fn taps_dir(&self) -> PathBuf {
+        let library_dir = self.library_dir();
+
+        library_dir.join("Taps")
+    }
+
+    pub(crate) fn tap_dir(&self, user: &str, repo: &str) -> PathBuf {
+        let taps_dir = self.taps_dir();
+
+        let repo = format!("homebrew-{repo}");
+
+        taps_dir.join(user).join(repo)
+    }
+
     pub(crate) fn bin_dir(&self) -> PathBuf {
         let prefix_dir = self.prefix_dir();
 
@@ -45,4 +45,30 @@ fn config_dir(&self) -> PathBuf {
 
         config_dir.join(app_name)
     }
+
+    pub(crate) fn config_file(&self) -> PathBuf {
+        let config_dir = self.config_dir();
+
+        config_dir.join("config.toml")
+    }
+
+    pub(crate) fn journal_dir(&self) -> PathBuf {
+        let data_dir = self.data_dir();
+
+        data_dir.join("journal")
+    }
+
+    pub(crate) fn store_dir(&self) -> PathBuf {
+        let cache_dir = self.cache_dir();
+
+        cache_dir.join("store")
+    }
+
+    pub(crate) fn etag_file(&self, file_name: &str) -> PathBuf {
+        let cache_dir = self.cache_dir();
+
+        let file_name = format!("{file_name}.etag");
+
+        cache_dir.join("etag").join(file_name)
+    }
 }
@@ -0,0 +1,240 @@
+use anyhow::Context as _;
+use reqwest::{RequestBuilder, Response, StatusCode};
+use url::form_urlencoded;
+
+use super::{config::Config, credentials::Credentials};
+
+const DEFAULT_API_DOMAIN: &str = "https://formulae.brew.sh/api";
+
+const DEFAULT_BOTTLE_DOMAIN: &str = "https://ghcr.io/v2/homebrew/core";
+
+const OCI_BOTTLE_DOMAIN_PREFIX: &str = "https://ghcr.io/v2/";
+
+pub(crate) struct Mirrors {
+    api: Option<String>,
+    bottle: Option<String>,
+    artifact: Option<String>,
+}
+
+impl Mirrors {
+    pub(super) fn new(config: &Config) -> Self {
+        let trim = |domain: &Option<String>| {
+            domain
+                .as_deref()
+                .map(|domain| domain.trim_end_matches('/'))
+                .filter(|domain| !domain.is_empty())
+                .map(ToOwned::to_owned)
+        };
+
+        Self {
+            api: trim(&config.api_domain),
+            bottle: trim(&config.bottle_domain),
+            artifact: trim(&config.artifact_domain),
+        }
+    }
+
+    pub(crate) fn api_urls(&self, url: &str) -> Vec<String> {
+        let mirror_url = self.api.as_deref().and_then(|api_domain| {
+            let url_postfix = url.strip_prefix(DEFAULT_API_DOMAIN)?;
+
+            Some(format!("{api_domain}{url_postfix}"))
+        });
+
+        dedup(mirror_url.into_iter().chain([url.to_owned()]))
+    }
+
+    pub(crate) fn bottle_urls(&self, url: &str, file_name: &str) -> Vec<String> {
+        let mirror_url = self.bottle.as_deref().and_then(|bottle_domain| {
+            let url_postfix = url.strip_prefix(DEFAULT_BOTTLE_DOMAIN)?;
+
+            if !bottle_domain.starts_with(OCI_BOTTLE_DOMAIN_PREFIX) {
+                let file_name = form_urlencoded::byte_serialize(file_name.as_bytes());
+                let file_name = file_name.collect::<String>();
+
+                return Some(format!("{bottle_domain}/{file_name}"));
+            }
+
+            Some(format!("{bottle_domain}{url_postfix}"))
+        });
+
+        let urls = mirror_url.into_iter().chain(self.artifact_urls(url));
+
+        dedup(urls)
+    }
+
+    pub(crate) fn artifact_urls(&self, url: &str) -> Vec<String> {
+        let mirror_url = self.artifact.as_deref().map(|artifact_domain| {
+            if let Some(url_postfix) = url.strip_prefix(OCI_BOTTLE_DOMAIN_PREFIX) {
+                return format!("{artifact_domain}/v2/{url_postfix}");
+            }
+
+            let url_postfix = ["https://", "http://", "ftp://"]
+                .into_iter()
+                .find_map(|scheme| url.strip_prefix(scheme))
+                .unwrap_or(url);
+
+            format!("{artifact_domain}/{url_postfix}")
+        });
+
+        dedup(mirror_url.into_iter().chain([url.to_owned()]))
+    }
+}
+
+pub(crate) async fn send_with_fallback(
+    credentials: &Credentials,
+    urls: &[String],
+    request: impl Fn(&str) -> RequestBuilder,
+) -> anyhow::Result<Response> {
+    let (origin_url, mirror_urls) = urls.split_last().context("No URL to request")?;
+
+    for mirror_url in mirror_urls {
+        let req = request(mirror_url);
+        let req = credentials.authorize(req, mirror_url).await?;
+
+        if let Ok(resp) = req.send().await
+            && (resp.status().is_success() || resp.status() == StatusCode::NOT_MODIFIED)
+        {
+            return Ok(resp);
+        }
+    }
+
+    let req = request(origin_url);
+    let req = credentials.authorize(req, origin_url).await?;
+
+    let resp = req.send().await?;
+
+    Ok(resp)
+}
+
+fn dedup(urls: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut deduped_urls = Vec::<String>::new();
+
+    for url in urls {
+        if !deduped_urls.contains(&url) {
+            deduped_urls.push(url);
+        }
+    }
+
+    deduped_urls
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOTTLE_URL: &str = "https://ghcr.io/v2/homebrew/core/wget/blobs/sha256:abc";
+
+    const BOTTLE_FILE_NAME: &str = "wget--1.25.0.arm64_sequoia.bottle.tar.gz";
+
+    fn mirrors(api: Option<&str>, bottle: Option<&str>, artifact: Option<&str>) -> Mirrors {
+        Mirrors {
+            api: api.map(ToOwned::to_owned),
+            bottle: bottle.map(ToOwned::to_owned),
+            artifact: artifact.map(ToOwned::to_owned),
+        }
+    }
+
+    #[test]
+    fn api_urls_without_mirror() {
+        let mirrors = mirrors(None, None, None);
+
+        let urls = mirrors.api_urls("https://formulae.brew.sh/api/formula.jws.json");
+
+        assert_eq!(urls, ["https://formulae.brew.sh/api/formula.jws.json"]);
+    }
+
+    #[test]
+    fn api_urls_rewrite_default_domain() {
+        let mirrors = mirrors(Some("https://mirror.example.com/api"), None, None);
+
+        let urls = mirrors.api_urls("https://formulae.brew.sh/api/formula/wget.json");
+
+        assert_eq!(
+            urls,
+            [
+                "https://mirror.example.com/api/formula/wget.json",
+                "https://formulae.brew.sh/api/formula/wget.json",
+            ]
+        );
+    }
+
+    #[test]
+    fn api_urls_keep_other_domains() {
+        let mirrors = mirrors(Some("https://mirror.example.com/api"), None, None);
+
+        let urls = mirrors.api_urls("https://example.com/formula.json");
+
+        assert_eq!(urls, ["https://example.com/formula.json"]);
+    }
+
+    #[test]
+    fn bottle_urls_rewrite_to_flat_mirror() {
+        let mirrors = mirrors(None, Some("https://mirror.example.com/bottles"), None);
+
+        let urls = mirrors.bottle_urls(BOTTLE_URL, BOTTLE_FILE_NAME);
+
+        assert_eq!(
+            urls,
+            [
+                "https://mirror.example.com/bottles/wget--1.25.0.arm64_sequoia.bottle.tar.gz",
+                BOTTLE_URL,
+            ]
+        );
+    }
+
+    #[test]
+    fn bottle_urls_rewrite_to_oci_mirror() {
+        let mirrors = mirrors(None, Some("https://ghcr.io/v2/example/core"), None);
+
+        let urls = mirrors.bottle_urls(BOTTLE_URL, BOTTLE_FILE_NAME);
+
+        assert_eq!(
+            urls,
+            [
+                "https://ghcr.io/v2/example/core/wget/blobs/sha256:abc",
+                BOTTLE_URL,
+            ]
+        );
+    }
+
+    #[test]
+    fn bottle_urls_keep_third_party_taps() {
+        let mirrors = mirrors(None, Some("https://mirror.example.com/bottles"), None);
+
+        let url = "https://ghcr.io/v2/example/tap/foo/blobs/sha256:abc";
+
+        let urls = mirrors.bottle_urls(url, "foo--1.0.arm64_sequoia.bottle.tar.gz");
+
+        assert_eq!(urls, [url]);
+    }
+
+    #[test]
+    fn bottle_urls_fall_back_to_artifact_mirror() {
+        let mirrors = mirrors(None, None, Some("https://artifacts.example.com"));
+
+        let urls = mirrors.bottle_urls(BOTTLE_URL, BOTTLE_FILE_NAME);
+
+        assert_eq!(
+            urls,
+            [
+                "https://artifacts.example.com/v2/homebrew/core/wget/blobs/sha256:abc",
+                BOTTLE_URL,
+            ]
+        );
+    }
+
+    #[test]
+    fn artifact_urls_strip_scheme() {
+        let mirrors = mirrors(None, None, Some("https://artifacts.example.com"));
+
+        let urls = mirrors.artifact_urls("https://example.com/app.dmg");
+
+        assert_eq!(
+            urls,
+            [
+                "https://artifacts.example.com/example.com/app.dmg",
+                "https://example.com/app.dmg",
+            ]
+        );
+    }
+}
@@ -1,18 +1,30 @@
-mod config;
+mod client;
+pub(crate) mod config;
+pub(crate) mod credentials;
 pub(crate) mod dirs;
+pub(crate) mod mirrors;
 
-use std::{num::NonZeroUsize, sync::LazyLock, thread};
+use std::{
+    num::NonZeroUsize,
+    sync::{Arc, LazyLock},
+    thread,
+};
 
 use clap::ArgMatches;
-use oci_client::{Client, client::ClientConfig};
+use figment::Figment;
+use oci_client::Client;
 use os_info::Info;
 use proc_exit::WithCodeResultExt as _;
 use tokio::sync::Semaphore;
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
 
 use self::{
     config::Config,
+    credentials::Credentials,
     dirs::{homebrew::HomebrewDirs, neobrew::NeobrewDirs},
+    mirrors::Mirrors,
 };
+use crate::{journal::Journal, store::Store};
 
 static INFO: LazyLock<Info> = LazyLock::new(os_info::get);
 
@@ -29,9 +41,6 @@
 static CONCURRENCY_LIMIT: LazyLock<usize> =
     LazyLock::new(|| AVAILABLE_PARALLELISM.min(MAX_CONCURRENCY));
 
-static CHANNEL_CAPACITY: LazyLock<usize> =
-    LazyLock::new(|| CONCURRENCY_LIMIT.saturating_mul(BUFFER_MULTIPLIER));
-
 #[expect(clippy::module_name_repetitions)]
 pub struct Context {
     pub(crate) info: &'static Info,
@@ -41,44 +50,92 @@ pub struct Context {
     pub(crate) channel_capacity: usize,
 
     pub(crate) config: Config,
+    pub(crate) config_figment: Figment,
 
     pub(crate) homebrew_dirs: HomebrewDirs,
     pub(crate) neobrew_dirs: NeobrewDirs,
 
+    pub(crate) mirrors: Mirrors,
+    pub(crate) credentials: Arc<Credentials>,
+
     pub(crate) client: reqwest::Client,
     pub(crate) oci_client: Client,
 
     pub(crate) semaphore: Semaphore,
+
+    pub(crate) tasks: TaskTracker,
+    pub(crate) cancellation: CancellationToken,
+
+    pub(crate) journal: Journal,
+
+    pub(crate) store: Store,
 }
 
 impl Context {
     #[expect(clippy::missing_errors_doc)]
     pub fn load(matches: &ArgMatches) -> Result<Self, proc_exit::Exit> {
-        let config = Config::load(matches);
-        let config = config.with_code(proc_exit::sysexits::CONFIG_ERR)?;
-
         let homebrew_dirs = HomebrewDirs::load();
         let homebrew_dirs = homebrew_dirs.with_code(proc_exit::sysexits::OS_ERR)?;
 
         let neobrew_dirs = NeobrewDirs::load();
         let neobrew_dirs = neobrew_dirs.with_code(proc_exit::sysexits::OS_ERR)?;
 
+        let config_figment = Config::figment(matches, neobrew_dirs.config_file());
+        let config_figment = config_figment.with_code(proc_exit::sysexits::CONFIG_ERR)?;
+
+        let config = Config::load(&config_figment);
+        let config = config.with_code(proc_exit::sysexits::CONFIG_ERR)?;
+
+        let concurrency_limit = config
+            .concurrency_limit
+            .map_or(*CONCURRENCY_LIMIT, |concurrency_limit| {
+                concurrency_limit.max(1)
+            });
+
+        let channel_capacity = concurrency_limit.saturating_mul(BUFFER_MULTIPLIER);
+
+        let client = client::build_client(&config);
+        let client = client.with_code(proc_exit::sysexits::CONFIG_ERR)?;
+
+        let oci_client = client::build_oci_client(&config);
+        let oci_client = oci_client.with_code(proc_exit::sysexits::CONFIG_ERR)?;
+
+        let mirrors = Mirrors::new(&config);
+
+        let credentials = Credentials::new(&config);
+        let credentials = Arc::new(credentials);
+
+        let journal = Journal::new(neobrew_dirs.journal_dir());
+
+        let store = Store::new(neobrew_dirs.store_dir());
+
         let this = Self {
             info: &INFO,
 
             available_parallelism: *AVAILABLE_PARALLELISM,
-            concurrency_limit: *CONCURRENCY_LIMIT,
-            channel_capacity: *CHANNEL_CAPACITY,
+            concurrency_limit,
+            channel_capacity,
 
             config,
+            config_figment,
 
             homebrew_dirs,
             neobrew_dirs,
 
-            client: reqwest::Client::new(),
-            oci_client: Client::new(ClientConfig::default()),
+            mirrors,
+            credentials,
+
+            client,
+            oci_client,
+
+            semaphore: Semaphore::new(concurrency_limit),
+
+            tasks: TaskTracker::new(),
+            cancellation: CancellationToken::new(),
+
+            journal,
 
-            semaphore: Semaphore::new(*CONCURRENCY_LIMIT),
+            store,
         };
 
         Ok(this)
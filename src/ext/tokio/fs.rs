@@ -1,15 +1,20 @@
 use std::path::Path;
 
+use tempfile::NamedTempFile;
 use tokio::{
-    fs::{File, OpenOptions},
-    io,
+    fs::{self, File, OpenOptions},
+    io::{self, AsyncWriteExt as _},
 };
 
+use super::super::std::path::PathExt as _;
+
 pub(crate) trait FileExt: Sized {
     async fn open_write(path: impl AsRef<Path>) -> io::Result<Self>;
 
     async fn open_read_write(path: impl AsRef<Path>) -> io::Result<Self>;
 
+    async fn open_append(path: impl AsRef<Path>) -> io::Result<Self>;
+
     async fn open_if_exists(path: impl AsRef<Path>) -> io::Result<Option<Self>>;
 
     async fn open_write_if_exists(path: impl AsRef<Path>) -> io::Result<Option<Self>>;
@@ -30,6 +35,16 @@ async fn open_read_write(path: impl AsRef<Path>) -> io::Result<Self> {
         Ok(file)
     }
 
+    async fn open_append(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await?;
+
+        Ok(file)
+    }
+
     async fn open_if_exists(path: impl AsRef<Path>) -> io::Result<Option<Self>> {
         let file = match Self::open(path).await {
             Ok(file) => file,
@@ -60,3 +75,39 @@ async fn open_read_write_if_exists(path: impl AsRef<Path>) -> io::Result<Option<
         Ok(Some(file))
     }
 }
+
+pub(crate) async fn write_atomically(
+    dest_file_path: &Path,
+    bytes: &[u8],
+    preserves_permissions: bool,
+) -> anyhow::Result<()> {
+    let permissions = if preserves_permissions {
+        let metadata = fs::symlink_metadata(dest_file_path).await?;
+
+        Some(metadata.permissions())
+    } else {
+        None
+    };
+
+    let dest_file_base_path = dest_file_path.base()?;
+
+    fs::create_dir_all(dest_file_base_path).await?;
+
+    let temp_file = NamedTempFile::new_in(dest_file_base_path)?;
+
+    let temp_file_path = temp_file.path();
+
+    let mut async_temp_file = File::open_write(temp_file_path).await?;
+
+    async_temp_file.write_all(bytes).await?;
+
+    if let Some(permissions) = permissions {
+        async_temp_file.set_permissions(permissions).await?;
+    }
+
+    async_temp_file.shutdown().await?;
+
+    temp_file.persist(dest_file_path)?;
+
+    Ok(())
+}
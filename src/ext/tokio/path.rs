@@ -20,6 +20,8 @@ pub(crate) trait PathExt {
 
     async fn add_permissions_mode(&self, mode: u32) -> io::Result<()>;
 
+    async fn is_exists_nofollow(&self) -> io::Result<bool>;
+
     async fn is_dir_exists_nofollow(&self) -> io::Result<bool>;
 
     async fn is_file_exists_nofollow(&self) -> io::Result<bool>;
@@ -83,6 +85,14 @@ async fn add_permissions_mode(&self, mode: u32) -> io::Result<()> {
         Ok(())
     }
 
+    async fn is_exists_nofollow(&self) -> io::Result<bool> {
+        match fs::symlink_metadata(self).await {
+            Ok(_) => Ok(true),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
     async fn is_dir_exists_nofollow(&self) -> io::Result<bool> {
         let metadata = match fs::symlink_metadata(self).await {
             Ok(metadata) => metadata,
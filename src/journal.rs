@@ -0,0 +1,594 @@
+use std::{
+    fs::File as StdFile,
+    path::{Path, PathBuf},
+    process,
+};
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    fs::{self, File, OpenOptions},
+    io::{self, AsyncWriteExt as _},
+    sync::Mutex,
+};
+
+use crate::ext::{std::path::PathExt as _, tokio::path::PathExt as _};
+
+const JOURNAL_FILE_EXTENSION: &str = "jsonl";
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum Entry {
+    Created {
+        path: PathBuf,
+    },
+    BackedUp {
+        path: PathBuf,
+        backup_path: PathBuf,
+    },
+    RemovedDir {
+        path: PathBuf,
+    },
+    Committed,
+}
+
+pub(crate) struct Journal {
+    dir_path: PathBuf,
+    file_path: PathBuf,
+
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    journal_file: Option<File>,
+    entries: Vec<Entry>,
+    backup_count: u64,
+}
+
+impl Journal {
+    pub(crate) fn new(dir_path: PathBuf) -> Self {
+        let file_name = format!("{}.{JOURNAL_FILE_EXTENSION}", process::id());
+
+        let file_path = dir_path.join(file_name);
+
+        Self {
+            dir_path,
+            file_path,
+
+            state: Mutex::default(),
+        }
+    }
+
+    pub(crate) async fn record_creation(&self, path: &Path) -> anyhow::Result<()> {
+        let mut state = self.state.lock().await;
+
+        self.back_up(&mut state, path).await?;
+
+        let entry = Entry::Created {
+            path: path.to_owned(),
+        };
+
+        self.append(&mut state, entry).await?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn remove(&self, path: &Path) -> anyhow::Result<()> {
+        let mut state = self.state.lock().await;
+
+        self.back_up(&mut state, path).await?;
+
+        Ok(())
+    }
+
+    // Empty directories are removed outright instead of being backed up next to themselves, which
+    // would keep their parents from ever becoming empty.
+    pub(crate) async fn remove_empty_dir(&self, path: &Path) -> anyhow::Result<()> {
+        let mut state = self.state.lock().await;
+
+        let entry = Entry::RemovedDir {
+            path: path.to_owned(),
+        };
+
+        self.append(&mut state, entry).await?;
+
+        fs::remove_dir(path).await?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn commit(&self) -> anyhow::Result<()> {
+        let mut state = self.state.lock().await;
+
+        if state.entries.is_empty() {
+            return Ok(());
+        }
+
+        self.append(&mut state, Entry::Committed).await?;
+
+        finish(&state.entries).await?;
+
+        self.close(&mut state).await?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn roll_back(&self) -> anyhow::Result<()> {
+        let mut state = self.state.lock().await;
+
+        if state.entries.is_empty() {
+            return Ok(());
+        }
+
+        undo(&state.entries)
+            .await
+            .context("Failed to roll back the installation")?;
+
+        self.close(&mut state).await?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn load_stale(&self) -> anyhow::Result<Vec<StaleJournal>> {
+        if !self.dir_path.is_dir_exists_nofollow().await? {
+            return Ok(Vec::new());
+        }
+
+        let mut stale_journals = Vec::new();
+
+        let mut journal_dir_entries = fs::read_dir(&self.dir_path).await?;
+
+        while let Some(journal_dir_entry) = journal_dir_entries.next_entry().await? {
+            let journal_file_path = journal_dir_entry.path();
+
+            let is_journal_file = journal_file_path
+                .extension()
+                .is_some_and(|extension| extension == JOURNAL_FILE_EXTENSION);
+
+            if !is_journal_file || journal_file_path == self.file_path {
+                continue;
+            }
+
+            let Some(stale_journal) = StaleJournal::load(journal_file_path).await? else {
+                continue;
+            };
+
+            stale_journals.push(stale_journal);
+        }
+
+        stale_journals.sort_by(|left, right| left.file_path.cmp(&right.file_path));
+
+        Ok(stale_journals)
+    }
+
+    async fn back_up(&self, state: &mut State, path: &Path) -> anyhow::Result<()> {
+        if !path.is_exists_nofollow().await? {
+            return Ok(());
+        }
+
+        state.backup_count = state.backup_count.saturating_add(1);
+
+        let backup_path = Self::backup_path(path, state.backup_count)?;
+
+        let entry = Entry::BackedUp {
+            path: path.to_owned(),
+            backup_path: backup_path.clone(),
+        };
+
+        self.append(state, entry).await?;
+
+        fs::rename(path, &backup_path).await.with_context(|| {
+            let path = path.display();
+
+            format!(r#"Failed to back up "{path}""#)
+        })?;
+
+        Ok(())
+    }
+
+    fn backup_path(path: &Path, backup_count: u64) -> anyhow::Result<PathBuf> {
+        let file_name = path.file_name().with_context(|| {
+            let path = path.display();
+
+            format!(r#"Path "{path}" has no file name"#)
+        })?;
+        let file_name = file_name.to_string_lossy();
+
+        let pid = process::id();
+
+        let backup_file_name = format!(".{file_name}.{pid}-{backup_count}.backup");
+
+        let backup_path = path.with_file_name(backup_file_name);
+
+        Ok(backup_path)
+    }
+
+    async fn append(&self, state: &mut State, entry: Entry) -> anyhow::Result<()> {
+        let mut journal_file = match state.journal_file.take() {
+            Some(journal_file) => journal_file,
+            None => self.open().await?,
+        };
+
+        let mut line = serde_json::to_vec(&entry)?;
+
+        line.push(b'\n');
+
+        journal_file.write_all(&line).await?;
+
+        journal_file.flush().await?;
+
+        state.journal_file = Some(journal_file);
+
+        state.entries.push(entry);
+
+        Ok(())
+    }
+
+    async fn open(&self) -> anyhow::Result<File> {
+        fs::create_dir_all(&self.dir_path).await?;
+
+        let journal_file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.file_path)
+            .await?;
+
+        let journal_file = journal_file.into_std().await;
+
+        journal_file.try_lock()?;
+
+        let journal_file = File::from_std(journal_file);
+
+        Ok(journal_file)
+    }
+
+    async fn close(&self, state: &mut State) -> anyhow::Result<()> {
+        state.entries.clear();
+
+        state.journal_file = None;
+
+        fs::remove_file(&self.file_path).await?;
+
+        Ok(())
+    }
+}
+
+pub(crate) struct StaleJournal {
+    file_path: PathBuf,
+    file: StdFile,
+
+    entries: Vec<Entry>,
+}
+
+impl StaleJournal {
+    async fn load(file_path: PathBuf) -> anyhow::Result<Option<Self>> {
+        let file = File::open(&file_path).await?;
+        let file = file.into_std().await;
+
+        if file.try_lock().is_err() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&file_path).await?;
+
+        let entries = content
+            .lines()
+            .filter_map(|line| serde_json::from_str::<Entry>(line).ok())
+            .collect::<Vec<_>>();
+
+        let this = Self {
+            file_path,
+            file,
+
+            entries,
+        };
+
+        Ok(Some(this))
+    }
+
+    pub(crate) fn file_path(&self) -> &Path {
+        &self.file_path
+    }
+
+    pub(crate) fn change_count(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| !matches!(entry, Entry::Committed))
+            .count()
+    }
+
+    pub(crate) fn is_committed(&self) -> bool {
+        self.entries
+            .iter()
+            .any(|entry| matches!(entry, Entry::Committed))
+    }
+
+    pub(crate) async fn repair(self) -> anyhow::Result<()> {
+        if self.is_committed() {
+            finish(&self.entries).await?;
+        } else {
+            undo(&self.entries).await?;
+        }
+
+        fs::remove_file(&self.file_path).await?;
+
+        drop(self.file);
+
+        Ok(())
+    }
+}
+
+async fn finish(entries: &[Entry]) -> anyhow::Result<()> {
+    for entry in entries {
+        let Entry::BackedUp {
+            backup_path,
+            ..
+        } = entry
+        else {
+            continue;
+        };
+
+        remove_path(backup_path).await?;
+    }
+
+    Ok(())
+}
+
+async fn undo(entries: &[Entry]) -> anyhow::Result<()> {
+    for entry in entries.iter().rev() {
+        match entry {
+            Entry::Created {
+                path,
+            } => remove_path(path).await?,
+            Entry::BackedUp {
+                path,
+                backup_path,
+            } => {
+                if !backup_path.is_exists_nofollow().await? {
+                    continue;
+                }
+
+                remove_path(path).await?;
+
+                let base_path = path.base()?;
+
+                fs::create_dir_all(base_path).await?;
+
+                fs::rename(backup_path, path).await.with_context(|| {
+                    let path = path.display();
+
+                    format!(r#"Failed to restore "{path}""#)
+                })?;
+            },
+            Entry::RemovedDir {
+                path,
+            } => fs::create_dir_all(path).await?,
+            Entry::Committed => {},
+        }
+    }
+
+    Ok(())
+}
+
+async fn remove_path(path: &Path) -> io::Result<()> {
+    let metadata = match fs::symlink_metadata(path).await {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err),
+    };
+
+    if metadata.is_dir() {
+        fs::remove_dir_all(path).await?;
+    } else {
+        fs::remove_file(path).await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs as std_fs;
+
+    use tempfile::TempDir;
+    use tokio::runtime;
+
+    use super::*;
+
+    #[test]
+    fn rolls_back_changes() {
+        let temp_dir = TempDir::new().expect("temp dir should be created");
+
+        let created_path = temp_dir.path().join("created");
+        let replaced_path = temp_dir.path().join("replaced");
+        let removed_dir_path = temp_dir.path().join("removed");
+
+        std_fs::write(&replaced_path, "old").expect("file should be written");
+        std_fs::create_dir(&removed_dir_path).expect("dir should be created");
+
+        let journal = Journal::new(temp_dir.path().join("journal"));
+
+        block_on(async {
+            journal.record_creation(&created_path).await?;
+            fs::write(&created_path, "new").await?;
+
+            journal.record_creation(&replaced_path).await?;
+            fs::write(&replaced_path, "new").await?;
+
+            journal.remove_empty_dir(&removed_dir_path).await?;
+
+            journal.roll_back().await
+        });
+
+        assert!(!created_path.exists());
+        assert_eq!(
+            std_fs::read_to_string(&replaced_path).expect("file should be read"),
+            "old"
+        );
+        assert!(removed_dir_path.is_dir());
+        assert!(!journal.file_path.exists());
+        assert_eq!(
+            entry_names(temp_dir.path()),
+            ["journal", "removed", "replaced"]
+        );
+    }
+
+    #[test]
+    fn commits_changes_and_drops_backups() {
+        let temp_dir = TempDir::new().expect("temp dir should be created");
+
+        let replaced_path = temp_dir.path().join("replaced");
+        let removed_path = temp_dir.path().join("removed");
+
+        std_fs::write(&replaced_path, "old").expect("file should be written");
+        std_fs::write(&removed_path, "old").expect("file should be written");
+
+        let journal = Journal::new(temp_dir.path().join("journal"));
+
+        block_on(async {
+            journal.record_creation(&replaced_path).await?;
+            fs::write(&replaced_path, "new").await?;
+
+            journal.remove(&removed_path).await?;
+
+            journal.commit().await
+        });
+
+        assert_eq!(
+            std_fs::read_to_string(&replaced_path).expect("file should be read"),
+            "new"
+        );
+        assert!(!removed_path.exists());
+        assert!(!journal.file_path.exists());
+        assert_eq!(entry_names(temp_dir.path()), ["journal", "replaced"]);
+    }
+
+    #[test]
+    fn repairs_interrupted_transaction() {
+        let temp_dir = TempDir::new().expect("temp dir should be created");
+
+        let journal_dir_path = temp_dir.path().join("journal");
+
+        let created_path = temp_dir.path().join("created");
+        let replaced_path = temp_dir.path().join("replaced");
+        let backup_path = temp_dir.path().join(".replaced.backup");
+
+        std_fs::write(&created_path, "new").expect("file should be written");
+        std_fs::write(&replaced_path, "new").expect("file should be written");
+        std_fs::write(&backup_path, "old").expect("file should be written");
+
+        write_stale_journal(
+            &journal_dir_path,
+            &[
+                Entry::BackedUp {
+                    path: replaced_path.clone(),
+                    backup_path,
+                },
+                Entry::Created {
+                    path: created_path.clone(),
+                },
+            ],
+        );
+
+        let journal = Journal::new(journal_dir_path);
+
+        let stale_journals = block_on(async { journal.load_stale().await });
+
+        assert_eq!(stale_journals.len(), 1);
+
+        for stale_journal in stale_journals {
+            assert_eq!(stale_journal.change_count(), 2);
+            assert!(!stale_journal.is_committed());
+
+            block_on(async { stale_journal.repair().await });
+        }
+
+        assert!(!created_path.exists());
+        assert_eq!(
+            std_fs::read_to_string(&replaced_path).expect("file should be read"),
+            "old"
+        );
+        assert_eq!(entry_names(temp_dir.path()), ["journal", "replaced"]);
+        assert_eq!(entry_names(&journal.dir_path), Vec::<String>::new());
+    }
+
+    #[test]
+    fn repairs_committed_transaction() {
+        let temp_dir = TempDir::new().expect("temp dir should be created");
+
+        let journal_dir_path = temp_dir.path().join("journal");
+
+        let replaced_path = temp_dir.path().join("replaced");
+        let backup_path = temp_dir.path().join(".replaced.backup");
+
+        std_fs::write(&replaced_path, "new").expect("file should be written");
+        std_fs::write(&backup_path, "old").expect("file should be written");
+
+        write_stale_journal(
+            &journal_dir_path,
+            &[
+                Entry::BackedUp {
+                    path: replaced_path.clone(),
+                    backup_path,
+                },
+                Entry::Committed,
+            ],
+        );
+
+        let journal = Journal::new(journal_dir_path);
+
+        let stale_journals = block_on(async { journal.load_stale().await });
+
+        for stale_journal in stale_journals {
+            assert!(stale_journal.is_committed());
+
+            block_on(async { stale_journal.repair().await });
+        }
+
+        assert_eq!(
+            std_fs::read_to_string(&replaced_path).expect("file should be read"),
+            "new"
+        );
+        assert_eq!(entry_names(temp_dir.path()), ["journal", "replaced"]);
+    }
+
+    fn block_on<T>(future: impl Future<Output = anyhow::Result<T>>) -> T {
+        let runtime = runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("runtime should be built");
+
+        runtime
+            .block_on(future)
+            .expect("journal operation should succeed")
+    }
+
+    fn write_stale_journal(journal_dir_path: &Path, entries: &[Entry]) {
+        std_fs::create_dir_all(journal_dir_path).expect("dir should be created");
+
+        let content = entries
+            .iter()
+            .map(|entry| serde_json::to_string(entry).expect("entry should serialize"))
+            .collect::<Vec<_>>();
+        let content = content.join("\n");
+
+        std_fs::write(journal_dir_path.join("0.jsonl"), content)
+            .expect("journal should be written");
+    }
+
+    fn entry_names(dir_path: &Path) -> Vec<String> {
+        let mut entry_names = std_fs::read_dir(dir_path)
+            .expect("dir should be read")
+            .map(|entry| {
+                let entry = entry.expect("entry should be read");
+
+                entry.file_name().to_string_lossy().into_owned()
+            })
+            .collect::<Vec<_>>();
+
+        entry_names.sort();
+
+        entry_names
+    }
+}
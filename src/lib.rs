@@ -42,10 +42,12 @@
 pub mod command;
 pub mod context;
 mod ext;
+mod journal;
 mod package;
 mod pipeline;
 mod receipt;
 mod registries;
+mod store;
 mod util;
 
 use clap::{ArgMatches, FromArgMatches as _};
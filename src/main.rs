@@ -33,8 +33,6 @@
 use clap::CommandFactory as _;
 use clap_verbosity_flag::VerbosityFilter;
 use neobrew::{command::Cli, context::Context};
-use proc_exit::WithCodeResultExt as _;
-use tokio::{signal, task};
 use tracing_subscriber::{
     EnvFilter,
     filter::{Directive, LevelFilter},
@@ -51,28 +49,7 @@ async fn main() -> proc_exit::ExitResult {
 
     init_tracing(*context.config().verbosity_filter());
 
-    let handle = task::spawn(async {
-        signal::ctrl_c().await?;
-
-        anyhow::Ok(())
-    });
-
-    #[expect(clippy::disallowed_macros)]
-    let result = tokio::select! {
-        biased;
-
-        result = handle => {
-            result
-                .with_code(proc_exit::sysexits::SOFTWARE_ERR)?
-                .with_code(proc_exit::sysexits::OS_ERR)?;
-
-            proc_exit::bash::SIGINT.ok()
-        },
-
-        result = neobrew::run(&matches, context) => result,
-    };
-
-    result?;
+    neobrew::run(&matches, context).await?;
 
     proc_exit::Code::SUCCESS.ok()
 }
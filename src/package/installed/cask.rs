@@ -1,20 +1,49 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+use serde::Deserialize;
+use tokio::{fs, io};
+
 use super::{
-    super::{PackageExt, prepared::cask::PreparedCask},
+    super::{
+        PackageExt,
+        prepared::{cask::PreparedCask, cask_stanza::Stanzas},
+        raw::cask::RawCask,
+        resolved::cask::ResolvedCask,
+    },
     InstalledPackageExt,
 };
+use crate::{
+    context::Context,
+    ext::tokio::path::PathExt as _,
+    receipt::cask::{CaskReceipt, RuntimeDependencies},
+    registries,
+};
 
 pub(crate) struct InstalledCask {
     token: String,
     version: String,
+    tap: Option<String>,
+    time: Option<i64>,
     is_requested: bool,
+    runtime_dependencies: RuntimeDependencies,
 }
 
 impl From<PreparedCask> for InstalledCask {
     fn from(prepared_cask: PreparedCask) -> Self {
+        let tap = prepared_cask.tap();
+        let tap = tap.to_owned();
+
+        let runtime_dependencies = prepared_cask.runtime_dependencies();
+        let runtime_dependencies = runtime_dependencies.clone();
+
         Self {
             token: prepared_cask.token,
             version: prepared_cask.version,
+            tap: Some(tap),
+            time: None,
             is_requested: prepared_cask.is_requested,
+            runtime_dependencies,
         }
     }
 }
@@ -29,4 +58,248 @@ fn version(&self) -> &str {
     }
 }
 
-impl InstalledPackageExt for InstalledCask {}
+impl InstalledPackageExt for InstalledCask {
+    fn tap(&self) -> Option<&str> {
+        self.tap.as_deref()
+    }
+
+    fn time(&self) -> Option<i64> {
+        self.time
+    }
+
+    fn is_requested(&self) -> bool {
+        self.is_requested
+    }
+}
+
+impl InstalledCask {
+    pub(super) async fn load_all(context: &Context) -> anyhow::Result<Vec<Self>> {
+        let caskroom_dir_path = context.homebrew_dirs.caskroom_dir();
+
+        if !caskroom_dir_path.is_dir_exists_nofollow().await? {
+            return Ok(Vec::new());
+        }
+
+        let mut ids = Vec::new();
+
+        let mut caskroom_dir_entries = fs::read_dir(caskroom_dir_path).await?;
+
+        while let Some(caskroom_dir_entry) = caskroom_dir_entries.next_entry().await? {
+            let caskroom_dir_entry_path = caskroom_dir_entry.path();
+
+            if !caskroom_dir_entry_path.is_dir_exists_nofollow().await? {
+                continue;
+            }
+
+            let caskroom_dir_entry_name = caskroom_dir_entry.file_name();
+
+            let Some(id) = caskroom_dir_entry_name.to_str() else {
+                continue;
+            };
+
+            if id.starts_with('.') {
+                continue;
+            }
+
+            ids.push(id.to_owned());
+        }
+
+        ids.sort();
+
+        let mut this = Vec::new();
+
+        for id in ids {
+            let installed_casks = Self::load_many(&id, context).await?;
+
+            this.extend(installed_casks);
+        }
+
+        Ok(this)
+    }
+
+    pub(crate) async fn load_many(id: &str, context: &Context) -> anyhow::Result<Vec<Self>> {
+        let cask_dir_path = context.homebrew_dirs.cask_dir(id);
+
+        if !cask_dir_path.is_dir_exists_nofollow().await? {
+            return Ok(Vec::new());
+        }
+
+        let metadata_dir_path = context.homebrew_dirs.metadata_dir(id);
+
+        let receipt = CaskReceipt::load(&metadata_dir_path).await?;
+
+        let mut this = Vec::new();
+
+        let mut cask_dir_entries = fs::read_dir(cask_dir_path).await?;
+
+        while let Some(cask_dir_entry) = cask_dir_entries.next_entry().await? {
+            let cask_dir_entry_path = cask_dir_entry.path();
+
+            if !cask_dir_entry_path.is_dir_exists_nofollow().await? {
+                continue;
+            }
+
+            let cask_dir_entry_name = cask_dir_entry.file_name();
+
+            let Some(version) = cask_dir_entry_name.to_str() else {
+                continue;
+            };
+
+            if version.starts_with('.') {
+                continue;
+            }
+
+            if cask_dir_entry_path.is_dir_empty().await? {
+                continue;
+            }
+
+            let mut installed_cask = Self {
+                token: id.to_owned(),
+                version: version.to_owned(),
+                tap: None,
+                time: None,
+                is_requested: false,
+                runtime_dependencies: RuntimeDependencies::default(),
+            };
+
+            if let Some(receipt) = &receipt {
+                installed_cask.tap = receipt.tap().map(ToOwned::to_owned);
+                installed_cask.time = receipt.time();
+                installed_cask.is_requested = receipt.installed_on_request();
+
+                if let Some(runtime_dependencies) = receipt.runtime_dependencies() {
+                    installed_cask.runtime_dependencies = runtime_dependencies.clone();
+                }
+            } else {
+                installed_cask.tap = installed_cask.load_tap(context).await?;
+            }
+
+            this.push(installed_cask);
+        }
+
+        this.sort_by(|left, right| left.version.cmp(&right.version));
+
+        Ok(this)
+    }
+
+    async fn load_tap(&self, context: &Context) -> anyhow::Result<Option<String>> {
+        let json_file_paths = self.json_file_paths(context).await?;
+
+        for json_file_path in &json_file_paths {
+            let Some(caskfile) = Caskfile::load(json_file_path).await? else {
+                continue;
+            };
+
+            if caskfile.token != self.token {
+                continue;
+            }
+
+            return Ok(caskfile.tap);
+        }
+
+        Ok(None)
+    }
+
+    pub(crate) async fn load_stanzas(&self, context: &Context) -> anyhow::Result<Stanzas> {
+        let id = self.id();
+
+        let json_file_paths = self.json_file_paths(context).await?;
+
+        for json_file_path in json_file_paths {
+            let bytes = match fs::read(&json_file_path).await {
+                Ok(bytes) => bytes,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err.into()),
+            };
+
+            let Ok(raw_cask) = serde_json::from_slice::<RawCask>(&bytes) else {
+                continue;
+            };
+
+            if raw_cask.token != id {
+                continue;
+            }
+
+            let resolved_cask = ResolvedCask::from((raw_cask, Vec::new(), Vec::new()));
+
+            let prepared_cask = PreparedCask::try_from((resolved_cask, context))?;
+
+            let stanzas = prepared_cask.into_stanzas();
+
+            return Ok(stanzas);
+        }
+
+        let err = anyhow!(r#"Cask "{id}" has no cached definition to determine its artifacts"#);
+
+        Err(err)
+    }
+
+    pub(crate) fn runtime_dependencies(&self) -> &RuntimeDependencies {
+        &self.runtime_dependencies
+    }
+
+    async fn json_file_paths(&self, context: &Context) -> anyhow::Result<Vec<PathBuf>> {
+        let id = self.id();
+
+        let version = self.version();
+
+        let file_name = format!("{id}.json");
+
+        let json_id = registries::qualify_cask(self.tap(), id);
+
+        let api_json_file_path = context.homebrew_dirs.cask_json_file(&json_id);
+
+        let metadata_dir_path = context.homebrew_dirs.metadata_dir(id).join(version);
+
+        if !metadata_dir_path.is_dir_exists_nofollow().await? {
+            return Ok(vec![api_json_file_path]);
+        }
+
+        let mut timestamp_dir_paths = Vec::new();
+
+        let mut metadata_dir_entries = fs::read_dir(metadata_dir_path).await?;
+
+        while let Some(metadata_dir_entry) = metadata_dir_entries.next_entry().await? {
+            let metadata_dir_entry_path = metadata_dir_entry.path();
+
+            if metadata_dir_entry_path.is_dir_exists_nofollow().await? {
+                timestamp_dir_paths.push(metadata_dir_entry_path);
+            }
+        }
+
+        timestamp_dir_paths.sort();
+
+        let mut json_file_paths = timestamp_dir_paths
+            .into_iter()
+            .rev()
+            .map(|timestamp_dir_path| timestamp_dir_path.join("Casks").join(&file_name))
+            .collect::<Vec<_>>();
+
+        json_file_paths.push(api_json_file_path);
+
+        Ok(json_file_paths)
+    }
+}
+
+#[derive(Deserialize)]
+struct Caskfile {
+    token: String,
+    #[serde(default)]
+    tap: Option<String>,
+}
+
+impl Caskfile {
+    async fn load(json_file_path: &Path) -> anyhow::Result<Option<Self>> {
+        let bytes = match fs::read(json_file_path).await {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        let Ok(caskfile) = serde_json::from_slice::<Self>(&bytes) else {
+            return Ok(None);
+        };
+
+        Ok(Some(caskfile))
+    }
+}
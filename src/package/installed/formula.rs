@@ -1,20 +1,51 @@
+use std::path::Path;
+
+use lazy_regex::regex_captures;
+use tokio::fs;
+
 use super::{
     super::{PackageExt, prepared::formula::PreparedFormula},
     InstalledPackageExt,
 };
+use crate::{
+    context::Context,
+    ext::tokio::path::PathExt as _,
+    receipt::formula::{FormulaReceipt, RuntimeDependency},
+};
 
 pub(crate) struct InstalledFormula {
     name: String,
     version: String,
+    version_revision: String,
+    revision: u64,
+    tap: Option<String>,
+    time: Option<i64>,
     is_requested: bool,
+    runtime_dependencies: Vec<RuntimeDependency>,
 }
 
 impl From<PreparedFormula> for InstalledFormula {
     fn from(prepared_formula: PreparedFormula) -> Self {
+        let version_revision = prepared_formula.version_revision();
+        let version_revision = version_revision.to_owned();
+
+        let (_, revision) = Self::split_version_revision(&version_revision);
+
+        let tap = prepared_formula.tap();
+        let tap = tap.to_owned();
+
+        let runtime_dependencies = prepared_formula.runtime_dependencies();
+        let runtime_dependencies = runtime_dependencies.to_vec();
+
         Self {
             name: prepared_formula.name,
             version: prepared_formula.version,
+            version_revision,
+            revision,
+            tap: Some(tap),
+            time: None,
             is_requested: prepared_formula.is_requested,
+            runtime_dependencies,
         }
     }
 }
@@ -29,4 +60,168 @@ fn version(&self) -> &str {
     }
 }
 
-impl InstalledPackageExt for InstalledFormula {}
+impl InstalledPackageExt for InstalledFormula {
+    fn tap(&self) -> Option<&str> {
+        self.tap.as_deref()
+    }
+
+    fn time(&self) -> Option<i64> {
+        self.time
+    }
+
+    fn is_requested(&self) -> bool {
+        self.is_requested
+    }
+}
+
+impl InstalledFormula {
+    pub(super) async fn load_all(context: &Context) -> anyhow::Result<Vec<Self>> {
+        let cellar_dir_path = context.homebrew_dirs.cellar_dir();
+
+        if !cellar_dir_path.is_dir_exists_nofollow().await? {
+            return Ok(Vec::new());
+        }
+
+        let mut ids = Vec::new();
+
+        let mut cellar_dir_entries = fs::read_dir(cellar_dir_path).await?;
+
+        while let Some(cellar_dir_entry) = cellar_dir_entries.next_entry().await? {
+            let cellar_dir_entry_path = cellar_dir_entry.path();
+
+            if !cellar_dir_entry_path.is_dir_exists_nofollow().await? {
+                continue;
+            }
+
+            let cellar_dir_entry_name = cellar_dir_entry.file_name();
+
+            let Some(id) = cellar_dir_entry_name.to_str() else {
+                continue;
+            };
+
+            if id.starts_with('.') {
+                continue;
+            }
+
+            ids.push(id.to_owned());
+        }
+
+        ids.sort();
+
+        let mut this = Vec::new();
+
+        for id in ids {
+            let installed_formulae = Self::load_many(&id, context).await?;
+
+            this.extend(installed_formulae);
+        }
+
+        Ok(this)
+    }
+
+    pub(crate) async fn load_many(id: &str, context: &Context) -> anyhow::Result<Vec<Self>> {
+        let rack_dir_path = context.homebrew_dirs.rack_dir(id);
+
+        if !rack_dir_path.is_dir_exists_nofollow().await? {
+            return Ok(Vec::new());
+        }
+
+        let mut this = Vec::new();
+
+        let mut rack_dir_entries = fs::read_dir(rack_dir_path).await?;
+
+        while let Some(rack_dir_entry) = rack_dir_entries.next_entry().await? {
+            let rack_dir_entry_path = rack_dir_entry.path();
+
+            if !rack_dir_entry_path.is_dir_exists_nofollow().await? {
+                continue;
+            }
+
+            let rack_dir_entry_name = rack_dir_entry.file_name();
+
+            let Some(version_revision) = rack_dir_entry_name.to_str() else {
+                continue;
+            };
+
+            if version_revision.starts_with('.') {
+                continue;
+            }
+
+            let Some(installed_formula) =
+                Self::load(id, version_revision, &rack_dir_entry_path).await?
+            else {
+                continue;
+            };
+
+            this.push(installed_formula);
+        }
+
+        this.sort_by(|left, right| left.version_revision.cmp(&right.version_revision));
+
+        Ok(this)
+    }
+
+    async fn load(
+        id: &str,
+        version_revision: &str,
+        keg_dir_path: &Path,
+    ) -> anyhow::Result<Option<Self>> {
+        if keg_dir_path.is_dir_empty().await? {
+            return Ok(None);
+        }
+
+        let (version, revision) = Self::split_version_revision(version_revision);
+
+        let receipt = FormulaReceipt::load(keg_dir_path).await?;
+
+        let this = match receipt {
+            Some(receipt) => Self {
+                name: id.to_owned(),
+                version: version.to_owned(),
+                version_revision: version_revision.to_owned(),
+                revision,
+                tap: receipt.tap().map(ToOwned::to_owned),
+                time: receipt.time(),
+                is_requested: receipt.installed_on_request(),
+                runtime_dependencies: receipt.runtime_dependencies().to_vec(),
+            },
+            None => Self {
+                name: id.to_owned(),
+                version: version.to_owned(),
+                version_revision: version_revision.to_owned(),
+                revision,
+                tap: None,
+                time: None,
+                is_requested: false,
+                runtime_dependencies: Vec::new(),
+            },
+        };
+
+        Ok(Some(this))
+    }
+
+    fn split_version_revision(version_revision: &str) -> (&str, u64) {
+        let Some((_, version, revision)) = regex_captures!(r"^(.+)_(\d+)$", version_revision)
+        else {
+            return (version_revision, 0);
+        };
+
+        let Ok(revision) = revision.parse::<u64>() else {
+            return (version_revision, 0);
+        };
+
+        (version, revision)
+    }
+
+    pub(crate) fn version_revision(&self) -> &str {
+        &self.version_revision
+    }
+
+    pub(crate) fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    pub(crate) fn runtime_dependencies(&self) -> &[RuntimeDependency] {
+        &self.runtime_dependencies
+    }
+}
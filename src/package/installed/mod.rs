@@ -1,10 +1,11 @@
-mod cask;
-mod formula;
+pub(crate) mod cask;
+pub(crate) mod formula;
 
 use enum_dispatch::enum_dispatch;
 
 use self::{cask::InstalledCask, formula::InstalledFormula};
 use super::PackageExt;
+use crate::context::Context;
 
 #[enum_dispatch]
 pub(crate) enum InstalledPackage {
@@ -12,5 +13,49 @@ pub(crate) enum InstalledPackage {
     Cask(InstalledCask),
 }
 
+impl InstalledPackage {
+    pub(crate) async fn load_all(context: &Context) -> anyhow::Result<Vec<Self>> {
+        let installed_formulae = InstalledFormula::load_all(context).await?;
+
+        let installed_casks = InstalledCask::load_all(context).await?;
+
+        let this = installed_formulae
+            .into_iter()
+            .map(Self::Formula)
+            .chain(installed_casks.into_iter().map(Self::Cask))
+            .collect::<Vec<_>>();
+
+        Ok(this)
+    }
+
+    pub(crate) async fn load_many(package: &str, context: &Context) -> anyhow::Result<Vec<Self>> {
+        let installed_formulae = InstalledFormula::load_many(package, context).await?;
+
+        if !installed_formulae.is_empty() {
+            let this = installed_formulae
+                .into_iter()
+                .map(Self::Formula)
+                .collect::<Vec<_>>();
+
+            return Ok(this);
+        }
+
+        let installed_casks = InstalledCask::load_many(package, context).await?;
+
+        let this = installed_casks
+            .into_iter()
+            .map(Self::Cask)
+            .collect::<Vec<_>>();
+
+        Ok(this)
+    }
+}
+
 #[enum_dispatch(InstalledPackage)]
-trait InstalledPackageExt: PackageExt {}
+pub(crate) trait InstalledPackageExt: PackageExt {
+    fn tap(&self) -> Option<&str>;
+
+    fn time(&self) -> Option<i64>;
+
+    fn is_requested(&self) -> bool;
+}
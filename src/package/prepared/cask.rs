@@ -2,22 +2,18 @@
 
 use bytes::Bytes;
 use futures::stream::BoxStream;
-use tokio::fs;
 
 use super::{
-    super::{
-        PackageExt,
-        raw::cask::{Artifact, Variation},
-        resolved::cask::ResolvedCask,
-    },
+    super::{PackageExt, installed::cask::InstalledCask, resolved::cask::ResolvedCask},
     PreparedPackageExt,
     cask_stanza::Stanzas,
     download::{Download, DownloadExt as _},
 };
-use crate::{context::Context, ext::tokio::path::PathExt as _};
+use crate::{context::Context, receipt::cask::RuntimeDependencies};
 
 pub(crate) struct PreparedCask<Dl = ()> {
     pub(in super::super) token: String,
+    tap: String,
     pub(in super::super) version: String,
     variation_tag: Option<String>,
     variation_url: String,
@@ -25,6 +21,7 @@ pub(crate) struct PreparedCask<Dl = ()> {
     variation_stanzas: Stanzas,
     is_compatible: bool,
     pub(in super::super) is_requested: bool,
+    runtime_dependencies: RuntimeDependencies,
 
     download: Dl,
 }
@@ -33,27 +30,23 @@ impl TryFrom<(ResolvedCask, &Context)> for PreparedCask {
     type Error = anyhow::Error;
 
     fn try_from(
-        (mut resolved_cask, context): (ResolvedCask, &Context),
+        (mut resolved_cask, _context): (ResolvedCask, &Context),
     ) -> Result<Self, Self::Error> {
-        let token = resolved_cask.token.clone();
-
-        let version = resolved_cask.version.clone();
-
         let is_compatible = *resolved_cask.is_compatible.get_mut();
 
         let is_requested = *resolved_cask.is_requested.get_mut();
 
-        let (variation_tag, variation) = resolved_cask.variation_entry(context)?;
-
         let this = Self {
-            token,
-            version,
-            variation_tag,
-            variation_url: variation.url,
-            variation_sha256: variation.sha256,
-            variation_stanzas: Stanzas::from(variation.artifacts),
+            token: resolved_cask.token,
+            tap: resolved_cask.tap,
+            version: resolved_cask.version,
+            variation_tag: resolved_cask.variation_tag,
+            variation_url: resolved_cask.url,
+            variation_sha256: resolved_cask.sha256,
+            variation_stanzas: Stanzas::from(resolved_cask.artifacts),
             is_compatible,
             is_requested,
+            runtime_dependencies: resolved_cask.runtime_dependencies,
 
             download: (),
         };
@@ -66,6 +59,7 @@ impl<Dl> From<(PreparedCask<()>, Dl)> for PreparedCask<Dl> {
     fn from((this, download): (PreparedCask<()>, Dl)) -> Self {
         Self {
             token: this.token,
+            tap: this.tap,
             version: this.version,
             variation_tag: this.variation_tag,
             variation_url: this.variation_url,
@@ -73,6 +67,7 @@ fn from((this, download): (PreparedCask<()>, Dl)) -> Self {
             variation_stanzas: this.variation_stanzas,
             is_compatible: this.is_compatible,
             is_requested: this.is_requested,
+            runtime_dependencies: this.runtime_dependencies,
 
             download,
         }
@@ -115,27 +110,9 @@ fn is_compatible(&self) -> bool {
     async fn is_installed(&self, context: &Context) -> anyhow::Result<bool> {
         let id = self.id();
 
-        let cask_dir_path = context.homebrew_dirs.cask_dir(id);
-
-        if !cask_dir_path.is_dir_exists_nofollow().await? {
-            return Ok(false);
-        }
-
-        let mut cask_dir_entries = fs::read_dir(cask_dir_path).await?;
+        let installed_casks = InstalledCask::load_many(id, context).await?;
 
-        while let Some(cask_dir_entry) = cask_dir_entries.next_entry().await? {
-            let cask_dir_entry_path = cask_dir_entry.path();
-
-            let is_cask_dir_entry_exists = cask_dir_entry_path.is_dir_exists_nofollow().await?;
-
-            let is_cask_dir_entry_not_empty = !cask_dir_entry_path.is_dir_empty().await?;
-
-            if is_cask_dir_entry_exists && is_cask_dir_entry_not_empty {
-                return Ok(true);
-            }
-        }
-
-        Ok(false)
+        Ok(!installed_casks.is_empty())
     }
 
     async fn is_up_to_date(&self, context: &Context) -> anyhow::Result<bool> {
@@ -143,17 +120,13 @@ async fn is_up_to_date(&self, context: &Context) -> anyhow::Result<bool> {
 
         let version = self.version();
 
-        let staged_dir_path = context.homebrew_dirs.staged_dir(id, version);
-
-        let is_staged_dir_exists = staged_dir_path.is_dir_exists_nofollow().await?;
+        let installed_casks = InstalledCask::load_many(id, context).await?;
 
-        let is_staged_dir_not_empty = !staged_dir_path.is_dir_empty().await?;
+        let is_up_to_date = installed_casks
+            .iter()
+            .any(|installed_cask| installed_cask.version() == version);
 
-        if is_staged_dir_exists && is_staged_dir_not_empty {
-            return Ok(true);
-        }
-
-        Ok(false)
+        Ok(is_up_to_date)
     }
 
     fn download(&self) -> &Self::Download {
@@ -170,133 +143,31 @@ fn extract_dir_path(&self, context: &Context) -> PathBuf {
 }
 
 impl<Dl> PreparedCask<Dl> {
-    pub(super) fn variation_url(&self) -> &str {
-        &self.variation_url
+    pub(crate) fn tap(&self) -> &str {
+        &self.tap
     }
 
-    pub(super) fn variation_sha256(&self) -> &str {
-        &self.variation_sha256
+    pub(crate) fn is_requested(&self) -> bool {
+        self.is_requested
     }
 
-    pub(crate) fn stanzas(&self) -> &Stanzas {
-        &self.variation_stanzas
+    pub(crate) fn runtime_dependencies(&self) -> &RuntimeDependencies {
+        &self.runtime_dependencies
     }
-}
-
-impl ResolvedCask {
-    fn variation_entry(
-        mut self,
-        context: &Context,
-    ) -> anyhow::Result<(Option<String>, Variation<Vec<Artifact>>)> {
-        #[expect(clippy::collapsible_if)]
-        if let Some(tag) = self.variation_tag(context)? {
-            if let Some((tag, variation)) = self.variations.remove_entry(&tag) {
-                let variation = variation.unwrap_artifacts_or(self.artifacts);
-
-                let entry = (Some(tag), variation);
 
-                return Ok(entry);
-            }
-        }
-
-        let variation = Variation {
-            url: self.url,
-            sha256: self.sha256,
-            artifacts: self.artifacts,
-        };
-
-        let entry = (None, variation);
-
-        Ok(entry)
+    pub(super) fn variation_url(&self) -> &str {
+        &self.variation_url
     }
 
-    #[cfg(target_os = "macos")]
-    fn variation_tag(&self, context: &Context) -> anyhow::Result<Option<String>> {
-        use crate::util::macos::tag::{Tag, TagError};
-
-        let current_macos_tag = Tag::try_default(context)?;
-
-        #[cfg(debug_assertions)]
-        let tagged_candidate_macos_tags = self
-            .variations
-            .keys()
-            .filter_map(|tag| {
-                let macos_tag = match tag.parse::<Tag>() {
-                    Ok(macos_tag) => macos_tag,
-                    Err(TagError::Unsupported) => return None,
-                    Err(TagError::Other(err)) => return Some(Err(err)),
-                };
-
-                Some(Ok((tag, macos_tag)))
-            })
-            .try_collect::<Vec<_>>()?;
-
-        #[cfg(not(debug_assertions))]
-        let tagged_candidate_macos_tags = self
-            .variations
-            .keys()
-            .filter_map(|tag| {
-                let macos_tag = match tag.parse::<Tag>() {
-                    Ok(macos_tag) => macos_tag,
-                    Err(TagError::Unsupported) => return None,
-                    Err(TagError::Other(err)) => return Some(Err(err)),
-                };
-
-                Some(Ok((tag, macos_tag)))
-            })
-            .collect::<anyhow::Result<Vec<_>>>()?;
-
-        let tag = tagged_candidate_macos_tags
-            .into_iter()
-            .filter(|(_, candidate_macos_tag)| {
-                let is_macos_architecture_equal =
-                    candidate_macos_tag.architecture() == current_macos_tag.architecture();
-
-                is_macos_architecture_equal && candidate_macos_tag <= &current_macos_tag
-            })
-            .max_by(|(_, left), (_, right)| left.cmp(right))
-            .map(|(tag, _)| tag.to_owned());
-
-        let Some(tag) = tag else {
-            return Ok(None);
-        };
-
-        Ok(Some(tag))
+    pub(super) fn variation_sha256(&self) -> &str {
+        &self.variation_sha256
     }
 
-    #[cfg(target_os = "linux")]
-    #[expect(clippy::unnecessary_wraps)]
-    fn variation_tag(&self, _context: &Context) -> anyhow::Result<Option<String>> {
-        let tag = cfg_select! {
-            target_arch = "aarch64" => "arm64_linux",
-            target_arch = "x86_64" => "x86_64_linux",
-        };
-        let tag = self.variations.contains_key(tag).then(|| tag.to_owned());
-
-        let Some(tag) = tag else {
-            return Ok(None);
-        };
-
-        Ok(Some(tag))
+    pub(crate) fn stanzas(&self) -> &Stanzas {
+        &self.variation_stanzas
     }
-}
-
-impl Variation {
-    fn unwrap_artifacts_or(self, default: Vec<Artifact>) -> Variation<Vec<Artifact>> {
-        #[cfg(debug_assertions)]
-        let this = Variation {
-            artifacts: self.artifacts.unwrap_or(default),
-
-            ..self
-        };
-
-        #[cfg(not(debug_assertions))]
-        let this = Variation {
-            url: self.url,
-            sha256: self.sha256,
-            artifacts: self.artifacts.unwrap_or(default),
-        };
 
-        this
+    pub(in super::super) fn into_stanzas(self) -> Stanzas {
+        self.variation_stanzas
     }
 }
@@ -1,19 +1,25 @@
-use std::path::{Path, PathBuf};
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use anyhow::Context as _;
 use base16ct::HexDisplay;
-use bytes::Bytes;
-use futures::stream::{BoxStream, StreamExt as _, TryStreamExt as _};
+use path_clean::PathClean as _;
 use sha2::{Digest as _, Sha256};
+use tokio::fs;
 use url::Url;
 
 use super::{
     super::{super::PackageExt as _, cask::PreparedCask},
     DownloadInnerExt,
+    resume::{self, Fetcher},
 };
 use crate::{
-    context::{Context, dirs::ProjectDirs as _},
-    ext::std::path::PathExt as _,
+    context::{Context, dirs::ProjectDirs as _, mirrors},
+    ext::{std::path::PathExt as _, tokio::path::PathExt as _},
+    store::RefKind,
     util::archive_format::{ArchiveFormat, ArchiveFormatError},
 };
 
@@ -30,17 +36,29 @@ async fn file_name_file_path_link_path(
 
         let url = self.variation_url();
 
-        let resp = context.client.get(url).send().await?;
-        let resp = resp.error_for_status()?;
+        let url = if context.config.offline {
+            Cow::Borrowed(url)
+        } else {
+            let urls = context.mirrors.artifact_urls(url);
+
+            let credentials = &context.credentials;
+
+            let resp =
+                mirrors::send_with_fallback(credentials, &urls, |url| context.client.get(url))
+                    .await?;
+            let resp = resp.error_for_status()?;
 
-        let url = resp.url();
-        let url = url.as_str();
+            let url = resp.url();
+            let url = url.as_str();
+
+            Cow::Owned(url.to_owned())
+        };
 
-        let url_hash = Sha256::digest(url);
+        let url_hash = Sha256::digest(url.as_bytes());
         let url_hash = HexDisplay(&url_hash);
         let url_hash = format!("{url_hash:x}");
 
-        let url = Url::parse(url)?;
+        let url = Url::parse(&url)?;
 
         let mut url_name = url.path_segments().context("Invalid URL")?;
         let url_name = url_name.next_back().context("Empty path segments")?;
@@ -54,7 +72,7 @@ async fn file_name_file_path_link_path(
 
         let file_name = format!("{url_hash}--{url_name}");
 
-        let file_path = cache_dir_path.join("downloads").join(file_name);
+        let mut file_path = cache_dir_path.join("downloads").join(file_name);
 
         let link_name = match url_compound_extension {
             Some(url_compound_extension) => {
@@ -69,6 +87,14 @@ async fn file_name_file_path_link_path(
 
         let link_path = cache_dir_path.join("Cask").join(link_name);
 
+        if context.config.offline && link_path.is_link_exists_nofollow().await? {
+            let link_file_path = fs::read_link(&link_path).await?;
+
+            let link_base_path = link_path.base()?;
+
+            file_path = link_base_path.join(link_file_path).clean();
+        }
+
         Ok((url_name, file_path, link_path))
     }
 
@@ -76,6 +102,14 @@ fn expected_sha256(&self) -> &str {
         self.variation_sha256()
     }
 
+    fn ref_link_path(&self, context: &Context) -> PathBuf {
+        let id = self.id();
+
+        let version = self.version();
+
+        context.store.ref_link(RefKind::Cask, id, version)
+    }
+
     fn archive_format(&self, file_name: &str) -> anyhow::Result<Option<ArchiveFormat>> {
         let archive_format = match ArchiveFormat::try_from(file_name) {
             Ok(archive_format) => archive_format,
@@ -86,21 +120,24 @@ fn archive_format(&self, file_name: &str) -> anyhow::Result<Option<ArchiveFormat
         Ok(Some(archive_format))
     }
 
-    async fn fetch_stream_content_length(
-        &self,
-        context: &Context,
-    ) -> anyhow::Result<(BoxStream<'static, anyhow::Result<Bytes>>, Option<u64>)> {
+    fn fetcher(&self, context: &Context) -> anyhow::Result<Fetcher> {
         let url = self.variation_url();
 
-        let resp = context.client.get(url).send().await?;
-        let resp = resp.error_for_status()?;
+        let urls = context.mirrors.artifact_urls(url);
+
+        let fetchers = urls
+            .into_iter()
+            .map(|url| {
+                let client = context.client.clone();
+
+                let credentials = Arc::clone(&context.credentials);
 
-        let content_length = resp.content_length();
+                resume::http_fetcher(client, credentials, url)
+            })
+            .collect();
 
-        let stream = resp.bytes_stream();
-        let stream = stream.err_into();
-        let stream = stream.boxed();
+        let fetcher = resume::fallback_fetcher(fetchers);
 
-        Ok((stream, content_length))
+        Ok(fetcher)
     }
 }
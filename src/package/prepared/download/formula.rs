@@ -1,22 +1,30 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, sync::Arc};
 
 use anyhow::Context as _;
 use base16ct::HexDisplay;
-use bytes::Bytes;
-use futures::stream::{BoxStream, StreamExt as _, TryStreamExt as _};
-use oci_client::{Reference, manifest::OciDescriptor, secrets::RegistryAuth};
+use futures::{
+    future::FutureExt as _,
+    stream::{StreamExt as _, TryStreamExt as _},
+};
+use oci_client::{Reference, client::BlobResponse, manifest::OciDescriptor};
 use sha2::{Digest as _, Sha256};
 use url::Url;
 
 use super::{
     super::{super::PackageExt as _, formula::PreparedFormula},
     DownloadInnerExt,
+    resume::{self, Fetched, Fetcher},
 };
 use crate::{
     context::{Context, dirs::ProjectDirs as _},
+    store::RefKind,
     util::archive_format::ArchiveFormat,
 };
 
+const OCI_REGISTRY_URL: &str = "ghcr.io";
+
+const OCI_URL_PREFIX: &str = "https://ghcr.io/v2/";
+
 impl DownloadInnerExt for PreparedFormula {
     fn url(&self) -> &str {
         self.bottle_url()
@@ -31,10 +39,6 @@ async fn file_name_file_path_link_path(
 
         let version_revision = self.version_revision();
 
-        let bottle_rebuild = self.bottle_rebuild();
-
-        let bottle_tag = self.bottle_tag();
-
         let url = self.bottle_url();
 
         let url_hash = Sha256::digest(url);
@@ -49,11 +53,9 @@ async fn file_name_file_path_link_path(
 
         let cache_dir_path = context.homebrew_dirs.cache_dir();
 
-        let file_name = format!("{url_hash}--{id}--{version_revision}.{bottle_tag}.bottle");
-        let file_name = match bottle_rebuild {
-            0 => format!("{file_name}.tar.gz"),
-            bottle_rebuild => format!("{file_name}.{bottle_rebuild}.tar.gz"),
-        };
+        let bottle_file_name = self.bottle_file_name();
+
+        let file_name = format!("{url_hash}--{bottle_file_name}");
 
         let file_path = cache_dir_path.join("downloads").join(file_name);
 
@@ -68,25 +70,76 @@ fn expected_sha256(&self) -> &str {
         self.bottle_sha256()
     }
 
+    fn ref_link_path(&self, context: &Context) -> PathBuf {
+        let id = self.id();
+
+        let version_revision = self.version_revision();
+
+        context
+            .store
+            .ref_link(RefKind::Formula, id, version_revision)
+    }
+
     fn archive_format(&self, _file_name: &str) -> anyhow::Result<Option<ArchiveFormat>> {
         let archive_format = ArchiveFormat::TarGzip;
 
         Ok(Some(archive_format))
     }
 
-    async fn fetch_stream_content_length(
-        &self,
-        context: &Context,
-    ) -> anyhow::Result<(BoxStream<'static, anyhow::Result<Bytes>>, Option<u64>)> {
-        const OCI_REGISTRY_URL: &str = "ghcr.io";
+    fn fetcher(&self, context: &Context) -> anyhow::Result<Fetcher> {
+        let url = self.bottle_url();
 
-        let registry = OCI_REGISTRY_URL;
+        let bottle_file_name = self.bottle_file_name();
 
-        let url = self.bottle_url();
+        let urls = context.mirrors.bottle_urls(url, &bottle_file_name);
 
-        let url_prefix = format!("https://{registry}/v2/");
+        let fetchers = urls
+            .into_iter()
+            .map(|url| {
+                if url.starts_with(OCI_URL_PREFIX) {
+                    return self.oci_fetcher(context, &url);
+                }
 
-        let url_postfix = url.strip_prefix(&url_prefix).context("Invalid OCI URL")?;
+                let client = context.client.clone();
+
+                let credentials = Arc::clone(&context.credentials);
+
+                let fetcher = resume::http_fetcher(client, credentials, url);
+
+                Ok(fetcher)
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let fetcher = resume::fallback_fetcher(fetchers);
+
+        Ok(fetcher)
+    }
+}
+
+impl PreparedFormula {
+    fn bottle_file_name(&self) -> String {
+        let id = self.id();
+
+        let version_revision = self.version_revision();
+
+        let bottle_rebuild = self.bottle_rebuild();
+
+        let bottle_tag = self.bottle_tag();
+
+        let bottle_file_name = format!("{id}--{version_revision}.{bottle_tag}.bottle");
+
+        match bottle_rebuild {
+            0 => format!("{bottle_file_name}.tar.gz"),
+            bottle_rebuild => format!("{bottle_file_name}.{bottle_rebuild}.tar.gz"),
+        }
+    }
+
+    fn oci_fetcher(&self, context: &Context, url: &str) -> anyhow::Result<Fetcher> {
+        let registry = OCI_REGISTRY_URL;
+
+        let url_postfix = url
+            .strip_prefix(OCI_URL_PREFIX)
+            .context("Invalid OCI blob URL")?;
 
         let (repository, _) = url_postfix
             .split_once("/blobs/")
@@ -105,22 +158,58 @@ async fn fetch_stream_content_length(
             ..OciDescriptor::default()
         };
 
-        context
-            .oci_client
-            .store_auth_if_needed(registry, &RegistryAuth::Anonymous)
-            .await;
+        let oci_client = context.oci_client.clone();
+
+        let credentials = Arc::clone(&context.credentials);
+
+        let fetcher: Fetcher = Arc::new(move |offset| {
+            let oci_client = oci_client.clone();
+
+            let credentials = Arc::clone(&credentials);
+
+            let reference = reference.clone();
+
+            let descriptor = descriptor.clone();
+
+            async move {
+                let registry_auth = credentials.registry_auth(registry).await?;
+
+                oci_client
+                    .store_auth_if_needed(registry, &registry_auth)
+                    .await;
+
+                let (sized_stream, is_partial) = if offset == 0 {
+                    let sized_stream = oci_client.pull_blob_stream(&reference, &descriptor).await?;
+
+                    (sized_stream, false)
+                } else {
+                    let blob_response = oci_client
+                        .pull_blob_stream_partial(&reference, &descriptor, offset, None)
+                        .await?;
+
+                    match blob_response {
+                        BlobResponse::Full(sized_stream) => (sized_stream, false),
+                        BlobResponse::Partial(sized_stream) => (sized_stream, true),
+                    }
+                };
+
+                let content_length = sized_stream.content_length;
 
-        let sized_stream = context
-            .oci_client
-            .pull_blob_stream(&reference, &descriptor)
-            .await?;
+                let stream = sized_stream.stream;
+                let stream = stream.err_into();
+                let stream = stream.boxed();
 
-        let content_length = sized_stream.content_length;
+                let fetched = Fetched {
+                    stream,
+                    content_length,
+                    is_partial,
+                };
 
-        let stream = sized_stream.stream;
-        let stream = stream.err_into();
-        let stream = stream.boxed();
+                Ok(fetched)
+            }
+            .boxed()
+        });
 
-        Ok((stream, content_length))
+        Ok(fetcher)
     }
 }
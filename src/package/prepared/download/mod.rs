@@ -1,20 +1,22 @@
 mod cask;
 mod formula;
+mod resume;
 
 use std::path::{Path, PathBuf};
 
-use base16ct::HexDisplay;
+use anyhow::anyhow;
 use bytes::Bytes;
 use futures::stream::{BoxStream, StreamExt as _, TryStreamExt as _};
-use sha2::{Digest as _, Sha256};
-use tokio::{fs::File, io};
-use tokio_util::io::{InspectWriter, ReaderStream};
+use tokio::{fs::File, task};
+use tokio_util::{io::ReaderStream, task::AbortOnDropHandle};
 
+use self::resume::Fetcher;
 use super::{PreparedCask, PreparedFormula, PreparedPackage, PreparedPackageExt};
 use crate::{
     context::Context,
-    ext::tokio::{fs::FileExt as _, path::PathExt as _},
-    util::archive_format::ArchiveFormat,
+    ext::tokio::path::PathExt as _,
+    store::Store,
+    util::{archive_format::ArchiveFormat, digest},
 };
 
 pub(crate) struct Download {
@@ -23,6 +25,9 @@ pub(crate) struct Download {
     file_name: String,
     file_path: PathBuf,
     link_path: PathBuf,
+    blob_file_path: PathBuf,
+    incomplete_file_path: PathBuf,
+    ref_link_path: PathBuf,
 
     actual_sha256: Option<String>,
     expected_sha256: String,
@@ -32,6 +37,7 @@ pub(crate) struct Download {
     archive_format: Option<ArchiveFormat>,
 
     content_length: Option<u64>,
+    resume_offset: u64,
 }
 
 impl Download {
@@ -47,6 +53,18 @@ pub(crate) fn link_path(&self) -> &Path {
         &self.link_path
     }
 
+    pub(crate) fn blob_file_path(&self) -> &Path {
+        &self.blob_file_path
+    }
+
+    pub(crate) fn incomplete_file_path(&self) -> &Path {
+        &self.incomplete_file_path
+    }
+
+    pub(crate) fn ref_link_path(&self) -> &Path {
+        &self.ref_link_path
+    }
+
     pub(crate) fn expected_sha256(&self) -> &str {
         &self.expected_sha256
     }
@@ -62,6 +80,10 @@ pub(crate) fn archive_format(&self) -> Option<ArchiveFormat> {
     pub(crate) fn content_length(&self) -> Option<u64> {
         self.content_length
     }
+
+    pub(crate) fn resume_offset(&self) -> u64 {
+        self.resume_offset
+    }
 }
 
 impl DownloadExt for PreparedPackage {
@@ -101,6 +123,13 @@ fn expected_sha256(&self) -> &str {
         }
     }
 
+    fn ref_link_path(&self, context: &Context) -> PathBuf {
+        match self {
+            Self::Formula(formula) => formula.ref_link_path(context),
+            Self::Cask(cask) => cask.ref_link_path(context),
+        }
+    }
+
     fn archive_format(&self, file_name: &str) -> anyhow::Result<Option<ArchiveFormat>> {
         match self {
             Self::Formula(formula) => formula.archive_format(file_name),
@@ -108,13 +137,10 @@ fn archive_format(&self, file_name: &str) -> anyhow::Result<Option<ArchiveFormat
         }
     }
 
-    async fn fetch_stream_content_length(
-        &self,
-        context: &Context,
-    ) -> anyhow::Result<(BoxStream<'static, anyhow::Result<Bytes>>, Option<u64>)> {
+    fn fetcher(&self, context: &Context) -> anyhow::Result<Fetcher> {
         match self {
-            Self::Formula(formula) => formula.fetch_stream_content_length(context).await,
-            Self::Cask(cask) => cask.fetch_stream_content_length(context).await,
+            Self::Formula(formula) => formula.fetcher(context),
+            Self::Cask(cask) => cask.fetcher(context),
         }
     }
 }
@@ -129,25 +155,54 @@ async fn prepare_download(
 
         let (file_name, file_path, link_path) = self.file_name_file_path_link_path(context).await?;
 
-        let actual_sha256 = self.actual_sha256(&file_path).await?;
-
         let expected_sha256 = self.expected_sha256().to_owned();
 
-        let is_verified = self
-            .is_verified(
-                &file_path,
-                &link_path,
-                actual_sha256.as_deref(),
-                &expected_sha256,
-            )
+        let blob_file_path = context.store.blob_file(&expected_sha256);
+
+        let incomplete_file_path = context.store.incomplete_file(&expected_sha256);
+
+        let ref_link_path = self.ref_link_path(context);
+
+        self.adopt_legacy_file(&file_path, &blob_file_path, &expected_sha256, context)
             .await?;
 
+        let is_recorded_verified = !context.config.verify_cache
+            && Store::is_verified(&blob_file_path, &expected_sha256).await?;
+
+        let actual_sha256 = if is_recorded_verified {
+            Some(expected_sha256.clone())
+        } else {
+            self.actual_sha256(&blob_file_path).await?
+        };
+
+        let is_verified = actual_sha256.as_deref() == Some(expected_sha256.as_str());
+
+        if is_verified && !is_recorded_verified {
+            Store::record_verified(&blob_file_path, &expected_sha256).await?;
+        }
+
+        if context.config.offline && !is_verified {
+            let id = self.id();
+
+            let file_path = file_path.display();
+
+            let err = anyhow!(r#"Package "{id}" has no verified download at "{file_path}""#);
+
+            return Err(err);
+        }
+
         let archive_format = self.archive_format(&file_name)?;
 
-        let (stream, content_length) = if is_verified {
-            self.file_stream_content_length(&file_path).await?
+        let (stream, content_length, resume_offset) = if is_verified {
+            let (stream, content_length) = self.file_stream_content_length(&blob_file_path).await?;
+
+            (stream, content_length, 0)
         } else {
-            self.fetch_stream_content_length(context).await?
+            let fetcher = self.fetcher(context)?;
+
+            let retries = context.config.retries;
+
+            resume::resumable_stream(fetcher, &incomplete_file_path, retries).await?
         };
 
         let download = Download {
@@ -156,6 +211,9 @@ async fn prepare_download(
             file_name,
             file_path,
             link_path,
+            blob_file_path,
+            incomplete_file_path,
+            ref_link_path,
 
             actual_sha256,
             expected_sha256,
@@ -165,10 +223,27 @@ async fn prepare_download(
             archive_format,
 
             content_length,
+            resume_offset,
         };
 
         Ok((download, stream))
     }
+
+    async fn missing_download(&self, context: &Context) -> anyhow::Result<Option<PathBuf>> {
+        let (_, file_path, _) = self.file_name_file_path_link_path(context).await?;
+
+        let blob_file_path = context.store.blob_file(self.expected_sha256());
+
+        let is_blob_file_exists = blob_file_path.is_file_exists_nofollow().await?;
+
+        let is_legacy_file_exists = file_path.is_file_exists_nofollow().await?;
+
+        if is_blob_file_exists || is_legacy_file_exists {
+            return Ok(None);
+        }
+
+        Ok(Some(file_path))
+    }
 }
 
 impl DownloadExt for PreparedFormula {}
@@ -184,48 +259,52 @@ async fn file_name_file_path_link_path(
     ) -> anyhow::Result<(String, PathBuf, PathBuf)>;
 
     async fn actual_sha256(&self, file_path: &Path) -> anyhow::Result<Option<String>> {
-        let Some(mut file) = File::open_if_exists(file_path).await? else {
+        if !file_path.is_file_exists_nofollow().await? {
             return Ok(None);
-        };
+        }
 
-        let mut digest = Sha256::new();
+        let handle = task::spawn_blocking({
+            let file_path = file_path.to_owned();
 
-        let mut sink = InspectWriter::new(io::sink(), |chunk| digest.update(chunk));
+            move || {
+                let sha256 = digest::sha256_file(&file_path)?;
 
-        io::copy(&mut file, &mut sink).await?;
+                anyhow::Ok(sha256)
+            }
+        });
+        let handle = AbortOnDropHandle::new(handle);
 
-        let sha256 = digest.finalize();
-        let sha256 = HexDisplay(&sha256);
-        let sha256 = format!("{sha256:x}");
+        let sha256 = handle.await??;
 
         Ok(Some(sha256))
     }
 
     fn expected_sha256(&self) -> &str;
 
-    async fn is_verified(
+    fn ref_link_path(&self, context: &Context) -> PathBuf;
+
+    async fn adopt_legacy_file(
         &self,
         file_path: &Path,
-        link_path: &Path,
-        actual_sha256: Option<&str>,
+        blob_file_path: &Path,
         expected_sha256: &str,
-    ) -> anyhow::Result<bool> {
-        let is_file_exists = file_path.is_file_exists_nofollow().await?;
-
-        let is_link_exists = link_path.is_link_exists_nofollow().await?;
-
-        let is_link_valid =
-            link_path.realpath_or_none().await? == file_path.realpath_or_none().await?;
+        context: &Context,
+    ) -> anyhow::Result<()> {
+        if blob_file_path.is_file_exists_nofollow().await?
+            || !file_path.is_file_exists_nofollow().await?
+        {
+            return Ok(());
+        }
 
-        let Some(actual_sha256) = actual_sha256 else {
-            return Ok(false);
-        };
+        let actual_sha256 = self.actual_sha256(file_path).await?;
 
-        let is_sha256_equal = actual_sha256 == expected_sha256;
+        if actual_sha256.as_deref() != Some(expected_sha256) {
+            return Ok(());
+        }
 
-        let is_verified = is_file_exists && is_link_exists && is_link_valid && is_sha256_equal;
+        context.store.adopt(file_path, expected_sha256).await?;
 
-        Ok(is_verified)
+        Ok(())
     }
 
     fn archive_format(&self, file_name: &str) -> anyhow::Result<Option<ArchiveFormat>>;
@@ -247,8 +326,5 @@ async fn file_stream_content_length(
         Ok((stream, Some(content_length)))
     }
 
-    async fn fetch_stream_content_length(
-        &self,
-        context: &Context,
-    ) -> anyhow::Result<(BoxStream<'static, anyhow::Result<Bytes>>, Option<u64>)>;
+    fn fetcher(&self, context: &Context) -> anyhow::Result<Fetcher>;
 }
@@ -0,0 +1,269 @@
+use std::{path::Path, sync::Arc, time::Duration};
+
+use anyhow::{Context as _, anyhow};
+use bytes::Bytes;
+use futures::{
+    future::{BoxFuture, FutureExt as _},
+    stream::{self, BoxStream, StreamExt as _, TryStreamExt as _},
+};
+use reqwest::{StatusCode, header};
+use tokio::{fs::File, io::AsyncReadExt as _, time};
+use tokio_util::io::ReaderStream;
+
+use crate::{context::credentials::Credentials, ext::tokio::fs::FileExt as _};
+
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+pub(super) struct Fetched {
+    pub(super) stream: BoxStream<'static, anyhow::Result<Bytes>>,
+    pub(super) content_length: Option<u64>,
+    pub(super) is_partial: bool,
+}
+
+pub(super) type Fetcher =
+    Arc<dyn Fn(u64) -> BoxFuture<'static, anyhow::Result<Fetched>> + Send + Sync>;
+
+pub(super) fn http_fetcher(
+    client: reqwest::Client,
+    credentials: Arc<Credentials>,
+    url: String,
+) -> Fetcher {
+    Arc::new(move |offset| {
+        let client = client.clone();
+
+        let credentials = Arc::clone(&credentials);
+
+        let url = url.clone();
+
+        async move {
+            let mut req = client.get(&url);
+
+            if offset > 0 {
+                req = req.header(header::RANGE, format!("bytes={offset}-"));
+            }
+
+            let req = credentials.authorize(req, &url).await?;
+
+            let mut resp = req.send().await?;
+
+            if resp.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+                let req = client.get(&url);
+                let req = credentials.authorize(req, &url).await?;
+
+                resp = req.send().await?;
+            }
+
+            let resp = resp.error_for_status()?;
+
+            let is_partial = resp.status() == StatusCode::PARTIAL_CONTENT;
+
+            let content_length = resp.content_length();
+
+            let stream = resp.bytes_stream();
+            let stream = stream.err_into();
+            let stream = stream.boxed();
+
+            let fetched = Fetched {
+                stream,
+                content_length,
+                is_partial,
+            };
+
+            Ok(fetched)
+        }
+        .boxed()
+    })
+}
+
+pub(super) fn fallback_fetcher(fetchers: Vec<Fetcher>) -> Fetcher {
+    let fetchers = Arc::new(fetchers);
+
+    Arc::new(move |offset| {
+        let fetchers = Arc::clone(&fetchers);
+
+        async move {
+            let (origin_fetcher, mirror_fetchers) =
+                fetchers.split_last().context("No URL to download")?;
+
+            for mirror_fetcher in mirror_fetchers {
+                if let Ok(fetched) = mirror_fetcher(offset).await {
+                    return Ok(fetched);
+                }
+            }
+
+            origin_fetcher(offset).await
+        }
+        .boxed()
+    })
+}
+
+struct Resumption {
+    fetcher: Fetcher,
+
+    stream: Option<BoxStream<'static, anyhow::Result<Bytes>>>,
+
+    offset: u64,
+    skip_length: u64,
+    total_length: Option<u64>,
+
+    retries: u32,
+    max_retries: u32,
+}
+
+impl Resumption {
+    async fn fetch(&mut self) -> anyhow::Result<()> {
+        loop {
+            let err = match (self.fetcher)(self.offset).await {
+                Ok(fetched) => {
+                    self.accept(fetched);
+
+                    return Ok(());
+                },
+                Err(err) => err,
+            };
+
+            self.back_off(err).await?;
+        }
+    }
+
+    fn accept(&mut self, fetched: Fetched) {
+        self.skip_length = if fetched.is_partial { 0 } else { self.offset };
+
+        if self.total_length.is_none() {
+            self.total_length = if fetched.is_partial {
+                fetched
+                    .content_length
+                    .map(|content_length| content_length.saturating_add(self.offset))
+            } else {
+                fetched.content_length
+            };
+        }
+
+        self.stream = Some(fetched.stream);
+    }
+
+    async fn back_off(&mut self, err: anyhow::Error) -> anyhow::Result<()> {
+        if self.retries >= self.max_retries {
+            let max_retries = self.max_retries;
+
+            let err = err.context(format!("Download failed after {max_retries} retries"));
+
+            return Err(err);
+        }
+
+        let backoff = INITIAL_BACKOFF.saturating_mul(2_u32.saturating_pow(self.retries));
+
+        time::sleep(backoff).await;
+
+        self.retries = self.retries.saturating_add(1);
+
+        Ok(())
+    }
+
+    async fn next_chunk(&mut self) -> anyhow::Result<Option<Bytes>> {
+        loop {
+            let Some(stream) = &mut self.stream else {
+                self.fetch().await?;
+
+                continue;
+            };
+
+            let err = match stream.next().await {
+                Some(Ok(chunk)) => {
+                    self.retries = 0;
+
+                    let Some(chunk) = self.skip(chunk)? else {
+                        continue;
+                    };
+
+                    let chunk_length = u64::try_from(chunk.len())?;
+
+                    self.offset = self.offset.saturating_add(chunk_length);
+
+                    return Ok(Some(chunk));
+                },
+                Some(Err(err)) => err,
+                None => match self.total_length {
+                    Some(total_length) if self.offset < total_length => {
+                        anyhow!("Download ended at {} of {total_length} bytes", self.offset)
+                    },
+                    _ => return Ok(None),
+                },
+            };
+
+            self.stream = None;
+
+            self.back_off(err).await?;
+        }
+    }
+
+    fn skip(&mut self, chunk: Bytes) -> anyhow::Result<Option<Bytes>> {
+        if self.skip_length == 0 {
+            return Ok(Some(chunk));
+        }
+
+        let chunk_length = u64::try_from(chunk.len())?;
+
+        if chunk_length <= self.skip_length {
+            self.skip_length = self.skip_length.saturating_sub(chunk_length);
+
+            return Ok(None);
+        }
+
+        let skip_length = usize::try_from(self.skip_length)?;
+
+        self.skip_length = 0;
+
+        Ok(Some(chunk.slice(skip_length..)))
+    }
+}
+
+pub(super) async fn resumable_stream(
+    fetcher: Fetcher,
+    incomplete_file_path: &Path,
+    max_retries: u32,
+) -> anyhow::Result<(BoxStream<'static, anyhow::Result<Bytes>>, Option<u64>, u64)> {
+    let incomplete_file = File::open_if_exists(incomplete_file_path).await?;
+
+    let offset = match &incomplete_file {
+        Some(incomplete_file) => incomplete_file.metadata().await?.len(),
+        None => 0,
+    };
+
+    let mut resumption = Resumption {
+        fetcher,
+
+        stream: None,
+
+        offset,
+        skip_length: 0,
+        total_length: None,
+
+        retries: 0,
+        max_retries,
+    };
+
+    resumption.fetch().await?;
+
+    let content_length = resumption.total_length;
+
+    let network_stream = stream::try_unfold(resumption, async |mut resumption| {
+        let chunk = resumption.next_chunk().await?;
+
+        let chunk = chunk.map(|chunk| (chunk, resumption));
+
+        anyhow::Ok(chunk)
+    });
+
+    let Some(incomplete_file) = incomplete_file.filter(|_| offset > 0) else {
+        return Ok((network_stream.boxed(), content_length, 0));
+    };
+
+    let incomplete_stream = ReaderStream::new(incomplete_file.take(offset));
+    let incomplete_stream = incomplete_stream.err_into();
+
+    let stream = incomplete_stream.chain(network_stream);
+    let stream = stream.boxed();
+
+    Ok((stream, content_length, offset))
+}
@@ -1,28 +1,30 @@
-use std::{
-    borrow::Cow,
-    path::{Path, PathBuf},
-};
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context as _, anyhow};
 use bytes::Bytes;
 use futures::stream::BoxStream;
-use tokio::fs;
 
 use super::{
     super::{
         PackageExt,
-        raw::formula::{BottleStable, BottleStableFile, BottleStableFileCellar},
+        installed::formula::InstalledFormula,
+        raw::{
+            self,
+            formula::{BottleStable, BottleStableFile, BottleStableFileCellar},
+        },
         resolved::formula::ResolvedFormula,
     },
     PreparedPackageExt,
     download::{Download, DownloadExt as _},
 };
-use crate::{context::Context, ext::tokio::path::PathExt as _};
+use crate::{context::Context, receipt::formula::RuntimeDependency};
 
 pub(crate) struct PreparedFormula<Dl = ()> {
     pub(in super::super) name: String,
+    tap: String,
     pub(in super::super) version: String,
     version_revision: String,
+    version_scheme: u64,
     bottle_rebuild: u64,
     bottle_tag: String,
     bottle_cellar: BottleStableFileCellar,
@@ -31,6 +33,7 @@ pub(crate) struct PreparedFormula<Dl = ()> {
     keg_only: bool,
     is_compatible: bool,
     pub(in super::super) is_requested: bool,
+    runtime_dependencies: Vec<RuntimeDependency>,
 
     download: Dl,
 }
@@ -56,8 +59,10 @@ fn try_from(
 
         let this = Self {
             name: resolved_formula.name,
+            tap: resolved_formula.tap,
             version: resolved_formula.versions.stable,
             version_revision,
+            version_scheme: resolved_formula.version_scheme,
             bottle_rebuild,
             bottle_tag,
             bottle_cellar: bottle.cellar,
@@ -66,6 +71,7 @@ fn try_from(
             keg_only: resolved_formula.keg_only,
             is_compatible: resolved_formula.is_compatible.into_inner(),
             is_requested: resolved_formula.is_requested.into_inner(),
+            runtime_dependencies: resolved_formula.runtime_dependencies,
 
             download: (),
         };
@@ -78,8 +84,10 @@ impl<Dl> From<(PreparedFormula<()>, Dl)> for PreparedFormula<Dl> {
     fn from((this, download): (PreparedFormula<()>, Dl)) -> Self {
         Self {
             name: this.name,
+            tap: this.tap,
             version: this.version,
             version_revision: this.version_revision,
+            version_scheme: this.version_scheme,
             bottle_rebuild: this.bottle_rebuild,
             bottle_tag: this.bottle_tag,
             bottle_cellar: this.bottle_cellar,
@@ -88,6 +96,7 @@ fn from((this, download): (PreparedFormula<()>, Dl)) -> Self {
             keg_only: this.keg_only,
             is_compatible: this.is_compatible,
             is_requested: this.is_requested,
+            runtime_dependencies: this.runtime_dependencies,
 
             download,
         }
@@ -130,27 +139,9 @@ fn is_compatible(&self) -> bool {
     async fn is_installed(&self, context: &Context) -> anyhow::Result<bool> {
         let id = self.id();
 
-        let rack_dir_path = context.homebrew_dirs.rack_dir(id);
-
-        if !rack_dir_path.is_dir_exists_nofollow().await? {
-            return Ok(false);
-        }
-
-        let mut rack_dir_entries = fs::read_dir(rack_dir_path).await?;
-
-        while let Some(rack_dir_entry) = rack_dir_entries.next_entry().await? {
-            let rack_dir_entry_path = rack_dir_entry.path();
+        let installed_formulae = InstalledFormula::load_many(id, context).await?;
 
-            let is_rack_dir_entry_exists = rack_dir_entry_path.is_dir_exists_nofollow().await?;
-
-            let is_rack_dir_entry_not_empty = !rack_dir_entry_path.is_dir_empty().await?;
-
-            if is_rack_dir_entry_exists && is_rack_dir_entry_not_empty {
-                return Ok(true);
-            }
-        }
-
-        Ok(false)
+        Ok(!installed_formulae.is_empty())
     }
 
     async fn is_up_to_date(&self, context: &Context) -> anyhow::Result<bool> {
@@ -158,17 +149,13 @@ async fn is_up_to_date(&self, context: &Context) -> anyhow::Result<bool> {
 
         let version_revision = self.version_revision();
 
-        let keg_dir_path = context.homebrew_dirs.keg_dir(id, version_revision);
-
-        let is_keg_dir_exists = keg_dir_path.is_dir_exists_nofollow().await?;
+        let installed_formulae = InstalledFormula::load_many(id, context).await?;
 
-        let is_keg_dir_not_empty = !keg_dir_path.is_dir_empty().await?;
+        let is_up_to_date = installed_formulae
+            .iter()
+            .any(|installed_formula| installed_formula.version_revision() == version_revision);
 
-        if is_keg_dir_exists && is_keg_dir_not_empty {
-            return Ok(true);
-        }
-
-        Ok(false)
+        Ok(is_up_to_date)
     }
 
     fn download(&self) -> &Self::Download {
@@ -181,10 +168,26 @@ fn extract_dir_path(&self, context: &Context) -> PathBuf {
 }
 
 impl<Dl> PreparedFormula<Dl> {
+    pub(crate) fn tap(&self) -> &str {
+        &self.tap
+    }
+
     pub(crate) fn version_revision(&self) -> &str {
         &self.version_revision
     }
 
+    pub(crate) fn version_scheme(&self) -> u64 {
+        self.version_scheme
+    }
+
+    pub(crate) fn is_requested(&self) -> bool {
+        self.is_requested
+    }
+
+    pub(crate) fn runtime_dependencies(&self) -> &[RuntimeDependency] {
+        &self.runtime_dependencies
+    }
+
     pub(super) fn bottle_rebuild(&self) -> u64 {
         self.bottle_rebuild
     }
@@ -216,21 +219,6 @@ pub(crate) fn should_link_keg(&self) -> bool {
     }
 }
 
-impl ResolvedFormula {
-    fn version_revision(&self) -> Cow<'_, str> {
-        let version = &self.versions.stable;
-
-        match self.revision {
-            0 => Cow::Borrowed(version),
-            revision => {
-                let version_revision = format!("{version}_{revision}");
-
-                Cow::Owned(version_revision)
-            },
-        }
-    }
-}
-
 impl BottleStable {
     fn entry(mut self, context: &Context) -> anyhow::Result<Option<(String, BottleStableFile)>> {
         let Some(tag) = self.tag(context)? else {
@@ -245,68 +233,8 @@ fn entry(mut self, context: &Context) -> anyhow::Result<Option<(String, BottleSt
         Ok(Some(entry))
     }
 
-    #[cfg(target_os = "macos")]
-    fn tag(&self, context: &Context) -> anyhow::Result<Option<String>> {
-        use crate::util::macos::tag::{Tag, TagError};
-
-        let current_macos_tag = Tag::try_default(context)?;
-
-        #[cfg(debug_assertions)]
-        let tagged_candidate_macos_tags = self
-            .files
-            .keys()
-            .filter_map(|tag| {
-                let macos_tag = match tag.parse::<Tag>() {
-                    Ok(macos_tag) => macos_tag,
-                    Err(TagError::Unsupported) => return None,
-                    Err(TagError::Other(err)) => return Some(Err(err)),
-                };
-
-                Some(Ok((tag, macos_tag)))
-            })
-            .try_collect::<Vec<_>>()?;
-
-        #[cfg(not(debug_assertions))]
-        let tagged_candidate_macos_tags = self
-            .files
-            .keys()
-            .filter_map(|tag| {
-                let macos_tag = match tag.parse::<Tag>() {
-                    Ok(macos_tag) => macos_tag,
-                    Err(TagError::Unsupported) => return None,
-                    Err(TagError::Other(err)) => return Some(Err(err)),
-                };
-
-                Some(Ok((tag, macos_tag)))
-            })
-            .collect::<anyhow::Result<Vec<_>>>()?;
-
-        let tag = tagged_candidate_macos_tags
-            .into_iter()
-            .filter(|(_, candidate_macos_tag)| {
-                let is_macos_architecture_equal =
-                    candidate_macos_tag.architecture() == current_macos_tag.architecture();
-
-                is_macos_architecture_equal && candidate_macos_tag <= &current_macos_tag
-            })
-            .max_by(|(_, left), (_, right)| left.cmp(right))
-            .map(|(tag, _)| tag.to_owned());
-
-        let Some(tag) = tag.or_else(|| self.tag_or_else()) else {
-            return Ok(None);
-        };
-
-        Ok(Some(tag))
-    }
-
-    #[cfg(target_os = "linux")]
-    #[expect(clippy::unnecessary_wraps)]
-    fn tag(&self, _context: &Context) -> anyhow::Result<Option<String>> {
-        let tag = cfg_select! {
-            target_arch = "aarch64" => "arm64_linux",
-            target_arch = "x86_64" => "x86_64_linux",
-        };
-        let tag = self.files.contains_key(tag).then(|| tag.to_owned());
+    pub(in super::super) fn tag(&self, context: &Context) -> anyhow::Result<Option<String>> {
+        let tag = raw::select_tag(self.files.keys(), context)?;
 
         let Some(tag) = tag.or_else(|| self.tag_or_else()) else {
             return Ok(None);
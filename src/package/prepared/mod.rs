@@ -9,7 +9,11 @@
 use bytes::Bytes;
 use futures::stream::BoxStream;
 
-use self::{cask::PreparedCask, download::Download, formula::PreparedFormula};
+use self::{
+    cask::PreparedCask,
+    download::{Download, DownloadExt as _},
+    formula::PreparedFormula,
+};
 use super::{PackageExt, resolved::ResolvedPackage};
 use crate::context::Context;
 
@@ -73,6 +77,53 @@ pub(crate) async fn with_download(
             },
         }
     }
+
+    pub(crate) async fn missing_download(
+        &self,
+        context: &Context,
+    ) -> anyhow::Result<Option<PathBuf>> {
+        match self {
+            Self::Formula(formula) => formula.missing_download(context).await,
+            Self::Cask(cask) => cask.missing_download(context).await,
+        }
+    }
+
+    pub(crate) fn direct_dependencies(&self) -> Box<dyn Iterator<Item = &str> + '_> {
+        match self {
+            Self::Formula(formula) => {
+                let dependencies = formula
+                    .runtime_dependencies()
+                    .iter()
+                    .filter(|dependency| dependency.declared_directly)
+                    .map(|dependency| Self::short_name(&dependency.full_name));
+
+                Box::new(dependencies)
+            },
+            Self::Cask(cask) => {
+                let runtime_dependencies = cask.runtime_dependencies();
+
+                let formula_dependencies = runtime_dependencies
+                    .formula
+                    .iter()
+                    .filter(|dependency| dependency.declared_directly)
+                    .map(|dependency| Self::short_name(&dependency.full_name));
+
+                let cask_dependencies = runtime_dependencies
+                    .cask
+                    .iter()
+                    .filter(|dependency| dependency.declared_directly)
+                    .map(|dependency| Self::short_name(&dependency.full_name));
+
+                Box::new(formula_dependencies.chain(cask_dependencies))
+            },
+        }
+    }
+
+    fn short_name(full_name: &str) -> &str {
+        full_name
+            .rsplit_once('/')
+            .map_or(full_name, |(_, short_name)| short_name)
+    }
 }
 
 impl<Dl> PackageExt for PreparedPackage<Dl>
@@ -7,18 +7,32 @@
 use serde_with::{BoolFromInt, FromInto, serde_as};
 
 use super::{super::PackageExt, RawPackageExt};
-use crate::{ext::serde::true_on_absent, util::macos::codename::Codename};
+use crate::{
+    context::Context,
+    ext::serde::true_on_absent,
+    registries,
+    util::macos::codename::Codename,
+};
 
 #[derive(Deserialize)]
 pub(crate) struct RawCask {
     pub(in super::super) token: String,
+    pub(in super::super) tap: String,
     pub(in super::super) version: String,
     pub(in super::super) url: String,
     pub(in super::super) sha256: String,
     pub(in super::super) artifacts: Vec<Artifact>,
-    pub(in super::super) variations: HashMap<String, Variation>,
+    #[serde(skip)]
+    pub(in super::super) variation_tag: Option<String>,
 
+    name: Vec<String>,
+    desc: Option<String>,
+    homepage: Option<String>,
+    caveats: Option<String>,
+    ruby_source_path: Option<String>,
     depends_on: DependsOn,
+    #[serde(default)]
+    variations: HashMap<String, Variation>,
 }
 
 impl PackageExt for RawCask {
@@ -34,6 +48,82 @@ fn version(&self) -> &str {
 impl RawPackageExt for RawCask {}
 
 impl RawCask {
+    pub(crate) async fn load_cached(
+        tap: Option<&str>,
+        id: &str,
+        context: &Context,
+    ) -> anyhow::Result<Option<Self>> {
+        let json_id = registries::qualify_cask(tap, id);
+
+        let json_file_path = context.homebrew_dirs.cask_json_file(&json_id);
+
+        let Some(mut this) = super::load_json::<Self>(&json_file_path).await? else {
+            return Ok(None);
+        };
+
+        this.apply_variation(context)?;
+
+        Ok(Some(this))
+    }
+
+    pub(crate) fn apply_variation(&mut self, context: &Context) -> anyhow::Result<()> {
+        let Some(tag) = super::select_variation_tag(self.variations.keys(), context)? else {
+            return Ok(());
+        };
+
+        let Some(variation) = self.variations.remove(&tag) else {
+            return Ok(());
+        };
+
+        if let Some(url) = variation.url {
+            self.url = url;
+        }
+
+        if let Some(sha256) = variation.sha256 {
+            self.sha256 = sha256;
+        }
+
+        if let Some(artifacts) = variation.artifacts {
+            self.artifacts = artifacts;
+        }
+
+        if let Some(caveats) = variation.caveats {
+            self.caveats = Some(caveats);
+        }
+
+        if let Some(depends_on) = variation.depends_on {
+            self.depends_on = depends_on;
+        }
+
+        self.variation_tag = Some(tag);
+
+        Ok(())
+    }
+
+    pub(crate) fn tap(&self) -> &str {
+        &self.tap
+    }
+
+    pub(crate) fn names(&self) -> &[String] {
+        &self.name
+    }
+
+    pub(crate) fn desc(&self) -> Option<&str> {
+        self.desc.as_deref()
+    }
+
+    pub(crate) fn homepage(&self) -> Option<&str> {
+        self.homepage.as_deref()
+    }
+
+    pub(crate) fn caveats(&self) -> Option<&str> {
+        self.caveats.as_deref()
+    }
+
+    pub(crate) fn ruby_source_path(&self) -> Option<&str> {
+        self.ruby_source_path.as_deref()
+    }
+
     pub(crate) fn depends_on(&self) -> &DependsOn {
         &self.depends_on
     }
@@ -246,10 +336,12 @@ pub(in super::super) struct ArtifactGenerateCompletionsFromExecutableSourceOptio
 pub(in super::super) struct ArtifactStageOnlySource(pub(in super::super) (bool,));
 
 #[derive(Deserialize)]
-pub(in super::super) struct Variation<Artifacts = Option<Vec<Artifact>>> {
-    pub(in super::super) url: String,
-    pub(in super::super) sha256: String,
-    pub(in super::super) artifacts: Artifacts,
+struct Variation {
+    url: Option<String>,
+    sha256: Option<String>,
+    artifacts: Option<Vec<Artifact>>,
+    caveats: Option<String>,
+    depends_on: Option<DependsOn>,
 }
 
 #[derive(Deserialize)]
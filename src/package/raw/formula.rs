@@ -1,26 +1,153 @@
-use std::{collections::HashMap, path::PathBuf, str::FromStr};
+use std::{collections::HashMap, path::PathBuf, slice, str::FromStr};
 
-use serde::{Deserialize, de::IgnoredAny};
+use serde::{Deserialize, Serialize, de::IgnoredAny};
 use serde_with::DeserializeFromStr;
 
 use super::{super::PackageExt, RawPackageExt};
-use crate::util::macos::codename::Codename;
+use crate::{context::Context, registries, util::macos::codename::Codename};
 
 #[derive(Deserialize)]
 pub(crate) struct RawFormula {
     pub(in super::super) name: String,
+    pub(in super::super) tap: String,
     pub(in super::super) versions: Versions,
     pub(in super::super) revision: u64,
+    pub(in super::super) version_scheme: u64,
     pub(in super::super) bottle: Bottle,
     pub(in super::super) keg_only: bool,
 
+    desc: Option<String>,
+    license: Option<String>,
+    homepage: Option<String>,
+    caveats: Option<String>,
+    ruby_source_path: Option<String>,
     requirements: Vec<Requirement>,
     dependencies: Vec<String>,
+    #[serde(default)]
+    build_dependencies: Vec<String>,
+    #[serde(default)]
+    test_dependencies: Vec<String>,
+    #[serde(default)]
+    recommended_dependencies: Vec<String>,
+    #[serde(default)]
+    optional_dependencies: Vec<String>,
     uses_from_macos: Vec<UseFromMacos>,
     uses_from_macos_bounds: Vec<UseFromMacosBound>,
+    #[serde(default)]
+    variations: HashMap<String, Variation>,
 }
 
 impl RawFormula {
+    pub(crate) async fn load_cached(
+        tap: Option<&str>,
+        id: &str,
+        context: &Context,
+    ) -> anyhow::Result<Option<Self>> {
+        let json_id = registries::qualify_formula(tap, id);
+
+        let json_file_path = context.homebrew_dirs.formula_json_file(&json_id);
+
+        let Some(mut this) = super::load_json::<Self>(&json_file_path).await? else {
+            return Ok(None);
+        };
+
+        this.apply_variation(context)?;
+
+        Ok(Some(this))
+    }
+
+    pub(crate) fn apply_variation(&mut self, context: &Context) -> anyhow::Result<()> {
+        let Some(tag) = super::select_variation_tag(self.variations.keys(), context)? else {
+            return Ok(());
+        };
+
+        let Some(variation) = self.variations.remove(&tag) else {
+            return Ok(());
+        };
+
+        if let Some(caveats) = variation.caveats {
+            self.caveats = Some(caveats);
+        }
+
+        if let Some(requirements) = variation.requirements {
+            self.requirements = requirements;
+        }
+
+        if let Some(dependencies) = variation.dependencies {
+            self.dependencies = dependencies;
+        }
+
+        if let Some(build_dependencies) = variation.build_dependencies {
+            self.build_dependencies = build_dependencies;
+        }
+
+        if let Some(test_dependencies) = variation.test_dependencies {
+            self.test_dependencies = test_dependencies;
+        }
+
+        if let Some(recommended_dependencies) = variation.recommended_dependencies {
+            self.recommended_dependencies = recommended_dependencies;
+        }
+
+        if let Some(optional_dependencies) = variation.optional_dependencies {
+            self.optional_dependencies = optional_dependencies;
+        }
+
+        if let Some(uses_from_macos) = variation.uses_from_macos {
+            let uses_from_macos_bounds = variation.uses_from_macos_bounds.unwrap_or_else(|| {
+                uses_from_macos
+                    .iter()
+                    .map(|_| UseFromMacosBound::default())
+                    .collect()
+            });
+
+            self.uses_from_macos = uses_from_macos;
+            self.uses_from_macos_bounds = uses_from_macos_bounds;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn tap(&self) -> &str {
+        &self.tap
+    }
+
+    pub(crate) fn head_version(&self) -> Option<&str> {
+        self.versions.head.as_deref()
+    }
+
+    pub(crate) fn bottle_tag(&self, context: &Context) -> anyhow::Result<Option<String>> {
+        self.bottle.stable.tag(context)
+    }
+
+    pub(crate) fn is_keg_only(&self) -> bool {
+        self.keg_only
+    }
+
+    pub(crate) fn desc(&self) -> Option<&str> {
+        self.desc.as_deref()
+    }
+
+    pub(crate) fn license(&self) -> Option<&str> {
+        self.license.as_deref()
+    }
+
+    pub(crate) fn homepage(&self) -> Option<&str> {
+        self.homepage.as_deref()
+    }
+
+    pub(crate) fn caveats(&self) -> Option<&str> {
+        self.caveats.as_deref()
+    }
+
+    pub(crate) fn ruby_source_path(&self) -> Option<&str> {
+        self.ruby_source_path.as_deref()
+    }
+
+    pub(crate) fn build_dependencies(&self) -> &[String] {
+        &self.build_dependencies
+    }
+
     pub(crate) fn requirements(&self) -> &[Requirement] {
         &self.requirements
     }
@@ -29,6 +156,22 @@ pub(crate) fn dependencies(&self) -> &[String] {
         &self.dependencies
     }
 
+    pub(crate) fn typed_dependencies(&self) -> impl Iterator<Item = (&str, DependencyType)> {
+        [
+            (&self.dependencies, DependencyType::Runtime),
+            (&self.recommended_dependencies, DependencyType::Recommended),
+            (&self.optional_dependencies, DependencyType::Optional),
+            (&self.build_dependencies, DependencyType::Build),
+            (&self.test_dependencies, DependencyType::Test),
+        ]
+        .into_iter()
+        .flat_map(|(dependencies, dependency_type)| {
+            dependencies
+                .iter()
+                .map(move |dependency| (dependency.as_str(), dependency_type))
+        })
+    }
+
     pub(crate) fn uses_from_macos_bounds(
         &self,
     ) -> impl Iterator<Item = (&UseFromMacos, &UseFromMacosBound)> {
@@ -58,6 +201,20 @@ impl RawPackageExt for RawFormula {}
 #[derive(Deserialize)]
 pub(in super::super) struct Versions {
     pub(in super::super) stable: String,
+    pub(in super::super) head: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Variation {
+    caveats: Option<String>,
+    requirements: Option<Vec<Requirement>>,
+    dependencies: Option<Vec<String>>,
+    build_dependencies: Option<Vec<String>>,
+    test_dependencies: Option<Vec<String>>,
+    recommended_dependencies: Option<Vec<String>>,
+    optional_dependencies: Option<Vec<String>>,
+    uses_from_macos: Option<Vec<UseFromMacos>>,
+    uses_from_macos_bounds: Option<Vec<UseFromMacosBound>>,
 }
 
 #[derive(Deserialize)]
@@ -157,8 +314,11 @@ pub(crate) enum RequirementSpec {
     Head,
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) enum DependencyType {
+    #[default]
+    #[serde(rename = "runtime")]
+    Runtime,
     #[serde(rename = "build")]
     Build,
     #[serde(rename = "test")]
@@ -176,11 +336,36 @@ pub(crate) enum UseFromMacos {
     HashedDependencies(HashMap<String, UseFromMacosDependencyType>),
 }
 
+impl DependencyType {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Runtime => "runtime",
+            Self::Build => "build",
+            Self::Test => "test",
+            Self::Recommended => "recommended",
+            Self::Optional => "optional",
+        }
+    }
+
+    pub(crate) fn is_runtime(self) -> bool {
+        matches!(self, Self::Runtime | Self::Recommended | Self::Optional)
+    }
+}
+
 impl UseFromMacos {
-    pub(crate) fn dependencies(&self) -> Vec<&str> {
+    pub(crate) fn dependencies(&self) -> Vec<(&str, DependencyType)> {
         match self {
-            Self::Dependency(dependency) => vec![dependency],
-            Self::HashedDependencies(_) => Vec::new(),
+            Self::Dependency(dependency) => vec![(dependency, DependencyType::Runtime)],
+            Self::HashedDependencies(hashed_dependencies) => hashed_dependencies
+                .iter()
+                .flat_map(|(dependency, dependency_type)| {
+                    dependency_type
+                        .types()
+                        .iter()
+                        .map(|dependency_type| (dependency.as_str(), *dependency_type))
+                        .collect::<Vec<_>>()
+                })
+                .collect(),
         }
     }
 }
@@ -192,7 +377,16 @@ pub(crate) enum UseFromMacosDependencyType {
     Multiple(Vec<DependencyType>),
 }
 
-#[derive(Deserialize)]
+impl UseFromMacosDependencyType {
+    fn types(&self) -> &[DependencyType] {
+        match self {
+            Self::Single(dependency_type) => slice::from_ref(dependency_type),
+            Self::Multiple(dependency_types) => dependency_types,
+        }
+    }
+}
+
+#[derive(Default, Deserialize)]
 pub(crate) struct UseFromMacosBound {
     #[serde(default)]
     pub(crate) since: Option<Codename>,
@@ -1,10 +1,16 @@
 pub(crate) mod cask;
 pub(crate) mod formula;
 
+use std::path::Path;
+
+use anyhow::Context as _;
 use enum_dispatch::enum_dispatch;
+use serde::de::DeserializeOwned;
+use tokio::{fs, io};
 
 use self::{cask::RawCask, formula::RawFormula};
 use super::PackageExt;
+use crate::context::Context;
 
 #[enum_dispatch]
 pub(crate) enum RawPackage {
@@ -12,5 +18,139 @@ pub(crate) enum RawPackage {
     Cask(RawCask),
 }
 
+impl RawPackage {
+    pub(crate) async fn load_cached(
+        package: &str,
+        context: &Context,
+    ) -> anyhow::Result<Option<Self>> {
+        if let Some(raw_formula) = RawFormula::load_cached(None, package, context).await? {
+            let this = Self::Formula(raw_formula);
+
+            return Ok(Some(this));
+        }
+
+        if let Some(raw_cask) = RawCask::load_cached(None, package, context).await? {
+            let this = Self::Cask(raw_cask);
+
+            return Ok(Some(this));
+        }
+
+        Ok(None)
+    }
+}
+
+async fn load_json<RawPkg: DeserializeOwned>(
+    json_file_path: &Path,
+) -> anyhow::Result<Option<RawPkg>> {
+    let bytes = match fs::read(json_file_path).await {
+        Ok(bytes) => bytes,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+
+    let raw_package = serde_json::from_slice(&bytes).with_context(|| {
+        let json_file_pstr = json_file_path.to_string_lossy();
+
+        format!(r#"Cached JSON "{json_file_pstr}" is malformed"#)
+    })?;
+
+    Ok(Some(raw_package))
+}
+
+#[cfg(target_os = "macos")]
+pub(super) fn select_tag<'a>(
+    tags: impl Iterator<Item = &'a String>,
+    context: &Context,
+) -> anyhow::Result<Option<String>> {
+    use crate::util::macos::tag::{Tag, TagError};
+
+    let current_macos_tag = Tag::try_default(context)?;
+
+    #[cfg(debug_assertions)]
+    let tagged_candidate_macos_tags = tags
+        .filter_map(|tag| {
+            let macos_tag = match tag.parse::<Tag>() {
+                Ok(macos_tag) => macos_tag,
+                Err(TagError::Unsupported) => return None,
+                Err(TagError::Other(err)) => return Some(Err(err)),
+            };
+
+            Some(Ok((tag, macos_tag)))
+        })
+        .try_collect::<Vec<_>>()?;
+
+    #[cfg(not(debug_assertions))]
+    let tagged_candidate_macos_tags = tags
+        .filter_map(|tag| {
+            let macos_tag = match tag.parse::<Tag>() {
+                Ok(macos_tag) => macos_tag,
+                Err(TagError::Unsupported) => return None,
+                Err(TagError::Other(err)) => return Some(Err(err)),
+            };
+
+            Some(Ok((tag, macos_tag)))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let tag = tagged_candidate_macos_tags
+        .into_iter()
+        .filter(|(_, candidate_macos_tag)| {
+            let is_macos_architecture_equal =
+                candidate_macos_tag.architecture() == current_macos_tag.architecture();
+
+            is_macos_architecture_equal && candidate_macos_tag <= &current_macos_tag
+        })
+        .max_by(|(_, left), (_, right)| left.cmp(right))
+        .map(|(tag, _)| tag.to_owned());
+
+    Ok(tag)
+}
+
+#[cfg(target_os = "linux")]
+#[expect(clippy::unnecessary_wraps)]
+pub(super) fn select_tag<'a>(
+    mut tags: impl Iterator<Item = &'a String>,
+    _context: &Context,
+) -> anyhow::Result<Option<String>> {
+    let current_linux_tag = cfg_select! {
+        target_arch = "aarch64" => "arm64_linux",
+        target_arch = "x86_64" => "x86_64_linux",
+    };
+
+    let tag = tags
+        .find(|tag| tag.as_str() == current_linux_tag)
+        .map(ToOwned::to_owned);
+
+    Ok(tag)
+}
+
+#[cfg(target_os = "macos")]
+pub(super) fn select_variation_tag<'a>(
+    tags: impl Iterator<Item = &'a String>,
+    context: &Context,
+) -> anyhow::Result<Option<String>> {
+    use crate::util::macos::tag::{Tag, TagError};
+
+    let current_macos_tag = Tag::try_default(context)?;
+
+    for tag in tags {
+        match tag.parse::<Tag>() {
+            Ok(macos_tag) if macos_tag == current_macos_tag => return Ok(Some(tag.to_owned())),
+            Ok(_) | Err(TagError::Unsupported) => {},
+            Err(TagError::Other(err)) => return Err(err),
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(target_os = "linux")]
+pub(super) fn select_variation_tag<'a>(
+    tags: impl Iterator<Item = &'a String>,
+    context: &Context,
+) -> anyhow::Result<Option<String>> {
+    select_tag(tags, context)
+}
+
 #[enum_dispatch(RawPackage)]
 trait RawPackageExt: PackageExt {}
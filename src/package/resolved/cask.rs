@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::HashSet,
     sync::{
         Arc,
         atomic::{AtomicBool, Ordering},
@@ -9,21 +9,27 @@
 use super::{
     super::{
         PackageExt,
-        raw::cask::{Artifact, RawCask, Variation},
+        raw::{
+            cask::{Artifact, RawCask},
+            formula::DependencyType,
+        },
     },
     ResolvedPackageExt,
     formula::ResolvedFormula,
 };
+use crate::receipt::cask::{RuntimeDependencies, RuntimeDependency};
 
 pub(crate) struct ResolvedCask {
     pub(in super::super) token: String,
+    pub(in super::super) tap: String,
     pub(in super::super) version: String,
     pub(in super::super) url: String,
     pub(in super::super) sha256: String,
     pub(in super::super) artifacts: Vec<Artifact>,
-    pub(in super::super) variations: HashMap<String, Variation>,
+    pub(in super::super) variation_tag: Option<String>,
     pub(in super::super) is_compatible: AtomicBool,
     pub(in super::super) is_requested: AtomicBool,
+    pub(in super::super) runtime_dependencies: RuntimeDependencies,
 
     dependencies: Vec<Arc<Self>>,
     formula_dependencies: Vec<Arc<ResolvedFormula>>,
@@ -37,15 +43,20 @@ fn from(
             Vec<Arc<ResolvedFormula>>,
         ),
     ) -> Self {
+        let runtime_dependencies =
+            Self::collect_runtime_dependencies(&dependencies, &formula_dependencies);
+
         Self {
             token: raw_cask.token,
+            tap: raw_cask.tap,
             version: raw_cask.version,
             url: raw_cask.url,
             sha256: raw_cask.sha256,
             artifacts: raw_cask.artifacts,
-            variations: raw_cask.variations,
+            variation_tag: raw_cask.variation_tag,
             is_compatible: AtomicBool::new(false),
             is_requested: AtomicBool::new(false),
+            runtime_dependencies,
 
             dependencies,
             formula_dependencies,
@@ -74,6 +85,10 @@ fn set_is_requested(&self, is_requested: bool) {
 }
 
 impl ResolvedCask {
+    pub(crate) fn tap(&self) -> &str {
+        &self.tap
+    }
+
     pub(crate) fn dependencies(&self) -> &[Arc<Self>] {
         &self.dependencies
     }
@@ -90,3 +105,75 @@ pub(crate) fn clear_formula_dependencies(&mut self) {
         self.formula_dependencies.clear();
     }
 }
+
+impl ResolvedCask {
+    fn runtime_dependency(&self, declared_directly: bool) -> RuntimeDependency {
+        RuntimeDependency {
+            full_name: self.token.clone(),
+            version: self.version.clone(),
+            declared_directly,
+        }
+    }
+
+    fn collect_runtime_dependencies(
+        dependencies: &[Arc<Self>],
+        formula_dependencies: &[Arc<ResolvedFormula>],
+    ) -> RuntimeDependencies {
+        let direct_runtime_dependencies = dependencies
+            .iter()
+            .map(|dependency| dependency.runtime_dependency(true));
+
+        let indirect_runtime_dependencies = dependencies
+            .iter()
+            .flat_map(|dependency| dependency.runtime_dependencies.cask.iter().cloned())
+            .map(|mut runtime_dependency| {
+                runtime_dependency.declared_directly = false;
+
+                runtime_dependency
+            });
+
+        let mut full_names = HashSet::new();
+
+        let runtime_dependencies = direct_runtime_dependencies
+            .chain(indirect_runtime_dependencies)
+            .filter(|runtime_dependency| full_names.insert(runtime_dependency.full_name.clone()))
+            .collect::<Vec<_>>();
+
+        let direct_formula_runtime_dependencies =
+            formula_dependencies.iter().map(|formula_dependency| {
+                formula_dependency.runtime_dependency(true, DependencyType::Runtime)
+            });
+
+        let indirect_formula_runtime_dependencies = formula_dependencies
+            .iter()
+            .flat_map(|formula_dependency| formula_dependency.runtime_dependencies.iter())
+            .chain(
+                dependencies
+                    .iter()
+                    .flat_map(|dependency| dependency.runtime_dependencies.formula.iter()),
+            )
+            .filter(|formula_runtime_dependency| {
+                formula_runtime_dependency.dependency_type.is_runtime()
+            })
+            .cloned()
+            .map(|mut formula_runtime_dependency| {
+                formula_runtime_dependency.declared_directly = false;
+
+                formula_runtime_dependency
+            });
+
+        let mut formula_full_names = HashSet::new();
+
+        let formula_runtime_dependencies = direct_formula_runtime_dependencies
+            .chain(indirect_formula_runtime_dependencies)
+            .filter(|formula_runtime_dependency| {
+                formula_full_names.insert(formula_runtime_dependency.full_name.clone())
+            })
+            .collect::<Vec<_>>();
+
+        RuntimeDependencies {
+            cask: runtime_dependencies,
+            formula: formula_runtime_dependencies,
+        }
+    }
+}
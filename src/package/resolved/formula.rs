@@ -1,38 +1,58 @@
-use std::sync::{
-    Arc,
-    atomic::{AtomicBool, Ordering},
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
 };
 
 use super::{
     super::{
         PackageExt,
-        raw::formula::{Bottle, RawFormula, Versions},
+        raw::formula::{Bottle, DependencyType, RawFormula, Versions},
     },
     ResolvedPackageExt,
 };
+use crate::receipt::formula::RuntimeDependency;
 
 pub(crate) struct ResolvedFormula {
     pub(in super::super) name: String,
+    pub(in super::super) tap: String,
     pub(in super::super) versions: Versions,
     pub(in super::super) revision: u64,
+    pub(in super::super) version_scheme: u64,
     pub(in super::super) bottle: Bottle,
     pub(in super::super) keg_only: bool,
     pub(in super::super) is_compatible: AtomicBool,
     pub(in super::super) is_requested: AtomicBool,
+    pub(in super::super) runtime_dependencies: Vec<RuntimeDependency>,
 
     dependencies: Vec<Arc<Self>>,
 }
 
-impl From<(RawFormula, Vec<Arc<Self>>)> for ResolvedFormula {
-    fn from((raw_formula, dependencies): (RawFormula, Vec<Arc<Self>>)) -> Self {
+impl From<(RawFormula, Vec<(Arc<Self>, DependencyType)>)> for ResolvedFormula {
+    fn from(
+        (raw_formula, typed_dependencies): (RawFormula, Vec<(Arc<Self>, DependencyType)>),
+    ) -> Self {
+        let runtime_dependencies = Self::collect_runtime_dependencies(&typed_dependencies);
+
+        let dependencies = typed_dependencies
+            .into_iter()
+            .map(|(dependency, _)| dependency)
+            .collect();
+
         Self {
             name: raw_formula.name,
+            tap: raw_formula.tap,
             versions: raw_formula.versions,
             revision: raw_formula.revision,
+            version_scheme: raw_formula.version_scheme,
             bottle: raw_formula.bottle,
             keg_only: raw_formula.keg_only,
             is_compatible: AtomicBool::new(false),
             is_requested: AtomicBool::new(false),
+            runtime_dependencies,
 
             dependencies,
         }
@@ -60,6 +80,10 @@ fn set_is_requested(&self, is_requested: bool) {
 }
 
 impl ResolvedFormula {
+    pub(crate) fn tap(&self) -> &str {
+        &self.tap
+    }
+
     pub(crate) fn dependencies(&self) -> &[Arc<Self>] {
         &self.dependencies
     }
@@ -67,4 +91,77 @@ pub(crate) fn dependencies(&self) -> &[Arc<Self>] {
     pub(crate) fn clear_dependencies(&mut self) {
         self.dependencies.clear();
     }
+
+    pub(crate) fn dependency_type(&self, dependency: &Self) -> DependencyType {
+        self.runtime_dependencies
+            .iter()
+            .find(|runtime_dependency| {
+                runtime_dependency.declared_directly
+                    && runtime_dependency.full_name == dependency.name
+            })
+            .map(|runtime_dependency| runtime_dependency.dependency_type)
+            .unwrap_or_default()
+    }
+}
+
+impl ResolvedFormula {
+    pub(crate) fn version_revision(&self) -> Cow<'_, str> {
+        let version = &self.versions.stable;
+
+        match self.revision {
+            0 => Cow::Borrowed(version),
+            revision => {
+                let version_revision = format!("{version}_{revision}");
+
+                Cow::Owned(version_revision)
+            },
+        }
+    }
+
+    pub(super) fn runtime_dependency(
+        &self,
+        declared_directly: bool,
+        dependency_type: DependencyType,
+    ) -> RuntimeDependency {
+        let version_revision = self.version_revision();
+        let version_revision = version_revision.into_owned();
+
+        RuntimeDependency {
+            full_name: self.name.clone(),
+            version: self.versions.stable.clone(),
+            revision: self.revision,
+            pkg_version: version_revision,
+            declared_directly,
+            dependency_type,
+        }
+    }
+
+    fn collect_runtime_dependencies(
+        typed_dependencies: &[(Arc<Self>, DependencyType)],
+    ) -> Vec<RuntimeDependency> {
+        let direct_runtime_dependencies =
+            typed_dependencies
+                .iter()
+                .map(|(dependency, dependency_type)| {
+                    dependency.runtime_dependency(true, *dependency_type)
+                });
+
+        let indirect_runtime_dependencies = typed_dependencies
+            .iter()
+            .filter(|(_, dependency_type)| dependency_type.is_runtime())
+            .flat_map(|(dependency, _)| dependency.runtime_dependencies.iter().cloned())
+            .filter(|runtime_dependency| runtime_dependency.dependency_type.is_runtime())
+            .map(|mut runtime_dependency| {
+                runtime_dependency.declared_directly = false;
+
+                runtime_dependency
+            });
+
+        let mut full_names = HashSet::new();
+
+        direct_runtime_dependencies
+            .chain(indirect_runtime_dependencies)
+            .filter(|runtime_dependency| full_names.insert(runtime_dependency.full_name.clone()))
+            .collect::<Vec<_>>()
+    }
 }
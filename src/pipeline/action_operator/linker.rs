@@ -18,6 +18,7 @@
     ext::{std::path::PathExt as _, tokio::path::PathExt as _},
     package::{
         PackageExt as _,
+        installed::formula::InstalledFormula,
         prepared::{PreparedPackage, download::Download, formula::PreparedFormula},
     },
 };
@@ -147,6 +148,11 @@ async fn link_opt(
 
         fs::create_dir_all(opt_prefix_link_base_path).await?;
 
+        context
+            .journal
+            .record_creation(&opt_prefix_link_path)
+            .await?;
+
         keg_dir_path
             .create_relative_link_atomically_at(&opt_prefix_link_path)
             .await?;
@@ -180,8 +186,13 @@ async fn link_keg(
 
             let should_skip = SKIP_LINK_DIR_NAMES.contains(keg_link_dir_name);
 
-            self.link_dir(&keg_link_dir_path, &prefix_link_dir_path, should_skip)
-                .await?;
+            self.link_dir(
+                &keg_link_dir_path,
+                &prefix_link_dir_path,
+                should_skip,
+                context,
+            )
+            .await?;
 
             anyhow::Ok(())
         });
@@ -192,6 +203,11 @@ async fn link_keg(
 
         fs::create_dir_all(linked_keg_prefix_link_base_path).await?;
 
+        context
+            .journal
+            .record_creation(&linked_keg_prefix_link_path)
+            .await?;
+
         keg_dir_path
             .create_relative_link_atomically_at(&linked_keg_prefix_link_path)
             .await?;
@@ -206,6 +222,7 @@ async fn link_dir(
         src_dir_path: &Path,
         dest_dir_path: &Path,
         should_skip: bool,
+        context: &Context,
     ) -> anyhow::Result<()> {
         let mut is_dest_dir_created = false;
 
@@ -223,7 +240,7 @@ async fn link_dir(
                     continue;
                 }
 
-                self.link_dir(&src_entry_dir_path, &dest_entry_dir_path, false)
+                self.link_dir(&src_entry_dir_path, &dest_entry_dir_path, false, context)
                     .await?;
 
                 continue;
@@ -235,6 +252,11 @@ async fn link_dir(
                 is_dest_dir_created = true;
             }
 
+            context
+                .journal
+                .record_creation(&dest_entry_dir_path)
+                .await?;
+
             src_entry_dir_path
                 .create_relative_link_atomically_at(dest_entry_dir_path)
                 .await?;
@@ -243,6 +265,122 @@ async fn link_dir(
         Ok(())
     }
 
+    pub(crate) async fn unlink(
+        &self,
+        installed_formula: &InstalledFormula,
+        context: &Context,
+    ) -> anyhow::Result<()> {
+        let id = installed_formula.id();
+
+        let version_revision = installed_formula.version_revision();
+
+        let keg_dir_path = context.homebrew_dirs.keg_dir(id, version_revision);
+
+        self.unlink_keg(&keg_dir_path, context).await?;
+
+        let opt_prefix_link_path = context.homebrew_dirs.opt_prefix_link(id);
+
+        let linked_keg_prefix_link_path = context.homebrew_dirs.linked_keg_prefix_link(id);
+
+        for prefix_link_path in [opt_prefix_link_path, linked_keg_prefix_link_path] {
+            if !prefix_link_path.is_link_exists_nofollow().await? {
+                continue;
+            }
+
+            let is_prefix_link_valid = keg_dir_path.realpath_or_none().await?
+                == prefix_link_path.realpath_or_none().await?;
+
+            if is_prefix_link_valid {
+                context.journal.remove(&prefix_link_path).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn unlink_keg(&self, keg_dir_path: &Path, context: &Context) -> anyhow::Result<()> {
+        let prefix_dir_path = context.homebrew_dirs.prefix_dir();
+
+        let keg_link_dir_name_futs = KEG_LINK_DIR_NAMES.iter().map(async |keg_link_dir_name| {
+            let keg_link_dir_path = keg_dir_path.join(keg_link_dir_name);
+
+            if !keg_link_dir_path.is_dir_exists_nofollow().await? {
+                return Ok(());
+            }
+
+            let prefix_link_dir_path = prefix_dir_path.join(keg_link_dir_name);
+
+            let should_skip = SKIP_LINK_DIR_NAMES.contains(keg_link_dir_name);
+
+            self.unlink_dir(
+                &keg_link_dir_path,
+                &prefix_link_dir_path,
+                should_skip,
+                context,
+            )
+            .await?;
+
+            anyhow::Ok(())
+        });
+
+        future::try_join_all(keg_link_dir_name_futs).await?;
+
+        Ok(())
+    }
+
+    #[expect(clippy::self_only_used_in_recursion)]
+    #[async_recursion]
+    async fn unlink_dir(
+        &self,
+        src_dir_path: &Path,
+        dest_dir_path: &Path,
+        should_skip: bool,
+        context: &Context,
+    ) -> anyhow::Result<()> {
+        let mut src_dir_entries = fs::read_dir(src_dir_path).await?;
+
+        while let Some(src_dir_entry) = src_dir_entries.next_entry().await? {
+            let src_entry_dir_name = src_dir_entry.file_name();
+
+            let src_entry_dir_path = src_dir_entry.path();
+
+            let dest_entry_dir_path = dest_dir_path.join(src_entry_dir_name);
+
+            if src_entry_dir_path.is_dir_exists_nofollow().await? {
+                if should_skip {
+                    continue;
+                }
+
+                self.unlink_dir(&src_entry_dir_path, &dest_entry_dir_path, false, context)
+                    .await?;
+
+                let is_dest_entry_dir_exists = dest_entry_dir_path.is_dir_exists_nofollow().await?;
+
+                if is_dest_entry_dir_exists && dest_entry_dir_path.is_dir_empty().await? {
+                    context
+                        .journal
+                        .remove_empty_dir(&dest_entry_dir_path)
+                        .await?;
+                }
+
+                continue;
+            }
+
+            if !dest_entry_dir_path.is_link_exists_nofollow().await? {
+                continue;
+            }
+
+            let is_dest_link_valid = src_entry_dir_path.realpath_or_none().await?
+                == dest_entry_dir_path.realpath_or_none().await?;
+
+            if is_dest_link_valid {
+                context.journal.remove(&dest_entry_dir_path).await?;
+            }
+        }
+
+        Ok(())
+    }
+
     async fn is_linked(
         &self,
         prepared_formula: &PreparedFormula<Download>,
@@ -3,7 +3,6 @@
 pub(crate) mod pkg_extractor;
 
 use async_trait::async_trait;
-use tokio::task;
 use tokio_util::task::AbortOnDropHandle;
 
 use super::{
@@ -78,7 +77,9 @@ fn proceed(
         input: Option<Self::Input>,
         mut session: Session,
     ) -> AbortOnDropHandle<anyhow::Result<Option<Self::Output>>> {
-        let handle = task::spawn(async move {
+        let tasks = session.context.tasks.clone();
+
+        let handle = tasks.spawn(async move {
             let _channel = &mut session.channel;
 
             let prepared_package = &session.prepared_package;
@@ -1,12 +1,14 @@
 pub(crate) mod action_operator;
 pub(crate) mod pull_connector;
 pub(crate) mod push_connector;
+pub(crate) mod schedule;
 pub(crate) mod sensor_operator;
 mod state_committer;
 mod state_store;
 
 use std::sync::Arc;
 
+use anyhow::Context as _;
 use async_trait::async_trait;
 use bytes::Bytes;
 use frunk::{
@@ -19,11 +21,11 @@
     stream::{self, StreamExt as _, TryStreamExt as _},
 };
 use indicatif::ProgressBar;
-use tokio::task;
 use tokio_util::task::AbortOnDropHandle;
 
 use self::{
     push_connector::progressor::Progressor,
+    schedule::Schedule,
     state_store::{ProgressedOutput, Session},
 };
 use crate::{
@@ -42,12 +44,13 @@ impl Pipeline<sink::SinkErrInto<sink::Drain<Bytes>, Bytes, anyhow::Error>, HNil>
     pub(crate) fn build(
         prepared_package: PreparedPackage<Download>,
         pb: ProgressBar,
+        schedule: Arc<Schedule>,
         context: Arc<Context>,
     ) -> Self {
         let sink = sink::drain();
         let sink = sink.sink_err_into();
 
-        let session = Session::new(prepared_package, pb, context);
+        let session = Session::new(prepared_package, pb, schedule, context);
 
         Self {
             sink,
@@ -116,12 +119,17 @@ pub(crate) async fn run_concurrently(
         self,
         stream: impl stream::TryStream<Ok = Bytes, Error = anyhow::Error> + Send + 'static,
     ) -> anyhow::Result<<Handles::Output as Collect>::Outputs> {
-        let handle = task::spawn(async {
+        let context = &self.session.context;
+
+        let cancellation = context.cancellation.clone();
+
+        let handle = context.tasks.spawn(async move {
             let stream = stream.err_into();
 
             let forward = stream.forward(self.sink);
+            let forward = cancellation.run_until_cancelled(forward);
 
-            forward.await?;
+            forward.await.context("Installation was interrupted")??;
 
             anyhow::Ok(())
         });
@@ -266,7 +274,9 @@ fn launch(
     ) {
         let (sink, handle) = self.source.launch(session.clone());
 
-        let handle = task::spawn(async {
+        let tasks = session.context.tasks.clone();
+
+        let handle = tasks.spawn(async {
             let connector_output = handle.await??;
 
             let operators_input = connector_output.clone();
@@ -308,7 +318,9 @@ fn proceed(
     ) -> AbortOnDropHandle<anyhow::Result<Option<Self::Output>>> {
         let handle = self.source.proceed(input, session.clone());
 
-        let handle = task::spawn(async {
+        let tasks = session.context.tasks.clone();
+
+        let handle = tasks.spawn(async {
             let operator_output = handle.await??;
 
             let operators_input = operator_output.clone();
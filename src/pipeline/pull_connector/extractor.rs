@@ -12,6 +12,7 @@
     XzDecoder,
     ZstdDecoder,
 };
+use async_recursion::async_recursion;
 use async_trait::async_trait;
 use async_zip::base::read::stream::ZipFileReader;
 use tempfile::TempDir;
@@ -131,9 +132,30 @@ async fn extract(
         Ok(())
     }
 
-    async fn persist(self, src_dir: TempDir, dest_dir_path: &Path) -> anyhow::Result<()> {
-        let src_dir_path = src_dir.path();
+    async fn persist(
+        self,
+        src_dir: TempDir,
+        dest_dir_path: &Path,
+        should_merge: bool,
+        context: &Context,
+    ) -> anyhow::Result<()> {
+        self.persist_dir(src_dir.path(), dest_dir_path, should_merge, context)
+            .await?;
+
+        src_dir.close()?;
 
+        Ok(())
+    }
+
+    #[expect(clippy::self_only_used_in_recursion)]
+    #[async_recursion]
+    async fn persist_dir(
+        &self,
+        src_dir_path: &Path,
+        dest_dir_path: &Path,
+        should_merge: bool,
+        context: &Context,
+    ) -> anyhow::Result<()> {
         let mut src_dir_entries = fs::read_dir(src_dir_path).await?;
 
         while let Some(src_dir_entry) = src_dir_entries.next_entry().await? {
@@ -147,10 +169,18 @@ async fn persist(self, src_dir: TempDir, dest_dir_path: &Path) -> anyhow::Result
                 continue;
             }
 
-            if dest_entry_dir_path.is_dir_exists_nofollow().await? {
-                fs::remove_dir_all(&dest_entry_dir_path).await?;
+            if should_merge && dest_entry_dir_path.is_dir_exists_nofollow().await? {
+                self.persist_dir(&src_entry_dir_path, &dest_entry_dir_path, false, context)
+                    .await?;
+
+                continue;
             }
 
+            context
+                .journal
+                .record_creation(&dest_entry_dir_path)
+                .await?;
+
             fs::rename(&src_entry_dir_path, &dest_entry_dir_path)
                 .await
                 .with_context(|| {
@@ -162,15 +192,13 @@ async fn persist(self, src_dir: TempDir, dest_dir_path: &Path) -> anyhow::Result
                 })?;
         }
 
-        src_dir.close()?;
-
         Ok(())
     }
 }
 
 #[async_trait]
 impl PullConnector for Extractor {
-    type Staging = (TempDir, PathBuf, ArchiveFormat);
+    type Staging = (TempDir, PathBuf, ArchiveFormat, bool);
     type Output = ExtractedOutput;
 
     fn should_run(&self, prepared_package: &PreparedPackage<Download>) -> bool {
@@ -230,7 +258,9 @@ async fn from_reader(
             },
         };
 
-        let staging = (src_dir, dest_dir_path, archive_format);
+        let should_merge = matches!(prepared_package, PreparedPackage::Formula(_));
+
+        let staging = (src_dir, dest_dir_path, archive_format, should_merge);
 
         Ok(staging)
     }
@@ -239,10 +269,15 @@ fn wait_stage(&self) -> Option<Stage> {
         Some(Stage::Hashed)
     }
 
-    async fn on_final_run(self, staging: Self::Staging) -> anyhow::Result<Self::Output> {
-        let (src_dir, dest_dir_path, archive_format) = staging;
+    async fn on_final_run(
+        self,
+        staging: Self::Staging,
+        context: &Context,
+    ) -> anyhow::Result<Self::Output> {
+        let (src_dir, dest_dir_path, archive_format, should_merge) = staging;
 
-        self.persist(src_dir, &dest_dir_path).await?;
+        self.persist(src_dir, &dest_dir_path, should_merge, context)
+            .await?;
 
         let output = ExtractedOutput {
             dest_dir_path,
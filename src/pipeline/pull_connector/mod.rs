@@ -9,7 +9,6 @@
 use tokio::{
     io::{self, AsyncRead},
     sync::mpsc,
-    task,
 };
 use tokio_stream::wrappers::ReceiverStream;
 use tokio_util::{io::StreamReader, sync::PollSender, task::AbortOnDropHandle};
@@ -55,7 +54,11 @@ fn wait_stage(&self) -> Option<Stage> {
         None
     }
 
-    async fn on_final_run(self, staging: Self::Staging) -> anyhow::Result<Self::Output>;
+    async fn on_final_run(
+        self,
+        staging: Self::Staging,
+        context: &Context,
+    ) -> anyhow::Result<Self::Output>;
 
     fn passed_prefix(&self) -> Option<&'static str> {
         None
@@ -92,7 +95,9 @@ fn launch(
         let sink = PollSender::new(tx);
         let sink = sink.sink_err_into();
 
-        let handle = task::spawn(async move {
+        let tasks = context.tasks.clone();
+
+        let handle = tasks.spawn(async move {
             let channel = &mut session.channel;
 
             let prepared_package = &session.prepared_package;
@@ -143,7 +148,7 @@ fn launch(
                     .await?;
             }
 
-            let output = self.on_final_run(staging).await;
+            let output = self.on_final_run(staging, context).await;
             let output = output.map(Some);
             let output = state_committer.finalize(output, &session)?;
 
@@ -3,6 +3,7 @@
 use base16ct::HexDisplay;
 use bytes::Bytes;
 use sha2::{Digest as _, Sha256};
+use tokio::fs;
 
 use super::{
     super::state_store::{HashedOutput, Stage},
@@ -87,6 +88,10 @@ async fn on_final_run(
         let is_verified = actual_sha256 == expected_sha256;
 
         if !is_verified {
+            let incomplete_file_path = download.incomplete_file_path();
+
+            fs::remove_file(incomplete_file_path).await?;
+
             let err = anyhow!("Hasher failed due to SHA-256 mismatch");
 
             return Err(err);
@@ -5,7 +5,7 @@
 use async_trait::async_trait;
 use bytes::Bytes;
 use futures::sink::{self, SinkExt as _};
-use tokio::{sync::mpsc, task};
+use tokio::sync::mpsc;
 use tokio_util::{sync::PollSender, task::AbortOnDropHandle};
 
 use super::{
@@ -92,7 +92,9 @@ fn launch(
         let sink = PollSender::new(tx);
         let sink = sink.sink_err_into();
 
-        let handle = task::spawn(async move {
+        let tasks = context.tasks.clone();
+
+        let handle = tasks.spawn(async move {
             let channel = &mut session.channel;
 
             let prepared_package = &session.prepared_package;
@@ -2,7 +2,6 @@
 
 use async_trait::async_trait;
 use bytes::Bytes;
-use tempfile::NamedTempFile;
 use tokio::{
     fs::{self, File},
     io::{AsyncWriteExt as _, BufWriter},
@@ -18,24 +17,36 @@
         tokio::{fs::FileExt as _, path::PathExt as _},
     },
     package::prepared::{PreparedPackage, PreparedPackageExt as _, download::Download},
+    store::Store,
 };
 
 pub(crate) struct Writer;
 
 impl Writer {
-    async fn persist(
-        self,
-        dest_file: NamedTempFile,
-        dest_file_path: &Path,
-        dest_link_path: &Path,
-    ) -> anyhow::Result<()> {
-        dest_file.persist(dest_file_path)?;
+    async fn persist(self, download: &Download) -> anyhow::Result<()> {
+        let blob_file_path = download.blob_file_path();
+
+        let dest_file_path = download.file_path();
+
+        let dest_link_path = download.link_path();
+
+        let ref_link_path = download.ref_link_path();
+
+        Self::link(blob_file_path, dest_file_path).await?;
+
+        Self::link(dest_file_path, dest_link_path).await?;
+
+        Self::link(blob_file_path, ref_link_path).await?;
 
+        Ok(())
+    }
+
+    async fn link(src_path: &Path, dest_link_path: &Path) -> anyhow::Result<()> {
         let dest_link_base_path = dest_link_path.base()?;
 
         fs::create_dir_all(dest_link_base_path).await?;
 
-        dest_file_path
+        src_path
             .create_relative_link_atomically_at(dest_link_path)
             .await?;
 
@@ -45,8 +56,8 @@ async fn persist(
 
 #[async_trait]
 impl PushConnector for Writer {
-    type State = (BufWriter<File>, NamedTempFile);
-    type Staging = NamedTempFile;
+    type State = (BufWriter<File>, u64);
+    type Staging = ();
     type Output = WrittenOutput;
 
     fn should_run(&self, prepared_package: &PreparedPackage<Download>) -> bool {
@@ -56,11 +67,13 @@ fn should_run(&self, prepared_package: &PreparedPackage<Download>) -> bool {
     }
 
     async fn on_skip_run(
-        mut self,
+        self,
         prepared_package: &PreparedPackage<Download>,
     ) -> anyhow::Result<Option<Self::Output>> {
         let download = prepared_package.download();
 
+        self.persist(download).await?;
+
         let dest_file_name = download.file_name();
 
         let dest_file_path = download.file_path();
@@ -82,65 +95,84 @@ async fn init(
     ) -> anyhow::Result<Self::State> {
         let download = prepared_package.download();
 
-        let dest_file_path = download.file_path();
+        let incomplete_file_path = download.incomplete_file_path();
 
-        let dest_file_base_path = dest_file_path.base()?;
+        let resume_offset = download.resume_offset();
 
-        fs::create_dir_all(dest_file_base_path).await?;
+        let incomplete_file_base_path = incomplete_file_path.base()?;
 
-        let dest_file = NamedTempFile::new_in(dest_file_base_path)?;
+        fs::create_dir_all(incomplete_file_base_path).await?;
 
-        let dest_file_path = dest_file.path();
+        let async_incomplete_file = File::open_append(incomplete_file_path).await?;
 
-        let async_dest_file = File::open_write(dest_file_path).await?;
+        async_incomplete_file.set_len(resume_offset).await?;
 
-        let buf_async_dest_file = BufWriter::new(async_dest_file);
+        let buf_async_incomplete_file = BufWriter::new(async_incomplete_file);
 
-        let state = (buf_async_dest_file, dest_file);
+        let state = (buf_async_incomplete_file, resume_offset);
 
         Ok(state)
     }
 
     async fn feed(&self, state: &mut Self::State, chunk: Bytes) -> anyhow::Result<()> {
-        let (buf_async_dest_file, _dest_file) = state;
+        let (buf_async_incomplete_file, skip_length) = state;
+
+        let chunk_length = u64::try_from(chunk.len())?;
+
+        if chunk_length <= *skip_length {
+            *skip_length = skip_length.saturating_sub(chunk_length);
 
-        buf_async_dest_file.write_all(&chunk).await?;
+            return Ok(());
+        }
+
+        let chunk_offset = usize::try_from(*skip_length)?;
+
+        *skip_length = 0;
+
+        let chunk = chunk.slice(chunk_offset..);
+
+        buf_async_incomplete_file.write_all(&chunk).await?;
 
         Ok(())
     }
 
     async fn flush(&self, state: Self::State) -> anyhow::Result<Self::Staging> {
-        let (mut buf_async_dest_file, dest_file) = state;
-
-        buf_async_dest_file.shutdown().await?;
+        let (mut buf_async_incomplete_file, _skip_length) = state;
 
-        let async_dest_file = buf_async_dest_file.get_mut();
+        buf_async_incomplete_file.shutdown().await?;
 
-        async_dest_file.shutdown().await?;
+        let async_incomplete_file = buf_async_incomplete_file.get_mut();
 
-        let staging = dest_file;
+        async_incomplete_file.shutdown().await?;
 
-        Ok(staging)
+        Ok(())
     }
 
     async fn on_final_run(
         self,
-        staging: Self::Staging,
+        _staging: Self::Staging,
         prepared_package: &PreparedPackage<Download>,
     ) -> anyhow::Result<Self::Output> {
-        let dest_file = staging;
-
         let download = prepared_package.download();
 
+        let incomplete_file_path = download.incomplete_file_path();
+
+        let blob_file_path = download.blob_file_path();
+
+        fs::rename(incomplete_file_path, blob_file_path).await?;
+
+        let expected_sha256 = download.expected_sha256();
+
+        Store::record_verified(blob_file_path, expected_sha256).await?;
+
+        self.persist(download).await?;
+
         let dest_file_name = download.file_name();
 
         let dest_file_path = download.file_path();
 
         let dest_link_path = download.link_path();
 
-        self.persist(dest_file, dest_file_path, dest_link_path)
-            .await?;
-
         let output = WrittenOutput {
             dest_file_name: dest_file_name.to_owned(),
             dest_file_path: dest_file_path.to_owned(),
@@ -150,6 +182,10 @@ async fn on_final_run(
         Ok(output)
     }
 
+    fn wait_stage(&self) -> Option<Stage> {
+        Some(Stage::Hashed)
+    }
+
     fn passed_stage(&self, _should_run: bool) -> Option<Stage> {
         Some(Stage::Written)
     }
@@ -0,0 +1,183 @@
+use std::collections::{BTreeSet, HashMap};
+
+use thiserror::Error;
+use tokio::sync::watch;
+
+use crate::package::{PackageExt as _, prepared::PreparedPackage};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Pending,
+    Linked,
+    Failed,
+}
+
+#[derive(Debug, Error)]
+#[error(r#"Skipped because dependency "{dependency}" failed"#)]
+pub(crate) struct SkippedError {
+    dependency: String,
+}
+
+pub(crate) struct Schedule {
+    order: Vec<String>,
+    dependencies: HashMap<String, Vec<String>>,
+    statuses: HashMap<String, watch::Sender<Status>>,
+}
+
+impl Schedule {
+    pub(crate) fn new(prepared_packages: &[PreparedPackage]) -> Self {
+        let statuses = prepared_packages
+            .iter()
+            .map(|prepared_package| {
+                let id = prepared_package.id().to_owned();
+
+                (id, watch::Sender::new(Status::Pending))
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut dependencies = prepared_packages
+            .iter()
+            .map(|prepared_package| {
+                let id = prepared_package.id();
+
+                let dependencies_of_one = prepared_package
+                    .direct_dependencies()
+                    .filter(|dependency| *dependency != id && statuses.contains_key(*dependency))
+                    .map(ToOwned::to_owned)
+                    .collect::<Vec<_>>();
+
+                (id.to_owned(), dependencies_of_one)
+            })
+            .collect::<HashMap<_, _>>();
+
+        let order = Self::sort(&mut dependencies);
+
+        Self {
+            order,
+            dependencies,
+            statuses,
+        }
+    }
+
+    fn sort(dependencies: &mut HashMap<String, Vec<String>>) -> Vec<String> {
+        let mut order = Vec::new();
+
+        let mut in_degrees = HashMap::<&str, usize>::new();
+        let mut dependents = HashMap::<&str, Vec<&str>>::new();
+
+        for (id, dependencies_of_one) in &*dependencies {
+            in_degrees.insert(id, dependencies_of_one.len());
+
+            for dependency in dependencies_of_one {
+                dependents.entry(dependency).or_default().push(id);
+            }
+        }
+
+        let mut ready = in_degrees
+            .iter()
+            .filter(|(_, in_degree)| **in_degree == 0)
+            .map(|(id, _)| *id)
+            .collect::<BTreeSet<_>>();
+
+        while let Some(id) = ready.pop_first() {
+            order.push(id.to_owned());
+
+            for dependent in dependents.get(id).into_iter().flatten() {
+                let Some(in_degree) = in_degrees.get_mut(dependent) else {
+                    continue;
+                };
+
+                *in_degree = in_degree.saturating_sub(1);
+
+                if *in_degree == 0 {
+                    ready.insert(*dependent);
+                }
+            }
+        }
+
+        let mut cyclic_ids = in_degrees
+            .into_iter()
+            .filter(|(_, in_degree)| *in_degree > 0)
+            .map(|(id, _)| id.to_owned())
+            .collect::<Vec<_>>();
+
+        cyclic_ids.sort();
+
+        for cyclic_id in &cyclic_ids {
+            if let Some(dependencies_of_one) = dependencies.get_mut(cyclic_id) {
+                dependencies_of_one.clear();
+            }
+        }
+
+        order.extend(cyclic_ids);
+
+        order
+    }
+
+    pub(crate) fn order<T>(
+        &self,
+        mut items: Vec<(PreparedPackage, T)>,
+    ) -> Vec<(PreparedPackage, T)> {
+        let positions = self
+            .order
+            .iter()
+            .enumerate()
+            .map(|(position, id)| (id.as_str(), position))
+            .collect::<HashMap<_, _>>();
+
+        items.sort_by_key(|(prepared_package, _)| positions.get(prepared_package.id()).copied());
+
+        items
+    }
+
+    pub(crate) async fn wait_for_dependencies(&self, id: &str) -> Result<(), SkippedError> {
+        let Some(dependencies) = self.dependencies.get(id) else {
+            return Ok(());
+        };
+
+        for dependency in dependencies {
+            let Some(status_tx) = self.statuses.get(dependency) else {
+                continue;
+            };
+
+            let mut status_rx = status_tx.subscribe();
+
+            let status = status_rx
+                .wait_for(|status| *status != Status::Pending)
+                .await
+                .map_or(Status::Failed, |status| *status);
+
+            if status == Status::Failed {
+                let dependency = dependency.clone();
+
+                return Err(SkippedError {
+                    dependency,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn mark_linked(&self, id: &str) {
+        self.mark(id, Status::Linked);
+    }
+
+    pub(crate) fn mark_failed(&self, id: &str) {
+        self.mark(id, Status::Failed);
+    }
+
+    fn mark(&self, id: &str, new_status: Status) {
+        let Some(status_tx) = self.statuses.get(id) else {
+            return;
+        };
+
+        status_tx.send_if_modified(|status| {
+            *status == Status::Pending && {
+                *status = new_status;
+
+                true
+            }
+        });
+    }
+}
@@ -5,7 +5,8 @@
     context::Context,
     package::{
         PackageExt as _,
-        prepared::{cask::PreparedCask, download::Download},
+        installed::cask::InstalledCask,
+        prepared::{cask::PreparedCask, cask_stanza::Stanzas, download::Download},
     },
 };
 
@@ -57,4 +58,38 @@ async fn link(
 
         Ok(staged_dir_path)
     }
+
+    #[expect(clippy::unused_async_trait_impl)]
+    async fn unrelocate(
+        &self,
+        installed_cask: &InstalledCask,
+        _stanzas: &Stanzas,
+        _replacement_pairs: &ReplacementPairs,
+        context: &Context,
+    ) -> anyhow::Result<PathBuf> {
+        let id = installed_cask.id();
+
+        let version = installed_cask.version();
+
+        let staged_dir_path = context.homebrew_dirs.staged_dir(id, version);
+
+        Ok(staged_dir_path)
+    }
+
+    #[expect(clippy::unused_async_trait_impl)]
+    async fn unlink(
+        &self,
+        installed_cask: &InstalledCask,
+        _stanzas: &Stanzas,
+        _replacement_pairs: &ReplacementPairs,
+        context: &Context,
+    ) -> anyhow::Result<PathBuf> {
+        let id = installed_cask.id();
+
+        let version = installed_cask.version();
+
+        let staged_dir_path = context.homebrew_dirs.staged_dir(id, version);
+
+        Ok(staged_dir_path)
+    }
 }
@@ -11,6 +11,7 @@
     ext::{std::path::PathExt as _, tokio::path::PathExt as _},
     package::{
         PackageExt as _,
+        installed::cask::InstalledCask,
         prepared::{
             cask::PreparedCask,
             cask_stanza::{CommonStanza, PkgStanza, Stanzas},
@@ -79,6 +80,44 @@ async fn link(
 
         Ok(staged_dir_path)
     }
+
+    async fn unrelocate(
+        &self,
+        installed_cask: &InstalledCask,
+        stanzas: &Stanzas,
+        replacement_pairs: &ReplacementPairs,
+        context: &Context,
+    ) -> anyhow::Result<PathBuf> {
+        let id = installed_cask.id();
+
+        let version = installed_cask.version();
+
+        let staged_dir_path = context.homebrew_dirs.staged_dir(id, version);
+
+        self.unrelocate_commons(stanzas, &staged_dir_path, replacement_pairs, context)
+            .await?;
+
+        Ok(staged_dir_path)
+    }
+
+    async fn unlink(
+        &self,
+        installed_cask: &InstalledCask,
+        stanzas: &Stanzas,
+        replacement_pairs: &ReplacementPairs,
+        context: &Context,
+    ) -> anyhow::Result<PathBuf> {
+        let id = installed_cask.id();
+
+        let version = installed_cask.version();
+
+        let staged_dir_path = context.homebrew_dirs.staged_dir(id, version);
+
+        self.unlink_commons(stanzas, &staged_dir_path, replacement_pairs, context)
+            .await?;
+
+        Ok(staged_dir_path)
+    }
 }
 
 impl Artifactor {
@@ -167,60 +206,20 @@ async fn relocate_commons(
         replacement_pairs: &ReplacementPairs,
         context: &Context,
     ) -> anyhow::Result<()> {
-        let common_stanzas_list = [
-            &stanzas.app,
-            &stanzas.suite,
-            &stanzas.colorpicker,
-            &stanzas.dictionary,
-            &stanzas.font,
-            &stanzas.input_method,
-            &stanzas.internet_plugin,
-            &stanzas.keyboard_layout,
-            &stanzas.prefpane,
-            &stanzas.qlplugin,
-            &stanzas.mdimporter,
-            &stanzas.screen_saver,
-            &stanzas.service,
-            &stanzas.audio_unit_plugin,
-            &stanzas.vst_plugin,
-            &stanzas.vst3_plugin,
-            &stanzas.artifact,
-        ];
-
-        let homebrew_dirs = &context.homebrew_dirs;
-
-        let dest_dir_paths = [
-            Some(homebrew_dirs.app_dir()),
-            Some(homebrew_dirs.app_dir()),
-            Some(homebrew_dirs.colorpicker_dir()),
-            Some(homebrew_dirs.dictionary_dir()),
-            Some(homebrew_dirs.font_dir()),
-            Some(homebrew_dirs.input_method_dir()),
-            Some(homebrew_dirs.internet_plugin_dir()),
-            Some(homebrew_dirs.keyboard_layout_dir()),
-            Some(homebrew_dirs.prefpane_dir()),
-            Some(homebrew_dirs.qlplugin_dir()),
-            Some(homebrew_dirs.mdimporter_dir()),
-            Some(homebrew_dirs.screen_saver_dir()),
-            Some(homebrew_dirs.service_dir()),
-            Some(homebrew_dirs.audio_unit_plugin_dir()),
-            Some(homebrew_dirs.vst_plugin_dir()),
-            Some(homebrew_dirs.vst3_plugin_dir()),
-            None,
-        ];
-
-        let common_stanzas_futs = common_stanzas_list
-            .into_iter()
-            .zip(dest_dir_paths.iter())
-            .map(|(common_stanzas, dest_dir_path)| {
-                self.relocate_common(
-                    common_stanzas,
-                    staged_dir_path,
-                    dest_dir_path.as_deref(),
-                    replacement_pairs,
-                    context,
-                )
-            });
+        let relocatable_commons = self.relocatable_commons(stanzas, context);
+
+        let common_stanzas_futs =
+            relocatable_commons
+                .iter()
+                .map(|(common_stanzas, dest_dir_path)| {
+                    self.relocate_common(
+                        common_stanzas,
+                        staged_dir_path,
+                        dest_dir_path.as_deref(),
+                        replacement_pairs,
+                        context,
+                    )
+                });
 
         future::try_join_all(common_stanzas_futs).await?;
 
@@ -250,34 +249,8 @@ async fn relocate_common(
 
             let src_item_path = staged_dir_path.join(common_source_path);
 
-            let common_target_pstr = &common_stanza.target;
-
-            let common_target_path =
-                self.resolve_target(common_target_pstr, replacement_pairs, context);
-
-            let dest_item_path = if common_target_path.is_relative() {
-                dest_dir_path
-                    .map(|dest_dir_path| dest_dir_path.join(&common_target_path))
-                    .unwrap_or(common_target_path)
-            } else {
-                common_target_path
-            };
-
-            let common_rename_pstr = &common_stanza.rename;
-
-            let dest_item_path = match common_rename_pstr {
-                Some(common_rename_pstr) => {
-                    let common_rename_path =
-                        self.resolve_target(common_rename_pstr, replacement_pairs, context);
-
-                    if common_rename_path.is_relative() {
-                        dest_item_path.with_file_name(common_rename_path)
-                    } else {
-                        common_rename_path
-                    }
-                },
-                None => dest_item_path,
-            };
+            let dest_item_path =
+                self.resolve_dest(common_stanza, dest_dir_path, replacement_pairs, context);
 
             let dest_item_base_path = dest_item_path.base()?;
 
@@ -285,9 +258,7 @@ async fn relocate_common(
                 fs::create_dir_all(dest_item_base_path).await?;
             }
 
-            if dest_item_path.is_dir_exists_nofollow().await? {
-                fs::remove_dir_all(&dest_item_path).await?;
-            }
+            context.journal.record_creation(&dest_item_path).await?;
 
             fs::rename(&src_item_path, &dest_item_path)
                 .await
@@ -299,6 +270,8 @@ async fn relocate_common(
                     format!(r#"Failed to rename "{src_item_path}" to "{dest_item_path}""#)
                 })?;
 
+            context.journal.record_creation(&src_item_path).await?;
+
             dest_item_path
                 .create_relative_link_atomically_at(src_item_path)
                 .await?;
@@ -318,40 +291,21 @@ async fn link_commons(
         replacement_pairs: &ReplacementPairs,
         context: &Context,
     ) -> anyhow::Result<()> {
-        let common_stanzas_list = [
-            &stanzas.binary,
-            &stanzas.manpage,
-            &stanzas.bash_completion,
-            &stanzas.fish_completion,
-            &stanzas.zsh_completion,
-        ];
-
-        let homebrew_dirs = &context.homebrew_dirs;
-
-        let dest_dir_paths = [
-            homebrew_dirs.bin_dir(),
-            homebrew_dirs.man_dir(),
-            homebrew_dirs.bash_completion_dir(),
-            homebrew_dirs.fish_completion_dir(),
-            homebrew_dirs.zsh_completion_dir(),
-        ];
-
-        let permissions_modes = [Some(0o111), None, None, None, None];
-
-        let common_stanzas_futs = common_stanzas_list
-            .into_iter()
-            .zip(dest_dir_paths.iter())
-            .zip(permissions_modes)
-            .map(|((common_stanzas, dest_dir_path), permissions_mode)| {
-                self.link_common(
-                    common_stanzas,
-                    staged_dir_path,
-                    dest_dir_path,
-                    permissions_mode,
-                    replacement_pairs,
-                    context,
-                )
-            });
+        let linkable_commons = self.linkable_commons(stanzas, context);
+
+        let common_stanzas_futs =
+            linkable_commons
+                .iter()
+                .map(|(common_stanzas, dest_dir_path, permissions_mode)| {
+                    self.link_common(
+                        common_stanzas,
+                        staged_dir_path,
+                        dest_dir_path,
+                        *permissions_mode,
+                        replacement_pairs,
+                        context,
+                    )
+                });
 
         future::try_join_all(common_stanzas_futs).await?;
 
@@ -380,32 +334,12 @@ async fn link_common(
 
             let src_item_path = staged_dir_path.join(common_source_path);
 
-            let common_target_pstr = &common_stanza.target;
-
-            let common_target_path =
-                self.resolve_target(common_target_pstr, replacement_pairs, context);
-
-            let dest_link_path = if common_target_path.is_relative() {
-                dest_dir_path.join(common_target_path)
-            } else {
-                common_target_path
-            };
-
-            let common_rename_pstr = &common_stanza.rename;
-
-            let dest_link_path = match common_rename_pstr {
-                Some(common_rename_pstr) => {
-                    let common_rename_path =
-                        self.resolve_target(common_rename_pstr, replacement_pairs, context);
-
-                    if common_rename_path.is_relative() {
-                        dest_link_path.with_file_name(common_rename_path)
-                    } else {
-                        common_rename_path
-                    }
-                },
-                None => dest_link_path,
-            };
+            let dest_link_path = self.resolve_dest(
+                common_stanza,
+                Some(dest_dir_path),
+                replacement_pairs,
+                context,
+            );
 
             let dest_link_base_path = dest_link_path.base()?;
 
@@ -413,6 +347,8 @@ async fn link_common(
                 fs::create_dir_all(dest_link_base_path).await?;
             }
 
+            context.journal.record_creation(&dest_link_path).await?;
+
             src_item_path
                 .create_relative_link_atomically_at(dest_link_path)
                 .await?;
@@ -428,4 +364,254 @@ async fn link_common(
 
         Ok(())
     }
+
+    async fn unrelocate_commons(
+        &self,
+        stanzas: &Stanzas,
+        staged_dir_path: &Path,
+        replacement_pairs: &ReplacementPairs,
+        context: &Context,
+    ) -> anyhow::Result<()> {
+        let relocatable_commons = self.relocatable_commons(stanzas, context);
+
+        let common_stanzas_futs =
+            relocatable_commons
+                .iter()
+                .map(|(common_stanzas, dest_dir_path)| {
+                    self.unrelocate_common(
+                        common_stanzas,
+                        staged_dir_path,
+                        dest_dir_path.as_deref(),
+                        replacement_pairs,
+                        context,
+                    )
+                });
+
+        future::try_join_all(common_stanzas_futs).await?;
+
+        Ok(())
+    }
+
+    async fn unrelocate_common(
+        &self,
+        common_stanzas: &[CommonStanza],
+        staged_dir_path: &Path,
+        dest_dir_path: Option<&Path>,
+        replacement_pairs: &ReplacementPairs,
+        context: &Context,
+    ) -> anyhow::Result<()> {
+        let common_stanza_futs = common_stanzas.iter().map(async |common_stanza| {
+            let common_source_pstr = &common_stanza.source;
+
+            let common_source_path = self.resolve_source(common_source_pstr, replacement_pairs);
+
+            let src_item_path = staged_dir_path.join(common_source_path);
+
+            let dest_item_path =
+                self.resolve_dest(common_stanza, dest_dir_path, replacement_pairs, context);
+
+            if !src_item_path.is_link_exists_nofollow().await? {
+                return Ok(());
+            }
+
+            let is_src_link_valid = src_item_path.realpath_or_none().await?
+                == dest_item_path.realpath_or_none().await?;
+
+            if !is_src_link_valid {
+                return Ok(());
+            }
+
+            context.journal.remove(&dest_item_path).await?;
+
+            context.journal.remove(&src_item_path).await?;
+
+            anyhow::Ok(())
+        });
+
+        future::try_join_all(common_stanza_futs).await?;
+
+        Ok(())
+    }
+
+    async fn unlink_commons(
+        &self,
+        stanzas: &Stanzas,
+        staged_dir_path: &Path,
+        replacement_pairs: &ReplacementPairs,
+        context: &Context,
+    ) -> anyhow::Result<()> {
+        let linkable_commons = self.linkable_commons(stanzas, context);
+
+        let common_stanzas_futs =
+            linkable_commons
+                .iter()
+                .map(|(common_stanzas, dest_dir_path, _permissions_mode)| {
+                    self.unlink_common(
+                        common_stanzas,
+                        staged_dir_path,
+                        dest_dir_path,
+                        replacement_pairs,
+                        context,
+                    )
+                });
+
+        future::try_join_all(common_stanzas_futs).await?;
+
+        Ok(())
+    }
+
+    async fn unlink_common(
+        &self,
+        common_stanzas: &[CommonStanza],
+        staged_dir_path: &Path,
+        dest_dir_path: &Path,
+        replacement_pairs: &ReplacementPairs,
+        context: &Context,
+    ) -> anyhow::Result<()> {
+        let common_stanza_futs = common_stanzas.iter().map(async |common_stanza| {
+            let common_source_pstr = &common_stanza.source;
+
+            let common_source_path = self.resolve_source(common_source_pstr, replacement_pairs);
+
+            let src_item_path = staged_dir_path.join(common_source_path);
+
+            let dest_link_path = self.resolve_dest(
+                common_stanza,
+                Some(dest_dir_path),
+                replacement_pairs,
+                context,
+            );
+
+            if !dest_link_path.is_link_exists_nofollow().await? {
+                return Ok(());
+            }
+
+            let is_dest_link_valid = src_item_path.realpath_or_none().await?
+                == dest_link_path.realpath_or_none().await?;
+
+            if is_dest_link_valid {
+                context.journal.remove(&dest_link_path).await?;
+            }
+
+            anyhow::Ok(())
+        });
+
+        future::try_join_all(common_stanza_futs).await?;
+
+        Ok(())
+    }
+
+    #[expect(clippy::unused_self)]
+    fn relocatable_commons<'stanzas>(
+        &self,
+        stanzas: &'stanzas Stanzas,
+        context: &Context,
+    ) -> [(&'stanzas [CommonStanza], Option<PathBuf>); 17] {
+        let homebrew_dirs = &context.homebrew_dirs;
+
+        [
+            (&stanzas.app, Some(homebrew_dirs.app_dir())),
+            (&stanzas.suite, Some(homebrew_dirs.app_dir())),
+            (&stanzas.colorpicker, Some(homebrew_dirs.colorpicker_dir())),
+            (&stanzas.dictionary, Some(homebrew_dirs.dictionary_dir())),
+            (&stanzas.font, Some(homebrew_dirs.font_dir())),
+            (
+                &stanzas.input_method,
+                Some(homebrew_dirs.input_method_dir()),
+            ),
+            (
+                &stanzas.internet_plugin,
+                Some(homebrew_dirs.internet_plugin_dir()),
+            ),
+            (
+                &stanzas.keyboard_layout,
+                Some(homebrew_dirs.keyboard_layout_dir()),
+            ),
+            (&stanzas.prefpane, Some(homebrew_dirs.prefpane_dir())),
+            (&stanzas.qlplugin, Some(homebrew_dirs.qlplugin_dir())),
+            (&stanzas.mdimporter, Some(homebrew_dirs.mdimporter_dir())),
+            (
+                &stanzas.screen_saver,
+                Some(homebrew_dirs.screen_saver_dir()),
+            ),
+            (&stanzas.service, Some(homebrew_dirs.service_dir())),
+            (
+                &stanzas.audio_unit_plugin,
+                Some(homebrew_dirs.audio_unit_plugin_dir()),
+            ),
+            (&stanzas.vst_plugin, Some(homebrew_dirs.vst_plugin_dir())),
+            (&stanzas.vst3_plugin, Some(homebrew_dirs.vst3_plugin_dir())),
+            (&stanzas.artifact, None),
+        ]
+        .map(|(common_stanzas, dest_dir_path)| (common_stanzas.as_slice(), dest_dir_path))
+    }
+
+    #[expect(clippy::unused_self)]
+    fn linkable_commons<'stanzas>(
+        &self,
+        stanzas: &'stanzas Stanzas,
+        context: &Context,
+    ) -> [(&'stanzas [CommonStanza], PathBuf, Option<u32>); 5] {
+        let homebrew_dirs = &context.homebrew_dirs;
+
+        [
+            (&stanzas.binary, homebrew_dirs.bin_dir(), Some(0o111)),
+            (&stanzas.manpage, homebrew_dirs.man_dir(), None),
+            (
+                &stanzas.bash_completion,
+                homebrew_dirs.bash_completion_dir(),
+                None,
+            ),
+            (
+                &stanzas.fish_completion,
+                homebrew_dirs.fish_completion_dir(),
+                None,
+            ),
+            (
+                &stanzas.zsh_completion,
+                homebrew_dirs.zsh_completion_dir(),
+                None,
+            ),
+        ]
+        .map(|(common_stanzas, dest_dir_path, permissions_mode)| {
+            (common_stanzas.as_slice(), dest_dir_path, permissions_mode)
+        })
+    }
+
+    fn resolve_dest(
+        &self,
+        common_stanza: &CommonStanza,
+        dest_dir_path: Option<&Path>,
+        replacement_pairs: &ReplacementPairs,
+        context: &Context,
+    ) -> PathBuf {
+        let common_target_pstr = &common_stanza.target;
+
+        let common_target_path =
+            self.resolve_target(common_target_pstr, replacement_pairs, context);
+
+        let dest_path = if common_target_path.is_relative() {
+            dest_dir_path
+                .map(|dest_dir_path| dest_dir_path.join(&common_target_path))
+                .unwrap_or(common_target_path)
+        } else {
+            common_target_path
+        };
+
+        let common_rename_pstr = &common_stanza.rename;
+
+        match common_rename_pstr {
+            Some(common_rename_pstr) => {
+                let common_rename_path =
+                    self.resolve_target(common_rename_pstr, replacement_pairs, context);
+
+                if common_rename_path.is_relative() {
+                    dest_path.with_file_name(common_rename_path)
+                } else {
+                    common_rename_path
+                }
+            },
+            None => dest_path,
+        }
+    }
 }
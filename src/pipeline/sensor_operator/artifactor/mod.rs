@@ -7,6 +7,7 @@
 
 use anyhow::anyhow;
 use async_trait::async_trait;
+use indicatif::ProgressBar;
 use path_clean::PathClean as _;
 
 use super::{
@@ -15,7 +16,10 @@
 };
 use crate::{
     context::{Context, dirs::ProjectDirs as _},
-    package::prepared::{PreparedPackage, cask::PreparedCask, download::Download},
+    package::{
+        installed::cask::InstalledCask,
+        prepared::{PreparedPackage, cask::PreparedCask, cask_stanza::Stanzas, download::Download},
+    },
 };
 
 #[cfg(target_os = "macos")]
@@ -54,6 +58,10 @@ fn should_run(
         true
     }
 
+    fn awaits_dependencies(&self) -> bool {
+        true
+    }
+
     fn running_prefix(&self) -> Option<&'static str> {
         Some("Installing")
     }
@@ -92,6 +100,7 @@ async fn execute(
         &self,
         state: &Self::State,
         prepared_package: &PreparedPackage<Download>,
+        _pb: &ProgressBar,
         context: &Context,
     ) -> anyhow::Result<Self::Staging> {
         let PreparedPackage::Cask(prepared_cask) = prepared_package else {
@@ -141,6 +150,26 @@ fn passed_stage(
 }
 
 impl Artifactor {
+    pub(crate) async fn uninstall(
+        &self,
+        installed_cask: &InstalledCask,
+        context: &Context,
+    ) -> anyhow::Result<()> {
+        let stanzas = installed_cask.load_stanzas(context).await?;
+
+        let replacement_pairs = self.init(context)?;
+
+        let _staged_dir_path = self
+            .unlink(installed_cask, &stanzas, &replacement_pairs, context)
+            .await?;
+
+        let _staged_dir_path = self
+            .unrelocate(installed_cask, &stanzas, &replacement_pairs, context)
+            .await?;
+
+        Ok(())
+    }
+
     fn resolve_source(&self, pstr: &str, replacement_pairs: &ReplacementPairs) -> PathBuf {
         self.replace_pstr(pstr, replacement_pairs)
     }
@@ -230,4 +259,20 @@ async fn link(
         replacement_pairs: &ReplacementPairs,
         context: &Context,
     ) -> anyhow::Result<PathBuf>;
+
+    async fn unrelocate(
+        &self,
+        installed_cask: &InstalledCask,
+        stanzas: &Stanzas,
+        replacement_pairs: &ReplacementPairs,
+        context: &Context,
+    ) -> anyhow::Result<PathBuf>;
+
+    async fn unlink(
+        &self,
+        installed_cask: &InstalledCask,
+        stanzas: &Stanzas,
+        replacement_pairs: &ReplacementPairs,
+        context: &Context,
+    ) -> anyhow::Result<PathBuf>;
 }
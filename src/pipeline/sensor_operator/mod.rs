@@ -1,10 +1,11 @@
 pub(crate) mod artifactor;
+pub(crate) mod receipter;
 pub(crate) mod relocator;
 
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use tokio::task;
+use indicatif::ProgressBar;
 use tokio_util::task::AbortOnDropHandle;
 
 use super::{
@@ -14,7 +15,10 @@
 };
 use crate::{
     context::Context,
-    package::prepared::{PreparedPackage, download::Download},
+    package::{
+        PackageExt as _,
+        prepared::{PreparedPackage, download::Download},
+    },
 };
 
 pub(crate) struct _SensorOperatorMarker;
@@ -41,6 +45,10 @@ fn on_skip_run(self) -> anyhow::Result<Option<Self::Output>> {
         Ok(None)
     }
 
+    fn awaits_dependencies(&self) -> bool {
+        false
+    }
+
     fn running_prefix(&self) -> Option<&'static str> {
         None
     }
@@ -51,6 +59,7 @@ async fn execute(
         &self,
         state: &Self::State,
         prepared_package: &PreparedPackage<Download>,
+        pb: &ProgressBar,
         context: &Context,
     ) -> anyhow::Result<Self::Staging>;
 
@@ -85,7 +94,9 @@ fn proceed(
         _: Option<Self::Input>,
         mut session: Session,
     ) -> AbortOnDropHandle<anyhow::Result<Option<Self::Output>>> {
-        let handle = task::spawn(async move {
+        let tasks = session.context.tasks.clone();
+
+        let handle = tasks.spawn(async move {
             let channel = &mut session.channel;
 
             let prepared_package = &session.prepared_package;
@@ -105,6 +116,20 @@ fn proceed(
                 Arc::clone(&state_store.payloads)
             };
 
+            if self.awaits_dependencies() {
+                let id = prepared_package.id();
+
+                session
+                    .schedule
+                    .wait_for_dependencies(id)
+                    .await
+                    .inspect_err(|_| {
+                        pb.set_prefix("Skipped");
+
+                        pb.finish();
+                    })?;
+            }
+
             let payload = payloads.subscribe()?;
 
             let should_run = self.should_run(payload, prepared_package, context);
@@ -129,7 +154,7 @@ fn proceed(
 
             let state = self.init(context)?;
 
-            let staging = self.execute(&state, prepared_package, context).await?;
+            let staging = self.execute(&state, prepared_package, pb, context).await?;
 
             let output = self.on_final_run(staging);
             let output = output.map(Some);
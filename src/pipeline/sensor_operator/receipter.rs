@@ -0,0 +1,176 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use indicatif::ProgressBar;
+use tokio::fs;
+
+use super::{
+    super::state_store::{ExtractedOutput, ReceiptedOutput, Stage},
+    SensorOperator,
+};
+use crate::{
+    context::Context,
+    package::{
+        PackageExt as _,
+        prepared::{
+            PreparedPackage,
+            cask::PreparedCask,
+            download::Download,
+            formula::PreparedFormula,
+        },
+    },
+    receipt::{RECEIPT_FILE_NAME, cask::CaskReceipt, formula::FormulaReceipt},
+    registries,
+};
+
+pub(crate) struct Receipter;
+
+#[async_trait]
+impl SensorOperator for Receipter {
+    type Payload = ExtractedOutput;
+    type State = DateTime<Utc>;
+    type Staging = PathBuf;
+    type Output = ReceiptedOutput;
+
+    fn poke_stage(&self) -> Stage {
+        Stage::Linked
+    }
+
+    fn should_run(
+        &self,
+        payload: Option<&Self::Payload>,
+        _prepared_package: &PreparedPackage<Download>,
+        _context: &Context,
+    ) -> bool {
+        let Some(_payload) = payload else {
+            return false;
+        };
+
+        true
+    }
+
+    fn init(&self, _context: &Context) -> anyhow::Result<Self::State> {
+        let installed_at = Utc::now();
+
+        let state = installed_at;
+
+        Ok(state)
+    }
+
+    async fn execute(
+        &self,
+        state: &Self::State,
+        prepared_package: &PreparedPackage<Download>,
+        _pb: &ProgressBar,
+        context: &Context,
+    ) -> anyhow::Result<Self::Staging> {
+        let installed_at = *state;
+
+        let receipt_file_path = match prepared_package {
+            PreparedPackage::Formula(prepared_formula) => {
+                self.write_formula_receipt(prepared_formula, installed_at, context)
+                    .await?
+            },
+            PreparedPackage::Cask(prepared_cask) => {
+                self.write_cask_receipt(prepared_cask, installed_at, context)
+                    .await?
+            },
+        };
+
+        let staging = receipt_file_path;
+
+        Ok(staging)
+    }
+
+    fn on_final_run(self, staging: Self::Staging) -> anyhow::Result<Self::Output> {
+        let receipt_file_path = staging;
+
+        let output = ReceiptedOutput {
+            receipt_file_path,
+        };
+
+        Ok(output)
+    }
+
+    fn passed_stage(
+        &self,
+        _should_run: bool,
+        _prepared_package: &PreparedPackage<Download>,
+    ) -> Option<Stage> {
+        Some(Stage::Receipted)
+    }
+}
+
+impl Receipter {
+    async fn write_formula_receipt(
+        &self,
+        prepared_formula: &PreparedFormula<Download>,
+        installed_at: DateTime<Utc>,
+        context: &Context,
+    ) -> anyhow::Result<PathBuf> {
+        let id = prepared_formula.id();
+
+        let version_revision = prepared_formula.version_revision();
+
+        let keg_dir_path = context.homebrew_dirs.keg_dir(id, version_revision);
+
+        let bottle_receipt = FormulaReceipt::load(&keg_dir_path).await?;
+
+        let receipt_file_path = keg_dir_path.join(RECEIPT_FILE_NAME);
+
+        context.journal.record_creation(&receipt_file_path).await?;
+
+        let receipt = FormulaReceipt::new(bottle_receipt, prepared_formula, installed_at, context);
+
+        let receipt_file_path = receipt.save(&keg_dir_path).await?;
+
+        Ok(receipt_file_path)
+    }
+
+    async fn write_cask_receipt(
+        &self,
+        prepared_cask: &PreparedCask<Download>,
+        installed_at: DateTime<Utc>,
+        context: &Context,
+    ) -> anyhow::Result<PathBuf> {
+        let id = prepared_cask.id();
+
+        let version = prepared_cask.version();
+
+        let metadata_dir_path = context.homebrew_dirs.metadata_dir(id);
+
+        let timestamp = installed_at.format("%Y%m%d%H%M%S%.3f");
+        let timestamp = timestamp.to_string();
+
+        let timestamp_dir_path = metadata_dir_path.join(version).join(timestamp);
+
+        context.journal.record_creation(&timestamp_dir_path).await?;
+
+        let caskfile_dir_path = timestamp_dir_path.join("Casks");
+
+        fs::create_dir_all(&caskfile_dir_path).await?;
+
+        let tap = prepared_cask.tap();
+
+        let json_id = registries::qualify_cask(Some(tap), id);
+
+        let json_file_path = context.homebrew_dirs.cask_json_file(&json_id);
+
+        let caskfile_name = format!("{id}.json");
+
+        let caskfile_path = caskfile_dir_path.join(caskfile_name);
+
+        fs::copy(json_file_path, caskfile_path).await?;
+
+        let receipt_file_path = metadata_dir_path.join(RECEIPT_FILE_NAME);
+
+        context.journal.record_creation(&receipt_file_path).await?;
+
+        let receipt = CaskReceipt::new(prepared_cask, installed_at, context);
+
+        let receipt_file_path = receipt.save(&metadata_dir_path).await?;
+
+        Ok(receipt_file_path)
+    }
+}
@@ -1,39 +1,41 @@
-use std::{borrow::Cow, collections::HashMap, path::Path};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 use arwen::elf::rewriter::Writer;
 use bytes::Bytes;
-use tempfile::NamedTempFile;
-use tokio::{
-    fs::{self, File},
-    io::AsyncWriteExt as _,
-    task,
-};
-use tokio_util::task::AbortOnDropHandle;
+use tokio_util::task::{AbortOnDropHandle, TaskTracker};
 
 use super::{Relocator, RelocatorExt, ReplacementPairs};
-use crate::{
-    ext::{std::path::PathExt as _, tokio::fs::FileExt as _},
-    util::linux::elf::Elf,
-};
+use crate::{ext::tokio::fs::write_atomically, util::linux::elf::Elf};
+
+const BREWED_DYNAMIC_LINKER_PATH: &str = "lib/ld.so";
+
+const SYSTEM_DYNAMIC_LINKER_PATHS: &[(u16, &str)] = &[
+    (Elf::EM_X86_64, "/lib64/ld-linux-x86-64.so.2"),
+    (Elf::EM_AARCH64, "/lib/ld-linux-aarch64.so.1"),
+    (Elf::EM_386, "/lib/ld-linux.so.2"),
+    (Elf::EM_ARM, "/lib/ld-linux-armhf.so.3"),
+    (Elf::EM_PPC64, "/lib64/ld64.so.2"),
+];
 
 impl RelocatorExt for Relocator {
+    fn has_magic(&self, peek_bytes: &[u8]) -> bool {
+        Elf::has_magic(peek_bytes)
+    }
+
     async fn patch_file(
         &self,
         dest_file_path: &Path,
+        bytes: Bytes,
         replacement_pairs: &ReplacementPairs,
-    ) -> anyhow::Result<()> {
-        let has_magic = Elf::has_magic(dest_file_path).await?;
-
-        if !has_magic {
-            return Ok(());
-        }
-
-        let bytes = fs::read(dest_file_path).await?;
-        let bytes = Bytes::from(bytes);
-
+        tasks: &TaskTracker,
+    ) -> anyhow::Result<Bytes> {
         let this = self.clone();
 
-        let handle = task::spawn_blocking({
+        let handle = tasks.spawn_blocking({
             let bytes = bytes.clone();
 
             let replacement_pairs = replacement_pairs.clone();
@@ -49,30 +51,14 @@ async fn patch_file(
         let replaced_bytes = handle.await??;
 
         if replaced_bytes == *bytes {
-            return Ok(());
+            return Ok(bytes);
         }
 
-        let metadata = fs::symlink_metadata(dest_file_path).await?;
+        write_atomically(dest_file_path, &replaced_bytes, true).await?;
 
-        let permissions = metadata.permissions();
+        let replaced_bytes = Bytes::from(replaced_bytes);
 
-        let dest_file_base_path = dest_file_path.base()?;
-
-        let temp_file = NamedTempFile::new_in(dest_file_base_path)?;
-
-        let temp_file_path = temp_file.path();
-
-        let mut async_temp_file = File::open_write(temp_file_path).await?;
-
-        async_temp_file.write_all(&replaced_bytes).await?;
-
-        async_temp_file.shutdown().await?;
-
-        let dest_file = temp_file.persist(dest_file_path)?;
-
-        dest_file.set_permissions(permissions)?;
-
-        Ok(())
+        Ok(replaced_bytes)
     }
 
     fn replace_bytes(
@@ -82,20 +68,25 @@ fn replace_bytes(
     ) -> anyhow::Result<Vec<u8>> {
         let mut rewriter = Writer::read(bytes)?;
 
-        if let Some(runpath) = rewriter.elf_runpath() {
-            let old_runpath = String::from_utf8_lossy(runpath);
+        let machine = Elf::machine(bytes);
 
-            let new_runpath = old_runpath
-                .split(':')
-                .map(|component| self.replace_pstr(component, replacement_pairs))
-                .collect::<Vec<_>>();
-            let new_runpath = new_runpath.join(":");
+        if let Some(interpreter) = rewriter.elf_interpreter()
+            && let Some(new_interpreter) =
+                self.replace_interpreter(interpreter, machine, replacement_pairs)
+        {
+            rewriter.elf_set_interpreter(new_interpreter)?;
+        }
 
-            if new_runpath != old_runpath {
-                let new_runpath = new_runpath.into_bytes();
+        if let Some(runpath) = rewriter.elf_runpath()
+            && let Some(new_runpath) = self.replace_search_path(runpath, replacement_pairs)
+        {
+            rewriter.elf_set_runpath(new_runpath)?;
+        }
 
-                rewriter.elf_set_runpath(new_runpath)?;
-            }
+        if let Some(rpath) = rewriter.elf_rpath()
+            && let Some(new_rpath) = self.replace_search_path(rpath, replacement_pairs)
+        {
+            rewriter.elf_set_rpath(new_rpath)?;
         }
 
         let old_needed = rewriter
@@ -133,3 +124,59 @@ fn replace_bytes(
         Ok(replaced_bytes)
     }
 }
+
+impl Relocator {
+    fn replace_interpreter(
+        &self,
+        interpreter: &[u8],
+        machine: Option<u16>,
+        replacement_pairs: &ReplacementPairs,
+    ) -> Option<Vec<u8>> {
+        let old_interpreter = String::from_utf8_lossy(interpreter);
+
+        let new_interpreter = self.replace_pstr(&old_interpreter, replacement_pairs);
+
+        let Cow::Owned(new_interpreter) = new_interpreter else {
+            return None;
+        };
+
+        let brewed_interpreter_path = replacement_pairs
+            .iter()
+            .find(|(placeholder, _)| *placeholder == Self::PREFIX_PLACEHOLDER)
+            .map(|(_, prefix_pstr)| Path::new(prefix_pstr).join(BREWED_DYNAMIC_LINKER_PATH))
+            .filter(|interpreter_path| interpreter_path.exists());
+
+        let system_interpreter_path = SYSTEM_DYNAMIC_LINKER_PATHS
+            .iter()
+            .find(|(linker_machine, _)| Some(*linker_machine) == machine)
+            .map(|(_, interpreter_path)| PathBuf::from(interpreter_path));
+
+        let new_interpreter = brewed_interpreter_path
+            .or(system_interpreter_path)
+            .map_or(new_interpreter, |interpreter_path| {
+                interpreter_path.to_string_lossy().into_owned()
+            });
+
+        Some(new_interpreter.into_bytes())
+    }
+
+    fn replace_search_path(
+        &self,
+        search_path: &[u8],
+        replacement_pairs: &ReplacementPairs,
+    ) -> Option<Vec<u8>> {
+        let old_search_path = String::from_utf8_lossy(search_path);
+
+        let new_search_path = old_search_path
+            .split(':')
+            .map(|component| self.replace_pstr(component, replacement_pairs))
+            .collect::<Vec<_>>();
+        let new_search_path = new_search_path.join(":");
+
+        if new_search_path == old_search_path {
+            return None;
+        }
+
+        Some(new_search_path.into_bytes())
+    }
+}
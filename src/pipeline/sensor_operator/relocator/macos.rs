@@ -2,38 +2,29 @@
 
 use arwen::macho::{MachoContainer, MachoType};
 use bytes::Bytes;
-use tempfile::NamedTempFile;
-use tokio::{
-    fs::{self, File},
-    io::AsyncWriteExt as _,
-    task,
-};
-use tokio_util::task::AbortOnDropHandle;
+use tokio_util::task::{AbortOnDropHandle, TaskTracker};
 
 use super::{Relocator, RelocatorExt, ReplacementPairs};
 use crate::{
-    ext::{std::path::PathExt as _, tokio::fs::FileExt as _},
+    ext::tokio::fs::write_atomically,
     util::macos::{codesign::Codesign, mach_o::MachO},
 };
 
 impl RelocatorExt for Relocator {
+    fn has_magic(&self, peek_bytes: &[u8]) -> bool {
+        MachO::has_magic(peek_bytes)
+    }
+
     async fn patch_file(
         &self,
         dest_file_path: &Path,
+        bytes: Bytes,
         replacement_pairs: &ReplacementPairs,
-    ) -> anyhow::Result<()> {
-        let has_magic = MachO::has_magic(dest_file_path).await?;
-
-        if !has_magic {
-            return Ok(());
-        }
-
-        let bytes = fs::read(dest_file_path).await?;
-        let bytes = Bytes::from(bytes);
-
+        tasks: &TaskTracker,
+    ) -> anyhow::Result<Bytes> {
         let this = self.clone();
 
-        let handle = task::spawn_blocking({
+        let handle = tasks.spawn_blocking({
             let bytes = bytes.clone();
 
             let replacement_pairs = replacement_pairs.clone();
@@ -49,32 +40,16 @@ async fn patch_file(
         let replaced_bytes = handle.await??;
 
         if replaced_bytes == *bytes {
-            return Ok(());
+            return Ok(bytes);
         }
 
-        let metadata = fs::symlink_metadata(dest_file_path).await?;
-
-        let permissions = metadata.permissions();
+        write_atomically(dest_file_path, &replaced_bytes, true).await?;
 
-        let dest_file_base_path = dest_file_path.base()?;
+        Codesign::in_place(dest_file_path, tasks).await?;
 
-        let temp_file = NamedTempFile::new_in(dest_file_base_path)?;
+        let replaced_bytes = Bytes::from(replaced_bytes);
 
-        let temp_file_path = temp_file.path();
-
-        let mut async_temp_file = File::open_write(temp_file_path).await?;
-
-        async_temp_file.write_all(&replaced_bytes).await?;
-
-        async_temp_file.shutdown().await?;
-
-        let dest_file = temp_file.persist(dest_file_path)?;
-
-        dest_file.set_permissions(permissions)?;
-
-        Codesign::in_place(dest_file_path).await?;
-
-        Ok(())
+        Ok(replaced_bytes)
     }
 
     fn replace_bytes(
@@ -6,7 +6,10 @@
 use std::{
     borrow::Cow,
     cmp::Reverse,
+    fmt::Write as _,
+    io::{self, Write as _},
     path::{Path, PathBuf},
+    sync::LazyLock,
 };
 
 use anyhow::anyhow;
@@ -14,6 +17,10 @@
 use async_walkdir::WalkDir;
 use bytes::Bytes;
 use futures::stream::StreamExt as _;
+use indicatif::ProgressBar;
+use memchr::memmem::Finder;
+use tokio::{fs::File, io::AsyncReadExt as _, task::JoinSet};
+use tokio_util::task::{AbortOnDropHandle, TaskTracker};
 
 use super::{
     super::state_store::{ExtractedOutput, RelocatedOutput, Stage},
@@ -21,7 +28,7 @@
 };
 use crate::{
     context::Context,
-    ext::tokio::path::PathExt as _,
+    ext::tokio::{fs::write_atomically, path::PathExt as _},
     package::{
         PackageExt as _,
         prepared::{PreparedPackage, download::Download, formula::PreparedFormula},
@@ -30,6 +37,15 @@
 
 type ReplacementPairs = [(&'static str, String); 4];
 
+const SNIFF_LENGTH: u64 = 1 << 13;
+
+const CHUNK_LENGTH: u64 = 1 << 16;
+
+const PLACEHOLDER_PREFIX: &[u8] = b"@@HOMEBREW_";
+
+static PLACEHOLDER_PREFIX_FINDER: LazyLock<Finder<'static>> =
+    LazyLock::new(|| Finder::new(PLACEHOLDER_PREFIX));
+
 #[derive(Clone)]
 pub(crate) struct Relocator;
 
@@ -63,6 +79,10 @@ fn should_run(
         prepared_formula.should_relocate(&cellar_dir_path)
     }
 
+    fn awaits_dependencies(&self) -> bool {
+        true
+    }
+
     fn running_prefix(&self) -> Option<&'static str> {
         Some("Relocating")
     }
@@ -94,6 +114,7 @@ async fn execute(
         &self,
         state: &Self::State,
         prepared_package: &PreparedPackage<Download>,
+        pb: &ProgressBar,
         context: &Context,
     ) -> anyhow::Result<Self::Staging> {
         let PreparedPackage::Formula(prepared_formula) = prepared_package else {
@@ -105,7 +126,7 @@ async fn execute(
         let replacement_pairs = state;
 
         let keg_dir_path = self
-            .patch(prepared_formula, replacement_pairs, context)
+            .patch(prepared_formula, replacement_pairs, pb, context)
             .await?;
 
         let staging = keg_dir_path;
@@ -146,6 +167,7 @@ async fn patch(
         &self,
         prepared_formula: &PreparedFormula<Download>,
         replacement_pairs: &ReplacementPairs,
+        pb: &ProgressBar,
         context: &Context,
     ) -> anyhow::Result<PathBuf> {
         let id = prepared_formula.id();
@@ -154,7 +176,8 @@ async fn patch(
 
         let keg_dir_path = context.homebrew_dirs.keg_dir(id, version_revision);
 
-        self.patch_keg(&keg_dir_path, replacement_pairs).await?;
+        self.patch_keg(&keg_dir_path, replacement_pairs, pb, context)
+            .await?;
 
         Ok(keg_dir_path)
     }
@@ -163,7 +186,13 @@ async fn patch_keg(
         &self,
         keg_dir_path: &Path,
         replacement_pairs: &ReplacementPairs,
+        pb: &ProgressBar,
+        context: &Context,
     ) -> anyhow::Result<()> {
+        let mut unrelocated_file_paths = Vec::new();
+
+        let mut set = JoinSet::new();
+
         let mut keg_entries = WalkDir::new(keg_dir_path);
 
         while let Some(keg_entry) = keg_entries.next().await {
@@ -175,9 +204,146 @@ async fn patch_keg(
                 continue;
             }
 
-            self.patch_file(&keg_entry_path, replacement_pairs).await?;
+            while set.len() >= context.available_parallelism {
+                if let Some(res) = set.join_next().await
+                    && let Some(unrelocated_file_path) = res??
+                {
+                    unrelocated_file_paths.push(unrelocated_file_path);
+                }
+            }
+
+            let this = self.clone();
+
+            let replacement_pairs = replacement_pairs.clone();
+
+            let tasks = context.tasks.clone();
+
+            set.spawn(context.tasks.track_future(async move {
+                this.patch_keg_file(keg_entry_path, &replacement_pairs, &tasks)
+                    .await
+            }));
+        }
+
+        while let Some(res) = set.join_next().await {
+            if let Some(unrelocated_file_path) = res?? {
+                unrelocated_file_paths.push(unrelocated_file_path);
+            }
+        }
+
+        unrelocated_file_paths.sort();
+
+        self.report_unrelocated(keg_dir_path, &unrelocated_file_paths, pb)?;
+
+        Ok(())
+    }
+
+    async fn patch_keg_file(
+        &self,
+        keg_file_path: PathBuf,
+        replacement_pairs: &ReplacementPairs,
+        tasks: &TaskTracker,
+    ) -> anyhow::Result<Option<PathBuf>> {
+        let mut file = File::open(&keg_file_path).await?;
+
+        let mut bytes = Vec::new();
+
+        (&mut file)
+            .take(SNIFF_LENGTH)
+            .read_to_end(&mut bytes)
+            .await?;
+
+        let has_magic = self.has_magic(&bytes);
+
+        let is_text = !has_magic && !bytes.contains(&0);
+
+        if !has_magic && !is_text {
+            let is_unrelocated = stream_has_placeholder(file, bytes, replacement_pairs).await?;
+
+            let unrelocated_file_path = is_unrelocated.then_some(keg_file_path);
+
+            return Ok(unrelocated_file_path);
+        }
+
+        file.read_to_end(&mut bytes).await?;
+
+        let bytes = Bytes::from(bytes);
+
+        if !has_placeholder(&bytes, replacement_pairs, tasks).await? {
+            return Ok(None);
+        }
+
+        let is_unrelocated = if has_magic {
+            let patched_bytes = self
+                .patch_file(&keg_file_path, bytes, replacement_pairs, tasks)
+                .await?;
+
+            has_placeholder(&patched_bytes, replacement_pairs, tasks).await?
+        } else {
+            self.patch_text_file(&keg_file_path, &bytes, replacement_pairs)
+                .await?;
+
+            false
+        };
+
+        let unrelocated_file_path = is_unrelocated.then_some(keg_file_path);
+
+        Ok(unrelocated_file_path)
+    }
+
+    async fn patch_text_file(
+        &self,
+        dest_file_path: &Path,
+        bytes: &[u8],
+        replacement_pairs: &ReplacementPairs,
+    ) -> anyhow::Result<()> {
+        let replaced_bytes = self.replace_text(bytes, replacement_pairs);
+
+        let Cow::Owned(replaced_bytes) = replaced_bytes else {
+            return Ok(());
+        };
+
+        // The extractor journals the whole keg as created, so rewriting files inside it needs no
+        // journal entry of its own.
+        write_atomically(dest_file_path, &replaced_bytes, true).await?;
+
+        Ok(())
+    }
+
+    #[expect(clippy::unused_self)]
+    fn report_unrelocated(
+        &self,
+        keg_dir_path: &Path,
+        unrelocated_file_paths: &[PathBuf],
+        pb: &ProgressBar,
+    ) -> anyhow::Result<()> {
+        if unrelocated_file_paths.is_empty() {
+            return Ok(());
+        }
+
+        let keg_dir_path_display = keg_dir_path.display();
+
+        let mut message =
+            format!(r#"Warning: Keg "{keg_dir_path_display}" still contains placeholders in:"#);
+
+        for unrelocated_file_path in unrelocated_file_paths {
+            let unrelocated_file_path = unrelocated_file_path
+                .strip_prefix(keg_dir_path)
+                .unwrap_or(unrelocated_file_path)
+                .display();
+
+            write!(message, "\n  {unrelocated_file_path}")?;
         }
 
+        message.push('\n');
+
+        pb.suspend(|| {
+            let mut stderr = io::stderr();
+
+            stderr.write_all(message.as_bytes())?;
+
+            stderr.flush()
+        })?;
+
         Ok(())
     }
 
@@ -200,14 +366,67 @@ fn replace_pstr<'a>(
             },
         )
     }
+
+    #[expect(clippy::unused_self)]
+    fn replace_text<'a>(
+        &self,
+        bytes: &'a [u8],
+        replacement_pairs: &ReplacementPairs,
+    ) -> Cow<'a, [u8]> {
+        let mut replaced_bytes = Vec::new();
+
+        let mut copied_index = 0;
+
+        let mut search_index = 0;
+
+        while let Some(offset) = bytes
+            .get(search_index..)
+            .and_then(|rest_bytes| PLACEHOLDER_PREFIX_FINDER.find(rest_bytes))
+        {
+            let index = search_index.saturating_add(offset);
+
+            let rest_bytes = bytes.get(index..).unwrap_or_default();
+
+            let Some((placeholder, replacement_pstr)) =
+                placeholder_at(rest_bytes, replacement_pairs)
+            else {
+                search_index = index.saturating_add(1);
+
+                continue;
+            };
+
+            let head_bytes = bytes.get(copied_index..index).unwrap_or_default();
+
+            replaced_bytes.extend_from_slice(head_bytes);
+            replaced_bytes.extend_from_slice(replacement_pstr.as_bytes());
+
+            copied_index = index.saturating_add(placeholder.len());
+
+            search_index = copied_index;
+        }
+
+        if copied_index == 0 {
+            return Cow::Borrowed(bytes);
+        }
+
+        let tail_bytes = bytes.get(copied_index..).unwrap_or_default();
+
+        replaced_bytes.extend_from_slice(tail_bytes);
+
+        Cow::Owned(replaced_bytes)
+    }
 }
 
 trait RelocatorExt {
+    fn has_magic(&self, peek_bytes: &[u8]) -> bool;
+
     async fn patch_file(
         &self,
         dest_file_path: &Path,
+        bytes: Bytes,
         replacement_pairs: &ReplacementPairs,
-    ) -> anyhow::Result<()>;
+        tasks: &TaskTracker,
+    ) -> anyhow::Result<Bytes>;
 
     fn replace_bytes(
         &self,
@@ -215,3 +434,141 @@ fn replace_bytes(
         replacement_pairs: &ReplacementPairs,
     ) -> anyhow::Result<Vec<u8>>;
 }
+
+async fn has_placeholder(
+    bytes: &Bytes,
+    replacement_pairs: &ReplacementPairs,
+    tasks: &TaskTracker,
+) -> anyhow::Result<bool> {
+    let handle = tasks.spawn_blocking({
+        let bytes = bytes.clone();
+
+        let replacement_pairs = replacement_pairs.clone();
+
+        move || contains_placeholder(&bytes, &replacement_pairs)
+    });
+    let handle = AbortOnDropHandle::new(handle);
+
+    let has_placeholder = handle.await?;
+
+    Ok(has_placeholder)
+}
+
+// Files that are neither binaries we can rewrite nor text are only checked, so they are searched
+// chunk by chunk instead of being read into memory whole.
+async fn stream_has_placeholder(
+    mut file: File,
+    mut bytes: Vec<u8>,
+    replacement_pairs: &ReplacementPairs,
+) -> anyhow::Result<bool> {
+    let overlap_length = replacement_pairs
+        .iter()
+        .map(|(placeholder, _)| placeholder.len())
+        .max()
+        .unwrap_or_default()
+        .saturating_sub(1);
+
+    loop {
+        if contains_placeholder(&bytes, replacement_pairs) {
+            return Ok(true);
+        }
+
+        let overlap_index = bytes.len().saturating_sub(overlap_length);
+
+        bytes.drain(..overlap_index);
+
+        let read_length = (&mut file)
+            .take(CHUNK_LENGTH)
+            .read_to_end(&mut bytes)
+            .await?;
+
+        if read_length == 0 {
+            return Ok(false);
+        }
+    }
+}
+
+fn contains_placeholder(bytes: &[u8], replacement_pairs: &ReplacementPairs) -> bool {
+    PLACEHOLDER_PREFIX_FINDER.find_iter(bytes).any(|index| {
+        let rest_bytes = bytes.get(index..).unwrap_or_default();
+
+        placeholder_at(rest_bytes, replacement_pairs).is_some()
+    })
+}
+
+fn placeholder_at<'a>(
+    bytes: &[u8],
+    replacement_pairs: &'a ReplacementPairs,
+) -> Option<&'a (&'static str, String)> {
+    replacement_pairs
+        .iter()
+        .find(|(placeholder, _)| bytes.starts_with(placeholder.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replacement_pairs() -> ReplacementPairs {
+        [
+            (
+                Relocator::REPOSITORY_PLACEHOLDER,
+                "/opt/homebrew".to_owned(),
+            ),
+            (
+                Relocator::LIBRARY_PLACEHOLDER,
+                "/opt/homebrew/Library".to_owned(),
+            ),
+            (Relocator::PREFIX_PLACEHOLDER, "/opt/homebrew".to_owned()),
+            (
+                Relocator::CELLAR_PLACEHOLDER,
+                "/opt/homebrew/Cellar".to_owned(),
+            ),
+        ]
+    }
+
+    #[test]
+    fn replace_text_replaces_every_placeholder() {
+        let bytes =
+            b"prefix=@@HOMEBREW_PREFIX@@\ncellar=@@HOMEBREW_CELLAR@@/foo @@HOMEBREW_PREFIX@@";
+
+        let replaced_bytes = Relocator.replace_text(bytes, &replacement_pairs());
+
+        assert_eq!(
+            &*replaced_bytes,
+            b"prefix=/opt/homebrew\ncellar=/opt/homebrew/Cellar/foo /opt/homebrew"
+        );
+    }
+
+    #[test]
+    fn replace_text_handles_adjacent_placeholders() {
+        let bytes = b"@@HOMEBREW_PREFIX@@HOMEBREW_CELLAR@@";
+
+        let replaced_bytes = Relocator.replace_text(bytes, &replacement_pairs());
+
+        assert_eq!(&*replaced_bytes, b"/opt/homebrewHOMEBREW_CELLAR@@");
+    }
+
+    #[test]
+    fn replace_text_borrows_without_placeholder() {
+        let bytes = b"@@HOMEBREW_UNKNOWN@@ and @@HOMEBREW_";
+
+        let replaced_bytes = Relocator.replace_text(bytes, &replacement_pairs());
+
+        assert!(matches!(replaced_bytes, Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn contains_placeholder_ignores_unknown_placeholders() {
+        let replacement_pairs = replacement_pairs();
+
+        assert!(contains_placeholder(
+            b"@@HOMEBREW_UNKNOWN@@ @@HOMEBREW_LIBRARY@@",
+            &replacement_pairs
+        ));
+        assert!(!contains_placeholder(
+            b"@@HOMEBREW_UNKNOWN@@ @@HOMEBREW_",
+            &replacement_pairs
+        ));
+    }
+}
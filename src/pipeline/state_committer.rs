@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
 use super::state_store::{Payloads, Publish, Session, Stage};
+use crate::package::PackageExt as _;
 
 pub(super) struct StateCommitter {
     pub(super) passed_prefix: Option<&'static str>,
@@ -59,6 +60,12 @@ pub(super) fn finalize<Output>(
                     true
                 }
             });
+
+            if passed_stage >= Stage::Linked {
+                let id = session.prepared_package.id();
+
+                session.schedule.mark_linked(id);
+            }
         }
 
         Ok(output)
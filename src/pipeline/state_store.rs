@@ -7,6 +7,7 @@
 use indicatif::ProgressBar;
 use tokio::sync::watch;
 
+use super::schedule::Schedule;
 use crate::{
     context::Context,
     ext::std::sync::OnceLockExt as _,
@@ -22,6 +23,8 @@ pub(crate) struct Session {
 
     pub(super) pb: ProgressBar,
 
+    pub(super) schedule: Arc<Schedule>,
+
     pub(super) context: Arc<Context>,
 }
 
@@ -29,6 +32,7 @@ impl Session {
     pub(super) fn new(
         prepared_package: PreparedPackage<Download>,
         pb: ProgressBar,
+        schedule: Arc<Schedule>,
         context: Arc<Context>,
     ) -> Self {
         Self {
@@ -38,6 +42,8 @@ pub(super) fn new(
 
             pb,
 
+            schedule,
+
             context,
         }
     }
@@ -79,6 +85,7 @@ pub(crate) enum Stage {
     Relocated,
     Linked,
     Artifacted,
+    Receipted,
 }
 
 #[derive(Default)]
@@ -91,6 +98,7 @@ pub(super) struct Payloads {
     pub(super) relocated: OnceLock<RelocatedOutput>,
     pub(super) linked: OnceLock<LinkedOutput>,
     pub(super) artifacted: OnceLock<ArtifactedOutput>,
+    pub(super) receipted: OnceLock<ReceiptedOutput>,
 }
 
 pub(super) trait Publish<Output> {
@@ -259,3 +267,24 @@ fn subscribe(&self) -> anyhow::Result<Option<&ArtifactedOutput>> {
         Ok(payload)
     }
 }
+
+#[derive(Clone)]
+pub(crate) struct ReceiptedOutput {
+    pub(super) receipt_file_path: PathBuf,
+}
+
+impl Publish<ReceiptedOutput> for Payloads {
+    fn publish(&self, output: &ReceiptedOutput) -> anyhow::Result<()> {
+        self.receipted.try_set(output.clone())?;
+
+        Ok(())
+    }
+}
+
+impl Subscribe<ReceiptedOutput> for Payloads {
+    fn subscribe(&self) -> anyhow::Result<Option<&ReceiptedOutput>> {
+        let payload = self.receipted.get();
+
+        Ok(payload)
+    }
+}
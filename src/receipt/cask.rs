@@ -1,23 +1,115 @@
-use serde::Serialize;
+use std::path::{Path, PathBuf};
 
-#[derive(Serialize)]
-struct CaskReceipt {
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::{
+    ARCH,
+    BuiltOn,
+    RECEIPT_FILE_NAME,
+    formula::RuntimeDependency as FormulaRuntimeDependency,
+};
+use crate::{
+    context::Context,
+    package::{PackageExt as _, prepared::cask::PreparedCask},
+};
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct CaskReceipt {
+    installed_as_dependency: bool,
     installed_on_request: bool,
+    loaded_from_api: bool,
+    time: Option<i64>,
+    runtime_dependencies: Option<RuntimeDependencies>,
     source: Source,
+    arch: Option<String>,
+    built_on: Option<BuiltOn>,
+
+    #[serde(flatten)]
+    others: Map<String, Value>,
 }
 
 impl CaskReceipt {
-    fn new(installed_on_request: bool, version: String) -> Self {
+    pub(crate) fn new<Dl>(
+        prepared_cask: &PreparedCask<Dl>,
+        installed_at: DateTime<Utc>,
+        context: &Context,
+    ) -> Self {
+        let is_requested = prepared_cask.is_requested();
+
         Self {
-            installed_on_request,
+            installed_as_dependency: !is_requested,
+            installed_on_request: is_requested,
+            loaded_from_api: true,
+            time: Some(installed_at.timestamp()),
+            runtime_dependencies: Some(prepared_cask.runtime_dependencies().clone()),
             source: Source {
-                version,
+                tap: Some(prepared_cask.tap().to_owned()),
+                version: Some(prepared_cask.version().to_owned()),
+
+                others: Map::new(),
             },
+            arch: Some(ARCH.to_owned()),
+            built_on: Some(BuiltOn::host(context)),
+
+            others: Map::new(),
         }
     }
+
+    pub(crate) async fn load(metadata_dir_path: &Path) -> anyhow::Result<Option<Self>> {
+        let receipt_file_path = metadata_dir_path.join(RECEIPT_FILE_NAME);
+
+        super::read(&receipt_file_path).await
+    }
+
+    pub(crate) async fn save(&self, metadata_dir_path: &Path) -> anyhow::Result<PathBuf> {
+        let receipt_file_path = metadata_dir_path.join(RECEIPT_FILE_NAME);
+
+        super::write(&receipt_file_path, self).await?;
+
+        Ok(receipt_file_path)
+    }
+
+    pub(crate) fn installed_on_request(&self) -> bool {
+        self.installed_on_request
+    }
+
+    pub(crate) fn time(&self) -> Option<i64> {
+        self.time
+    }
+
+    pub(crate) fn tap(&self) -> Option<&str> {
+        self.source.tap.as_deref()
+    }
+
+    pub(crate) fn runtime_dependencies(&self) -> Option<&RuntimeDependencies> {
+        self.runtime_dependencies.as_ref()
+    }
+}
+
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct RuntimeDependencies {
+    pub(crate) cask: Vec<RuntimeDependency>,
+    pub(crate) formula: Vec<FormulaRuntimeDependency>,
 }
 
-#[derive(Serialize)]
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct RuntimeDependency {
+    pub(crate) full_name: String,
+    pub(crate) version: String,
+    #[serde(default)]
+    pub(crate) declared_directly: bool,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
 struct Source {
-    version: String,
+    tap: Option<String>,
+    version: Option<String>,
+
+    #[serde(flatten)]
+    others: Map<String, Value>,
 }
@@ -1,30 +1,139 @@
-use serde::Serialize;
+use std::path::{Path, PathBuf};
 
-#[derive(Serialize)]
-struct FormulaReceipt {
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::{ARCH, BuiltOn, RECEIPT_FILE_NAME};
+use crate::{
+    context::Context,
+    package::{PackageExt as _, prepared::formula::PreparedFormula, raw::formula::DependencyType},
+};
+
+#[expect(clippy::struct_excessive_bools)]
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct FormulaReceipt {
+    installed_as_dependency: bool,
     installed_on_request: bool,
+    poured_from_bottle: bool,
+    loaded_from_api: bool,
+    time: Option<i64>,
+    runtime_dependencies: Option<Vec<RuntimeDependency>>,
     source: Source,
+    arch: Option<String>,
+    built_on: Option<BuiltOn>,
+    compiler: Option<String>,
+
+    #[serde(flatten)]
+    others: Map<String, Value>,
 }
 
 impl FormulaReceipt {
-    fn new(installed_on_request: bool, version: String) -> Self {
-        Self {
-            installed_on_request,
-            source: Source {
-                versions: Versions {
-                    stable: version,
-                },
-            },
+    const DEFAULT_COMPILER: &str = cfg_select! {
+        target_os = "macos" => "clang",
+        target_os = "linux" => "gcc",
+    };
+
+    pub(crate) fn new<Dl>(
+        bottle_receipt: Option<Self>,
+        prepared_formula: &PreparedFormula<Dl>,
+        installed_at: DateTime<Utc>,
+        context: &Context,
+    ) -> Self {
+        let mut this = bottle_receipt.unwrap_or_default();
+
+        let is_requested = prepared_formula.is_requested();
+
+        this.installed_as_dependency = !is_requested;
+        this.installed_on_request = is_requested;
+        this.poured_from_bottle = true;
+        this.loaded_from_api = true;
+        this.time = Some(installed_at.timestamp());
+        this.runtime_dependencies = Some(prepared_formula.runtime_dependencies().to_vec());
+
+        this.source.tap = Some(prepared_formula.tap().to_owned());
+        this.source.spec = Some("stable".to_owned());
+        this.source.versions.stable = Some(prepared_formula.version().to_owned());
+        this.source.versions.head = None;
+        this.source.versions.version_scheme = prepared_formula.version_scheme();
+
+        this.arch = Some(ARCH.to_owned());
+
+        if this.built_on.is_none() {
+            this.built_on = Some(BuiltOn::host(context));
+        }
+
+        if this.compiler.is_none() {
+            this.compiler = Some(Self::DEFAULT_COMPILER.to_owned());
         }
+
+        this
+    }
+
+    pub(crate) async fn load(keg_dir_path: &Path) -> anyhow::Result<Option<Self>> {
+        let receipt_file_path = keg_dir_path.join(RECEIPT_FILE_NAME);
+
+        super::read(&receipt_file_path).await
+    }
+
+    pub(crate) async fn save(&self, keg_dir_path: &Path) -> anyhow::Result<PathBuf> {
+        let receipt_file_path = keg_dir_path.join(RECEIPT_FILE_NAME);
+
+        super::write(&receipt_file_path, self).await?;
+
+        Ok(receipt_file_path)
+    }
+
+    pub(crate) fn installed_on_request(&self) -> bool {
+        self.installed_on_request
+    }
+
+    pub(crate) fn time(&self) -> Option<i64> {
+        self.time
+    }
+
+    pub(crate) fn tap(&self) -> Option<&str> {
+        self.source.tap.as_deref()
+    }
+
+    pub(crate) fn runtime_dependencies(&self) -> &[RuntimeDependency] {
+        self.runtime_dependencies.as_deref().unwrap_or_default()
     }
 }
 
-#[derive(Serialize)]
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
 struct Source {
+    tap: Option<String>,
+    spec: Option<String>,
     versions: Versions,
+
+    #[serde(flatten)]
+    others: Map<String, Value>,
 }
 
-#[derive(Serialize)]
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
 struct Versions {
-    stable: String,
+    stable: Option<String>,
+    head: Option<String>,
+    version_scheme: u64,
+
+    #[serde(flatten)]
+    others: Map<String, Value>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct RuntimeDependency {
+    pub(crate) full_name: String,
+    pub(crate) version: String,
+    #[serde(default)]
+    pub(crate) revision: u64,
+    #[serde(default)]
+    pub(crate) pkg_version: String,
+    #[serde(default)]
+    pub(crate) declared_directly: bool,
+    #[serde(default)]
+    pub(crate) dependency_type: DependencyType,
 }
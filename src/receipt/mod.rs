@@ -1,2 +1,80 @@
-mod cask;
-mod formula;
+pub(crate) mod cask;
+pub(crate) mod formula;
+
+use std::path::Path;
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Map, Value};
+use tokio::{fs, io};
+
+use crate::{context::Context, ext::tokio::fs::write_atomically};
+
+pub(crate) const RECEIPT_FILE_NAME: &str = "INSTALL_RECEIPT.json";
+
+const ARCH: &str = cfg_select! {
+    target_arch = "aarch64" => "arm64",
+    target_arch = "x86_64" => "x86_64",
+};
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct BuiltOn {
+    os: Option<String>,
+    os_version: Option<String>,
+
+    #[serde(flatten)]
+    others: Map<String, Value>,
+}
+
+impl BuiltOn {
+    fn host(context: &Context) -> Self {
+        let os = cfg_select! {
+            target_os = "macos" => "Macintosh",
+            target_os = "linux" => "Linux",
+        };
+
+        let version = context.info.version();
+
+        let os_version = cfg_select! {
+            target_os = "macos" => format!("macOS {version}"),
+            target_os = "linux" => format!("{} {version}", context.info.os_type()),
+        };
+
+        Self {
+            os: Some(os.to_owned()),
+            os_version: Some(os_version),
+
+            others: Map::new(),
+        }
+    }
+}
+
+async fn read<Receipt: DeserializeOwned>(
+    receipt_file_path: &Path,
+) -> anyhow::Result<Option<Receipt>> {
+    let bytes = match fs::read(receipt_file_path).await {
+        Ok(bytes) => bytes,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+
+    let receipt = serde_json::from_slice(&bytes).with_context(|| {
+        let receipt_file_pstr = receipt_file_path.to_string_lossy();
+
+        format!(r#"Receipt "{receipt_file_pstr}" is malformed"#)
+    })?;
+
+    Ok(Some(receipt))
+}
+
+async fn write<Receipt: Serialize>(
+    receipt_file_path: &Path,
+    receipt: &Receipt,
+) -> anyhow::Result<()> {
+    let bytes = serde_json::to_vec_pretty(receipt)?;
+
+    write_atomically(receipt_file_path, &bytes, false).await?;
+
+    Ok(())
+}
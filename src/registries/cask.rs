@@ -2,17 +2,22 @@
 
 use anyhow::anyhow;
 use async_recursion::async_recursion;
+use bytes::Bytes;
 use foyer::{Cache, CacheBuilder};
 use futures::future;
+use tokio::sync::OnceCell;
 
 use super::{
     FormulaRegistry,
     RegistryExt,
     RegistryJsonExt,
     compatibility::{CaskCompatibility as _, Compatibility},
+    index::Index,
+    tap::{Tap, TapKind},
+    tap_migrations::TapMigrations,
 };
 use crate::{
-    context::{Context, dirs::ProjectDirs as _},
+    context::{Context, mirrors},
     package::{
         PackageExt as _,
         raw::cask::RawCask,
@@ -23,6 +28,10 @@
 pub(super) struct CaskRegistry {
     store: Cache<Arc<str>, Arc<ResolvedCask>>,
 
+    index: OnceCell<Result<Index, anyhow::Error>>,
+
+    tap_migrations: OnceCell<Option<TapMigrations>>,
+
     formula_registry: Arc<FormulaRegistry>,
 
     compatibility: Arc<Compatibility>,
@@ -39,6 +48,10 @@ pub(super) fn new(
         Self {
             store: CacheBuilder::new(usize::MAX).build(),
 
+            index: OnceCell::new(),
+
+            tap_migrations: OnceCell::new(),
+
             formula_registry,
 
             compatibility,
@@ -51,6 +64,9 @@ pub(super) fn new(
 impl RegistryExt for CaskRegistry {
     type ResolvedPackage = ResolvedCask;
 
+    const TAP: &str = "homebrew/cask";
+    const TAP_KIND: TapKind = TapKind::Cask;
+
     const API_URL: &str = "https://formulae.brew.sh/api/cask/{}.json";
 
     const JSON_URL: &str = "https://formulae.brew.sh/api/cask.json";
@@ -73,6 +89,8 @@ async fn resolve_with_stack(
         package: Arc<str>,
         mut stack: Vec<Arc<str>>,
     ) -> anyhow::Result<Arc<ResolvedCask>> {
+        let package = self.canonicalize(package).await?;
+
         if stack.contains(&package) {
             let cask = package;
 
@@ -111,16 +129,15 @@ async fn fetch_with_stack(
         package: Arc<str>,
         stack: Vec<Arc<str>>,
     ) -> anyhow::Result<Arc<ResolvedCask>> {
-        let api_url = Self::API_URL.replace("{}", &package);
+        let bytes = self.fetch_json(&package).await?;
 
-        let resp = self.context.client.get(api_url).send().await?;
-        let resp = resp.error_for_status()?;
+        let mut raw_cask: RawCask = serde_json::from_slice(&bytes)?;
 
-        let bytes = resp.bytes().await?;
+        raw_cask.apply_variation(&self.context)?;
 
-        let raw_cask: RawCask = serde_json::from_slice(&bytes)?;
+        let json_id = Self::qualify(raw_cask.tap(), raw_cask.id());
 
-        self.save_json(raw_cask.id(), bytes).await?;
+        self.save_json(&json_id, bytes).await?;
 
         let raw_dependencies = raw_cask
             .dependencies()
@@ -182,14 +199,139 @@ async fn fetch_with_stack(
 
         Ok(resolved_cask)
     }
+
+    async fn fetch_json(&self, package: &str) -> anyhow::Result<Bytes> {
+        if let Some((tap, id)) = Tap::split(package)
+            && tap != Self::TAP
+        {
+            let tap = Tap::new(tap, Self::TAP_KIND)?;
+
+            let bytes = tap.fetch_json(id, &self.context).await?;
+
+            return Ok(bytes);
+        }
+
+        let index = self.index().await?;
+
+        if let Ok(index) = index
+            && let Some(bytes) = index.get(package)?
+        {
+            return Ok(bytes);
+        }
+
+        if self.context.config.offline {
+            let Some(bytes) = self.load_json(package).await? else {
+                let json_file_path = self.json_path(package);
+                let json_file_path = json_file_path.display();
+
+                let err = anyhow!(r#"Cask "{package}" is not cached at "{json_file_path}""#);
+
+                return Err(err);
+            };
+
+            return Ok(bytes);
+        }
+
+        if !self.context.config.allow_unsigned_api {
+            let err = match index {
+                Ok(_) => anyhow!(r#"Cask "{package}" is not in the signed API index"#),
+                Err(cause) => {
+                    let hint = super::ALLOW_UNSIGNED_API_HINT;
+
+                    anyhow!("Signed API index is unavailable ({cause}), {hint}")
+                },
+            };
+
+            return Err(err);
+        }
+
+        let api_url = Self::API_URL.replace("{}", package);
+
+        let api_urls = self.context.mirrors.api_urls(&api_url);
+
+        let resp = mirrors::send_with_fallback(&self.context.credentials, &api_urls, |api_url| {
+            self.context.client.get(api_url)
+        })
+        .await?;
+        let resp = resp.error_for_status()?;
+
+        let bytes = resp.bytes().await?;
+
+        Ok(bytes)
+    }
+
+    async fn index(&self) -> anyhow::Result<Result<&Index, &anyhow::Error>> {
+        let index = self
+            .index
+            .get_or_try_init(async || {
+                let jws_json_file_path = self.context.homebrew_dirs.cask_jws_json_file();
+
+                Index::load(
+                    Self::JWS_JSON_URL,
+                    &jws_json_file_path,
+                    "token",
+                    &self.context,
+                )
+                .await
+            })
+            .await?;
+
+        Ok(index.as_ref())
+    }
+
+    async fn tap_migrations(&self) -> anyhow::Result<Option<&TapMigrations>> {
+        let tap_migrations = self
+            .tap_migrations
+            .get_or_try_init(async || {
+                let jws_json_file_path = self
+                    .context
+                    .homebrew_dirs
+                    .cask_tap_migrations_jws_json_file();
+
+                TapMigrations::load(
+                    Self::TAP_MIGRATIONS_JWS_URL,
+                    &jws_json_file_path,
+                    &self.context,
+                )
+                .await
+            })
+            .await?;
+
+        Ok(tap_migrations.as_ref())
+    }
+
+    async fn canonicalize(&self, package: Arc<str>) -> anyhow::Result<Arc<str>> {
+        let package = match Tap::split(&package) {
+            Some((tap, id)) if tap == Self::TAP => Arc::from(id),
+            _ => package,
+        };
+
+        let Ok(index) = self.index().await? else {
+            return Ok(package);
+        };
+
+        if index.contains(&package) {
+            return Ok(package);
+        }
+
+        if let Some(id) = index.alias_target(&package) {
+            return Ok(Arc::from(id));
+        }
+
+        if let Some(id) = index.rename_target(&package) {
+            let message = format!(r#"Cask "{package}" was renamed to "{id}""#);
+
+            super::notify(&message).await?;
+
+            return Ok(Arc::from(id));
+        }
+
+        Ok(package)
+    }
 }
 
 impl RegistryJsonExt for CaskRegistry {
     fn json_path(&self, id: &str) -> PathBuf {
-        let file_name = format!("{id}.json");
-
-        let cache_dir_path = self.context.homebrew_dirs.cache_dir();
-
-        cache_dir_path.join("api/cask").join(file_name)
+        self.context.homebrew_dirs.cask_json_file(id)
     }
 }
@@ -1,17 +1,23 @@
-use std::{path::PathBuf, sync::Arc};
+use std::{collections::HashSet, path::PathBuf, sync::Arc};
 
 use anyhow::anyhow;
 use async_recursion::async_recursion;
+use bytes::Bytes;
 use foyer::{Cache, CacheBuilder};
 use futures::future;
+use tokio::sync::OnceCell;
 
 use super::{
+    DependencyFilter,
     RegistryExt,
     RegistryJsonExt,
     compatibility::{Compatibility, FormulaCompatibility as _},
+    index::Index,
+    tap::{Tap, TapKind},
+    tap_migrations::TapMigrations,
 };
 use crate::{
-    context::{Context, dirs::ProjectDirs as _},
+    context::{Context, mirrors},
     package::{
         PackageExt as _,
         raw::formula::RawFormula,
@@ -22,16 +28,32 @@
 pub(super) struct FormulaRegistry {
     store: Cache<Arc<str>, Arc<ResolvedFormula>>,
 
+    index: OnceCell<Result<Index, anyhow::Error>>,
+
+    tap_migrations: OnceCell<Option<TapMigrations>>,
+
+    dependency_filter: DependencyFilter,
+
     compatibility: Arc<Compatibility>,
 
     context: Arc<Context>,
 }
 
 impl FormulaRegistry {
-    pub(super) fn new(compatibility: Arc<Compatibility>, context: Arc<Context>) -> Self {
+    pub(super) fn new(
+        dependency_filter: DependencyFilter,
+        compatibility: Arc<Compatibility>,
+        context: Arc<Context>,
+    ) -> Self {
         Self {
             store: CacheBuilder::new(usize::MAX).build(),
 
+            index: OnceCell::new(),
+
+            tap_migrations: OnceCell::new(),
+
+            dependency_filter,
+
             compatibility,
 
             context,
@@ -42,6 +64,9 @@ pub(super) fn new(compatibility: Arc<Compatibility>, context: Arc<Context>) -> S
 impl RegistryExt for FormulaRegistry {
     type ResolvedPackage = ResolvedFormula;
 
+    const TAP: &str = "homebrew/core";
+    const TAP_KIND: TapKind = TapKind::Formula;
+
     const API_URL: &str = "https://formulae.brew.sh/api/formula/{}.json";
 
     const JSON_URL: &str = "https://formulae.brew.sh/api/formula.json";
@@ -64,6 +89,8 @@ async fn resolve_with_stack(
         package: Arc<str>,
         mut stack: Vec<Arc<str>>,
     ) -> anyhow::Result<Arc<ResolvedFormula>> {
+        let package = self.canonicalize(package).await?;
+
         if stack.contains(&package) {
             let formula = package;
 
@@ -102,46 +129,45 @@ async fn fetch_with_stack(
         package: Arc<str>,
         stack: Vec<Arc<str>>,
     ) -> anyhow::Result<Arc<ResolvedFormula>> {
-        let api_url = Self::API_URL.replace("{}", &package);
+        let bytes = self.fetch_json(&package).await?;
 
-        let resp = self.context.client.get(api_url).send().await?;
-        let resp = resp.error_for_status()?;
+        let mut raw_formula: RawFormula = serde_json::from_slice(&bytes)?;
 
-        let bytes = resp.bytes().await?;
-
-        let raw_formula: RawFormula = serde_json::from_slice(&bytes)?;
+        raw_formula.apply_variation(&self.context)?;
 
-        self.save_json(raw_formula.id(), bytes).await?;
+        let json_id = Self::qualify(raw_formula.tap(), raw_formula.id());
 
-        let mut raw_dependencies = raw_formula
-            .dependencies()
-            .iter()
-            .map(|raw_dependency| {
-                let raw_dependency = raw_dependency.as_str();
+        self.save_json(&json_id, bytes).await?;
 
-                Arc::from(raw_dependency)
-            })
-            .collect::<Vec<_>>();
+        let mut typed_raw_dependencies = raw_formula.typed_dependencies().collect::<Vec<_>>();
 
         for (use_from_macos, bound) in raw_formula.uses_from_macos_bounds() {
             if self.compatibility.is_use_from_macos_dependency(bound) {
-                for raw_dependency in use_from_macos.dependencies() {
-                    let raw_dependency = Arc::from(raw_dependency);
-
-                    raw_dependencies.push(raw_dependency);
-                }
+                typed_raw_dependencies.extend(use_from_macos.dependencies());
             }
         }
 
-        let resolved_dependencies_futs = raw_dependencies.into_iter().map(async |raw_dependency| {
-            let this = Arc::clone(&self);
+        let mut raw_dependency_ids = HashSet::new();
 
-            let resolved_dependency = this
-                .resolve_with_stack(raw_dependency, stack.clone())
-                .await?;
+        let typed_raw_dependencies = typed_raw_dependencies
+            .into_iter()
+            .filter(|(_, dependency_type)| self.dependency_filter.includes(*dependency_type))
+            .filter(|(raw_dependency, _)| raw_dependency_ids.insert(*raw_dependency))
+            .map(|(raw_dependency, dependency_type)| (Arc::from(raw_dependency), dependency_type))
+            .collect::<Vec<(Arc<str>, _)>>();
 
-            anyhow::Ok(resolved_dependency)
-        });
+        let resolved_dependencies_futs =
+            typed_raw_dependencies
+                .into_iter()
+                .map(async |(raw_dependency, dependency_type)| {
+                    let this = Arc::clone(&self);
+
+                    let resolved_dependency = this
+                        .resolve_with_stack(raw_dependency, stack.clone())
+                        .await?;
+
+                    anyhow::Ok((resolved_dependency, dependency_type))
+                });
 
         let dependencies = future::try_join_all(resolved_dependencies_futs).await?;
 
@@ -154,14 +180,139 @@ async fn fetch_with_stack(
 
         Ok(resolved_formula)
     }
+
+    async fn fetch_json(&self, package: &str) -> anyhow::Result<Bytes> {
+        if let Some((tap, id)) = Tap::split(package)
+            && tap != Self::TAP
+        {
+            let tap = Tap::new(tap, Self::TAP_KIND)?;
+
+            let bytes = tap.fetch_json(id, &self.context).await?;
+
+            return Ok(bytes);
+        }
+
+        let index = self.index().await?;
+
+        if let Ok(index) = index
+            && let Some(bytes) = index.get(package)?
+        {
+            return Ok(bytes);
+        }
+
+        if self.context.config.offline {
+            let Some(bytes) = self.load_json(package).await? else {
+                let json_file_path = self.json_path(package);
+                let json_file_path = json_file_path.display();
+
+                let err = anyhow!(r#"Formula "{package}" is not cached at "{json_file_path}""#);
+
+                return Err(err);
+            };
+
+            return Ok(bytes);
+        }
+
+        if !self.context.config.allow_unsigned_api {
+            let err = match index {
+                Ok(_) => anyhow!(r#"Formula "{package}" is not in the signed API index"#),
+                Err(cause) => {
+                    let hint = super::ALLOW_UNSIGNED_API_HINT;
+
+                    anyhow!("Signed API index is unavailable ({cause}), {hint}")
+                },
+            };
+
+            return Err(err);
+        }
+
+        let api_url = Self::API_URL.replace("{}", package);
+
+        let api_urls = self.context.mirrors.api_urls(&api_url);
+
+        let resp = mirrors::send_with_fallback(&self.context.credentials, &api_urls, |api_url| {
+            self.context.client.get(api_url)
+        })
+        .await?;
+        let resp = resp.error_for_status()?;
+
+        let bytes = resp.bytes().await?;
+
+        Ok(bytes)
+    }
+
+    async fn index(&self) -> anyhow::Result<Result<&Index, &anyhow::Error>> {
+        let index = self
+            .index
+            .get_or_try_init(async || {
+                let jws_json_file_path = self.context.homebrew_dirs.formula_jws_json_file();
+
+                Index::load(
+                    Self::JWS_JSON_URL,
+                    &jws_json_file_path,
+                    "name",
+                    &self.context,
+                )
+                .await
+            })
+            .await?;
+
+        Ok(index.as_ref())
+    }
+
+    async fn tap_migrations(&self) -> anyhow::Result<Option<&TapMigrations>> {
+        let tap_migrations = self
+            .tap_migrations
+            .get_or_try_init(async || {
+                let jws_json_file_path = self
+                    .context
+                    .homebrew_dirs
+                    .formula_tap_migrations_jws_json_file();
+
+                TapMigrations::load(
+                    Self::TAP_MIGRATIONS_JWS_URL,
+                    &jws_json_file_path,
+                    &self.context,
+                )
+                .await
+            })
+            .await?;
+
+        Ok(tap_migrations.as_ref())
+    }
+
+    async fn canonicalize(&self, package: Arc<str>) -> anyhow::Result<Arc<str>> {
+        let package = match Tap::split(&package) {
+            Some((tap, id)) if tap == Self::TAP => Arc::from(id),
+            _ => package,
+        };
+
+        let Ok(index) = self.index().await? else {
+            return Ok(package);
+        };
+
+        if index.contains(&package) {
+            return Ok(package);
+        }
+
+        if let Some(id) = index.alias_target(&package) {
+            return Ok(Arc::from(id));
+        }
+
+        if let Some(id) = index.rename_target(&package) {
+            let message = format!(r#"Formula "{package}" was renamed to "{id}""#);
+
+            super::notify(&message).await?;
+
+            return Ok(Arc::from(id));
+        }
+
+        Ok(package)
+    }
 }
 
 impl RegistryJsonExt for FormulaRegistry {
     fn json_path(&self, id: &str) -> PathBuf {
-        let file_name = format!("{id}.json");
-
-        let cache_dir_path = self.context.homebrew_dirs.cache_dir();
-
-        cache_dir_path.join("api/formula").join(file_name)
+        self.context.homebrew_dirs.formula_json_file(id)
     }
 }
@@ -0,0 +1,230 @@
+use std::{collections::HashMap, path::Path};
+
+use anyhow::{Context as _, anyhow};
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use reqwest::{
+    StatusCode,
+    header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH},
+};
+use serde_json::Value;
+use tokio::{fs, io};
+
+use super::jws::Jws;
+use crate::{
+    context::{Context, mirrors},
+    ext::tokio::fs::write_atomically,
+};
+
+const ALIAS_KEYS: &[&str] = &["aliases"];
+
+const RENAME_KEYS: &[&str] = &["oldname", "oldnames", "old_tokens"];
+
+pub(super) struct Index {
+    entries: HashMap<String, Value>,
+    aliases: HashMap<String, String>,
+    renames: HashMap<String, String>,
+}
+
+impl Index {
+    pub(super) async fn load(
+        jws_json_url: &str,
+        jws_json_file_path: &Path,
+        id_key: &str,
+        context: &Context,
+    ) -> anyhow::Result<Result<Self, anyhow::Error>> {
+        let jws = match Self::load_jws(jws_json_url, jws_json_file_path, context).await? {
+            Ok(jws) => jws,
+            Err(cause) => return Ok(Err(cause)),
+        };
+
+        let values: Vec<Value> = serde_json::from_str(jws.payload())?;
+
+        let mut entries = HashMap::new();
+
+        let mut aliases = HashMap::new();
+
+        let mut renames = HashMap::new();
+
+        for value in values {
+            let Some(id) = value.get(id_key).and_then(Value::as_str) else {
+                continue;
+            };
+            let id = id.to_owned();
+
+            for alias in Self::names_of(&value, ALIAS_KEYS) {
+                aliases.insert(alias, id.clone());
+            }
+
+            for old_name in Self::names_of(&value, RENAME_KEYS) {
+                renames.insert(old_name, id.clone());
+            }
+
+            entries.insert(id, value);
+        }
+
+        let this = Self {
+            entries,
+            aliases,
+            renames,
+        };
+
+        Ok(Ok(this))
+    }
+
+    pub(super) async fn load_jws(
+        jws_json_url: &str,
+        jws_json_file_path: &Path,
+        context: &Context,
+    ) -> anyhow::Result<Result<Jws, anyhow::Error>> {
+        let mut cause = anyhow!(r#"API index "{jws_json_url}" is not cached"#);
+
+        if !context.config.offline {
+            match Self::refresh(jws_json_url, jws_json_file_path, context).await {
+                Ok(Some(jws)) => return Ok(Ok(jws)),
+                Ok(None) => {},
+                Err(err) if err.is::<reqwest::Error>() => cause = err,
+                Err(err) => {
+                    let err = err.context(format!(r#"API index "{jws_json_url}" is invalid"#));
+
+                    return Err(err);
+                },
+            }
+        }
+
+        let bytes = match fs::read(jws_json_file_path).await {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Err(cause)),
+            Err(err) => return Err(err.into()),
+        };
+
+        let jws = Jws::parse_verified(&bytes).with_context(|| {
+            let jws_json_file_path = jws_json_file_path.display();
+
+            format!(r#"API index "{jws_json_file_path}" is invalid"#)
+        })?;
+
+        Ok(Ok(jws))
+    }
+
+    fn names_of(value: &Value, keys: &[&str]) -> Vec<String> {
+        keys.iter()
+            .filter_map(|key| value.get(key))
+            .flat_map(|names| match names {
+                Value::String(name) => vec![name.clone()],
+                Value::Array(names) => names
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(ToOwned::to_owned)
+                    .collect(),
+                _ => Vec::new(),
+            })
+            .collect()
+    }
+
+    async fn refresh(
+        jws_json_url: &str,
+        jws_json_file_path: &Path,
+        context: &Context,
+    ) -> anyhow::Result<Option<Jws>> {
+        let jws_json_file_name = jws_json_file_path
+            .file_name()
+            .context("API index has no file name")?;
+        let jws_json_file_name = jws_json_file_name.to_string_lossy();
+
+        let etag_file_path = context.neobrew_dirs.etag_file(&jws_json_file_name);
+
+        let mut if_modified_since = None;
+
+        let mut if_none_match = None;
+
+        match fs::symlink_metadata(jws_json_file_path).await {
+            Ok(metadata) => {
+                let modified_at = metadata.modified()?;
+                let modified_at = DateTime::<Utc>::from(modified_at);
+                let modified_at = modified_at.format("%a, %d %b %Y %H:%M:%S GMT");
+
+                if_modified_since = Some(modified_at.to_string());
+
+                match fs::read_to_string(&etag_file_path).await {
+                    Ok(etag) => if_none_match = Some(etag.trim().to_owned()),
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => {},
+                    Err(err) => return Err(err.into()),
+                }
+            },
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {},
+            Err(err) => return Err(err.into()),
+        }
+
+        let jws_json_urls = context.mirrors.api_urls(jws_json_url);
+
+        let credentials = &context.credentials;
+
+        let resp = mirrors::send_with_fallback(credentials, &jws_json_urls, |jws_json_url| {
+            let mut req = context.client.get(jws_json_url);
+
+            if let Some(if_modified_since) = &if_modified_since {
+                req = req.header(IF_MODIFIED_SINCE, if_modified_since);
+            }
+
+            if let Some(if_none_match) = &if_none_match {
+                req = req.header(IF_NONE_MATCH, if_none_match);
+            }
+
+            req
+        })
+        .await?;
+
+        if resp.status() == StatusCode::NOT_MODIFIED {
+            return Ok(None);
+        }
+
+        let resp = resp.error_for_status()?;
+
+        let etag = resp
+            .headers()
+            .get(ETAG)
+            .and_then(|etag| etag.to_str().ok())
+            .map(ToOwned::to_owned);
+
+        let bytes = resp.bytes().await?;
+
+        let jws = Jws::parse_verified(&bytes)?;
+
+        write_atomically(jws_json_file_path, &bytes, false).await?;
+
+        match etag {
+            Some(etag) => write_atomically(&etag_file_path, etag.as_bytes(), false).await?,
+            None => match fs::remove_file(&etag_file_path).await {
+                Ok(()) => {},
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {},
+                Err(err) => return Err(err.into()),
+            },
+        }
+
+        Ok(Some(jws))
+    }
+
+    pub(super) fn get(&self, id: &str) -> anyhow::Result<Option<Bytes>> {
+        let Some(value) = self.entries.get(id) else {
+            return Ok(None);
+        };
+
+        let bytes = serde_json::to_vec(value)?;
+        let bytes = Bytes::from(bytes);
+
+        Ok(Some(bytes))
+    }
+
+    pub(super) fn contains(&self, id: &str) -> bool {
+        self.entries.contains_key(id)
+    }
+
+    pub(super) fn alias_target(&self, alias: &str) -> Option<&str> {
+        self.aliases.get(alias).map(String::as_str)
+    }
+
+    pub(super) fn rename_target(&self, old_name: &str) -> Option<&str> {
+        self.renames.get(old_name).map(String::as_str)
+    }
+}
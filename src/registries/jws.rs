@@ -0,0 +1,220 @@
+use anyhow::{Context as _, anyhow};
+use aws_lc_rs::signature::{RSA_PSS_2048_8192_SHA512, UnparsedPublicKey};
+use base64::{
+    Engine as _,
+    alphabet,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig, general_purpose},
+};
+use serde::Deserialize;
+
+const PUBLIC_KEYS: &[(&str, &str)] = &[("homebrew-1", include_str!("keys/homebrew-1.pem"))];
+
+const ALGORITHM: &str = "PS512";
+
+const URL_SAFE: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::URL_SAFE,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+#[derive(Deserialize)]
+pub(super) struct Jws {
+    payload: String,
+    signatures: Vec<Signature>,
+}
+
+#[expect(clippy::struct_field_names)]
+#[derive(Deserialize)]
+struct Signature {
+    protected: String,
+    header: Header,
+    signature: String,
+}
+
+#[derive(Deserialize)]
+struct Header {
+    kid: String,
+}
+
+#[derive(Deserialize)]
+struct ProtectedHeader {
+    alg: String,
+    b64: Option<bool>,
+}
+
+impl Jws {
+    pub(super) fn parse_verified(bytes: &[u8]) -> anyhow::Result<Self> {
+        let this: Self = serde_json::from_slice(bytes).context("JWS envelope is malformed")?;
+
+        this.verify(PUBLIC_KEYS)?;
+
+        Ok(this)
+    }
+
+    fn verify(&self, public_keys: &[(&str, &str)]) -> anyhow::Result<()> {
+        let Some((signature, pem)) = self.signatures.iter().find_map(|signature| {
+            public_keys
+                .iter()
+                .find(|(key_id, _)| *key_id == signature.header.kid)
+                .map(|(_, pem)| (signature, *pem))
+        }) else {
+            let err = anyhow!("JWS envelope has no Homebrew signature");
+
+            return Err(err);
+        };
+
+        let public_key = Self::decode_public_key(&signature.header.kid, pem)?;
+
+        signature.verify(&self.payload, &public_key)?;
+
+        Ok(())
+    }
+
+    fn decode_public_key(key_id: &str, pem: &str) -> anyhow::Result<Vec<u8>> {
+        let base64 = pem
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with("-----"))
+            .collect::<String>();
+
+        if base64.is_empty() {
+            let err = anyhow!(r#"Homebrew public key "{key_id}" is empty"#);
+
+            return Err(err);
+        }
+
+        let public_key = general_purpose::STANDARD
+            .decode(base64)
+            .with_context(|| format!(r#"Homebrew public key "{key_id}" is malformed"#))?;
+
+        Ok(public_key)
+    }
+
+    pub(super) fn payload(&self) -> &str {
+        &self.payload
+    }
+}
+
+impl Signature {
+    fn verify(&self, payload: &str, public_key: &[u8]) -> anyhow::Result<()> {
+        let protected_header = URL_SAFE
+            .decode(&self.protected)
+            .context("JWS protected header is malformed")?;
+
+        let protected_header: ProtectedHeader = serde_json::from_slice(&protected_header)
+            .context("JWS protected header is malformed")?;
+
+        if protected_header.alg != ALGORITHM || protected_header.b64 != Some(false) {
+            let alg = protected_header.alg;
+
+            let err = anyhow!(r#"JWS signature uses unsupported algorithm "{alg}""#);
+
+            return Err(err);
+        }
+
+        let signature = URL_SAFE
+            .decode(&self.signature)
+            .context("JWS signature is malformed")?;
+
+        let signing_input = format!("{}.{payload}", self.protected);
+
+        let public_key = UnparsedPublicKey::new(&RSA_PSS_2048_8192_SHA512, public_key);
+
+        public_key
+            .verify(signing_input.as_bytes(), &signature)
+            .context("JWS signature verification failed")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_lc_rs::{
+        encoding::AsDer as _,
+        rand::SystemRandom,
+        rsa::{KeyPair, KeySize},
+        signature::{KeyPair as _, RSA_PSS_SHA512},
+    };
+
+    use super::*;
+
+    const TEST_KEY_ID: &str = "test-1";
+
+    const PAYLOAD: &str = r#"[{"name":"hello"}]"#;
+
+    #[test]
+    fn embedded_public_keys_are_valid() {
+        for (key_id, pem) in PUBLIC_KEYS {
+            let public_key = Jws::decode_public_key(key_id, pem);
+
+            assert!(public_key.is_ok(), r#"embedded key "{key_id}" is invalid"#);
+        }
+    }
+
+    #[test]
+    fn verifies_signed_envelope() {
+        let (pem, bytes) = sign(PAYLOAD);
+
+        let jws: Jws = serde_json::from_str(&bytes).expect("envelope should parse");
+
+        assert!(jws.verify(&[(TEST_KEY_ID, &pem)]).is_ok());
+        assert_eq!(jws.payload(), PAYLOAD);
+    }
+
+    #[test]
+    fn rejects_tampered_payload() {
+        let (pem, bytes) = sign(PAYLOAD);
+
+        let bytes = bytes.replace("hello", "hallo");
+
+        let jws: Jws = serde_json::from_str(&bytes).expect("envelope should parse");
+
+        assert!(jws.verify(&[(TEST_KEY_ID, &pem)]).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_key_id() {
+        let (pem, bytes) = sign(PAYLOAD);
+
+        let jws: Jws = serde_json::from_str(&bytes).expect("envelope should parse");
+
+        assert!(jws.verify(&[("other-1", &pem)]).is_err());
+    }
+
+    fn sign(payload: &str) -> (String, String) {
+        let key_pair = KeyPair::generate(KeySize::Rsa2048).expect("key should generate");
+
+        let public_key = key_pair.public_key().as_der().expect("key should encode");
+        let public_key = general_purpose::STANDARD.encode(public_key.as_ref());
+
+        let pem = format!("-----BEGIN PUBLIC KEY-----\n{public_key}\n-----END PUBLIC KEY-----\n");
+
+        let protected = URL_SAFE.encode(r#"{"alg":"PS512","b64":false,"crit":["b64"]}"#);
+
+        let signing_input = format!("{protected}.{payload}");
+
+        let mut signature = vec![0; key_pair.public_modulus_len()];
+
+        key_pair
+            .sign(
+                &RSA_PSS_SHA512,
+                &SystemRandom::new(),
+                signing_input.as_bytes(),
+                &mut signature,
+            )
+            .expect("payload should sign");
+
+        let signature = URL_SAFE.encode(signature);
+
+        let envelope = serde_json::json!({
+            "payload": payload,
+            "signatures": [{
+                "protected": protected,
+                "header": { "kid": TEST_KEY_ID },
+                "signature": signature,
+            }],
+        });
+
+        (pem, envelope.to_string())
+    }
+}
@@ -1,53 +1,120 @@
 mod cask;
 mod compatibility;
 mod formula;
+mod index;
+mod jws;
+mod tap;
+mod tap_migrations;
 
-use std::{collections::HashSet, path::PathBuf, sync::Arc};
+use std::{borrow::Cow, collections::HashSet, path::PathBuf, sync::Arc};
 
 use anyhow::anyhow;
 use bytes::Bytes;
 use futures::future::{self, FutureExt as _};
-use tempfile::NamedTempFile;
 use tokio::{
-    fs::{self, File},
-    io::AsyncWriteExt as _,
+    fs,
+    io::{self, AsyncWriteExt as _},
 };
 
 use self::{
     cask::CaskRegistry,
     compatibility::{Compatibility, CompatibilityExt as _},
     formula::FormulaRegistry,
+    index::Index,
+    tap::{Tap, TapKind},
+    tap_migrations::TapMigrations,
 };
 use crate::{
     context::Context,
-    ext::{std::path::PathExt as _, tokio::fs::FileExt as _},
+    ext::tokio::{fs::write_atomically, path::PathExt as _},
     package::{
         PackageExt as _,
-        resolved::{ResolvedPackage, ResolvedPackageExt as _},
+        raw::formula::DependencyType,
+        resolved::{
+            ResolvedPackage,
+            ResolvedPackageExt as _,
+            cask::ResolvedCask,
+            formula::ResolvedFormula,
+        },
     },
 };
 
+const OFFICIAL_TAPS: &[&str] = &["homebrew/core", "homebrew/cask"];
+
+const ALLOW_UNSIGNED_API_HINT: &str = "set NEOBREW_ALLOW_UNSIGNED_API to fetch packages unsigned";
+
+pub(crate) fn qualify_formula<'a>(tap: Option<&str>, id: &'a str) -> Cow<'a, str> {
+    match tap {
+        Some(tap) => FormulaRegistry::qualify(tap, id),
+        None => Cow::Borrowed(id),
+    }
+}
+
+pub(crate) fn qualify_cask<'a>(tap: Option<&str>, id: &'a str) -> Cow<'a, str> {
+    match tap {
+        Some(tap) => CaskRegistry::qualify(tap, id),
+        None => Cow::Borrowed(id),
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+pub(crate) struct DependencyFilter {
+    pub(crate) include_build: bool,
+    pub(crate) include_test: bool,
+    pub(crate) include_optional: bool,
+}
+
+impl DependencyFilter {
+    fn includes(self, dependency_type: DependencyType) -> bool {
+        match dependency_type {
+            DependencyType::Runtime | DependencyType::Recommended => true,
+            DependencyType::Build => self.include_build,
+            DependencyType::Test => self.include_test,
+            DependencyType::Optional => self.include_optional,
+        }
+    }
+}
+
 pub(crate) struct Registries {
     formula_registry: Arc<FormulaRegistry>,
     cask_registry: Arc<CaskRegistry>,
+
+    context: Arc<Context>,
 }
 
 impl Registries {
     pub(crate) async fn try_new(context: Arc<Context>) -> anyhow::Result<Self> {
+        let dependency_filter = DependencyFilter::default();
+
+        Self::try_with_dependency_filter(dependency_filter, context).await
+    }
+
+    pub(crate) async fn try_with_dependency_filter(
+        dependency_filter: DependencyFilter,
+        context: Arc<Context>,
+    ) -> anyhow::Result<Self> {
         let compatibility = Compatibility::try_new(&context).await?;
         let compatibility = Arc::new(compatibility);
 
-        let formula_registry =
-            FormulaRegistry::new(Arc::clone(&compatibility), Arc::clone(&context));
+        let formula_registry = FormulaRegistry::new(
+            dependency_filter,
+            Arc::clone(&compatibility),
+            Arc::clone(&context),
+        );
         let formula_registry = Arc::new(formula_registry);
 
-        let cask_registry =
-            CaskRegistry::new(Arc::clone(&formula_registry), compatibility, context);
+        let cask_registry = CaskRegistry::new(
+            Arc::clone(&formula_registry),
+            compatibility,
+            Arc::clone(&context),
+        );
         let cask_registry = Arc::new(cask_registry);
 
         let this = Self {
             formula_registry,
             cask_registry,
+
+            context,
         };
 
         Ok(this)
@@ -81,6 +148,24 @@ pub(crate) async fn resolve(self, packages: &[String]) -> anyhow::Result<Vec<Res
         Ok(resolved_packages)
     }
 
+    pub(crate) async fn resolve_formula(
+        &self,
+        package: Arc<str>,
+    ) -> anyhow::Result<Arc<ResolvedFormula>> {
+        let formula_registry = Arc::clone(&self.formula_registry);
+
+        formula_registry.resolve(package).await
+    }
+
+    pub(crate) async fn resolve_cask(
+        &self,
+        package: Arc<str>,
+    ) -> anyhow::Result<Arc<ResolvedCask>> {
+        let cask_registry = Arc::clone(&self.cask_registry);
+
+        cask_registry.resolve(package).await
+    }
+
     async fn resolve_many(self, packages: &[String]) -> anyhow::Result<Vec<ResolvedPackage>> {
         let resolved_packages_fut = packages.iter().map(async |package| {
             let package = package.as_ref();
@@ -91,16 +176,105 @@ async fn resolve_many(self, packages: &[String]) -> anyhow::Result<Vec<ResolvedP
             anyhow::Ok(resolved_package)
         });
 
-        let resolved_packages = future::try_join_all(resolved_packages_fut).await?;
+        let resolved_package_results = future::join_all(resolved_packages_fut).await;
+
+        let mut resolved_packages = Vec::new();
+
+        let mut errs = Vec::new();
+
+        for resolved_package_res in resolved_package_results {
+            match resolved_package_res {
+                Ok(resolved_package) => resolved_packages.push(resolved_package),
+                Err(err) => errs.push(err),
+            }
+        }
+
+        if errs.len() > 1 {
+            let errs = errs
+                .iter()
+                .map(|err| format!("  {err:#}"))
+                .collect::<Vec<_>>();
+            let errs = errs.join("\n");
+
+            let err = anyhow!("Failed to resolve packages:\n{errs}");
+
+            return Err(err);
+        }
+
+        if let Some(err) = errs.pop() {
+            return Err(err);
+        }
 
         Ok(resolved_packages)
     }
 
-    async fn resolve_one(&self, package: Arc<str>) -> anyhow::Result<ResolvedPackage> {
+    pub(crate) async fn resolve_one(&self, package: Arc<str>) -> anyhow::Result<ResolvedPackage> {
+        if self.context.config.offline {
+            let resolved_package = self.resolve_one_offline(package).await?;
+
+            return Ok(resolved_package);
+        }
+
+        if let Ok(resolved_package) = self.race(&package).await {
+            return Ok(resolved_package);
+        }
+
+        if let Some(new_package) = self.migrate(&package).await?
+            && let Ok(resolved_package) = self.race(&new_package).await
+        {
+            return Ok(resolved_package);
+        }
+
+        let err = anyhow!(r#"Package "{package}" not found"#);
+
+        Err(err)
+    }
+
+    async fn resolve_one_offline(&self, package: Arc<str>) -> anyhow::Result<ResolvedPackage> {
+        if let Some((tap, _)) = Tap::split(&package)
+            && !OFFICIAL_TAPS.contains(&tap)
+        {
+            let resolved_package = self.race(&package).await?;
+
+            return Ok(resolved_package);
+        }
+
+        if self.formula_registry.is_cached(&package).await? {
+            let formula_registry = Arc::clone(&self.formula_registry);
+
+            let resolved_formula = formula_registry.resolve(package).await?;
+            let resolved_formula = ResolvedPackage::Formula(resolved_formula);
+
+            return Ok(resolved_formula);
+        }
+
+        if self.cask_registry.is_cached(&package).await? {
+            let cask_registry = Arc::clone(&self.cask_registry);
+
+            let resolved_cask = cask_registry.resolve(package).await?;
+            let resolved_cask = ResolvedPackage::Cask(resolved_cask);
+
+            return Ok(resolved_cask);
+        }
+
+        let formula_json_file_path = self.formula_registry.json_path(&package);
+
+        let cask_json_file_path = self.cask_registry.json_path(&package);
+
+        let err = anyhow!(
+            r#"Package "{package}" is not cached at "{}" or "{}""#,
+            formula_json_file_path.display(),
+            cask_json_file_path.display(),
+        );
+
+        Err(err)
+    }
+
+    async fn race(&self, package: &Arc<str>) -> anyhow::Result<ResolvedPackage> {
         let resolved_formula_fut = async {
             let formula_registry = Arc::clone(&self.formula_registry);
 
-            let resolved_formula = formula_registry.resolve(Arc::clone(&package)).await?;
+            let resolved_formula = formula_registry.resolve(Arc::clone(package)).await?;
             let resolved_formula = ResolvedPackage::Formula(resolved_formula);
 
             anyhow::Ok(resolved_formula)
@@ -110,7 +284,7 @@ async fn resolve_one(&self, package: Arc<str>) -> anyhow::Result<ResolvedPackage
         let resolved_cask_fut = async {
             let cask_registry = Arc::clone(&self.cask_registry);
 
-            let resolved_cask = cask_registry.resolve(Arc::clone(&package)).await?;
+            let resolved_cask = cask_registry.resolve(Arc::clone(package)).await?;
             let resolved_cask = ResolvedPackage::Cask(resolved_cask);
 
             anyhow::Ok(resolved_cask)
@@ -120,19 +294,48 @@ async fn resolve_one(&self, package: Arc<str>) -> anyhow::Result<ResolvedPackage
         let resolved_package_res =
             future::select_ok([resolved_formula_fut, resolved_cask_fut]).await;
 
-        #[expect(clippy::manual_let_else, clippy::single_match_else)]
-        let resolved_package = match resolved_package_res {
-            Ok((resolved_package, _)) => resolved_package,
-            Err(_) => {
-                let err = anyhow!(r#"Package "{package}" not found"#);
-
-                return Err(err);
-            },
-        };
+        let (resolved_package, _) = resolved_package_res?;
 
         Ok(resolved_package)
     }
 
+    async fn migrate(&self, package: &str) -> anyhow::Result<Option<Arc<str>>> {
+        let formula_tap_migrations = self.formula_registry.tap_migrations().await?;
+
+        let cask_tap_migrations = self.cask_registry.tap_migrations().await?;
+
+        let tap_migrations_list = [
+            ("Formula", formula_tap_migrations),
+            ("Cask", cask_tap_migrations),
+        ];
+
+        for (kind, tap_migrations) in tap_migrations_list {
+            let Some(tap_migrations) = tap_migrations else {
+                continue;
+            };
+
+            let Some((new_tap, new_id)) = tap_migrations.get(package) else {
+                continue;
+            };
+
+            let new_id = new_id.unwrap_or(package);
+
+            let message = format!(r#"{kind} "{package}" was migrated to "{new_tap}/{new_id}""#);
+
+            notify(&message).await?;
+
+            let new_package = if OFFICIAL_TAPS.contains(&new_tap) {
+                Arc::from(new_id)
+            } else {
+                Arc::from(format!("{new_tap}/{new_id}"))
+            };
+
+            return Ok(Some(new_package));
+        }
+
+        Ok(None)
+    }
+
     fn clear_dependencies(resolved_packages: &mut [ResolvedPackage]) {
         let dependency_count = resolved_packages
             .iter_mut()
@@ -182,6 +385,9 @@ enum Registry {
 trait RegistryExt: RegistryJsonExt {
     type ResolvedPackage;
 
+    const TAP: &str;
+    const TAP_KIND: TapKind;
+
     const API_URL: &str;
 
     const JSON_URL: &str;
@@ -206,6 +412,40 @@ async fn fetch_with_stack(
         package: Arc<str>,
         stack: Vec<Arc<str>>,
     ) -> anyhow::Result<Arc<Self::ResolvedPackage>>;
+
+    async fn fetch_json(&self, package: &str) -> anyhow::Result<Bytes>;
+
+    async fn index(&self) -> anyhow::Result<Result<&Index, &anyhow::Error>>;
+
+    async fn tap_migrations(&self) -> anyhow::Result<Option<&TapMigrations>>;
+
+    async fn canonicalize(&self, package: Arc<str>) -> anyhow::Result<Arc<str>>;
+
+    fn qualify<'a>(tap: &str, id: &'a str) -> Cow<'a, str> {
+        if tap == Self::TAP {
+            return Cow::Borrowed(id);
+        }
+
+        Cow::Owned(format!("{tap}/{id}"))
+    }
+
+    async fn is_cached(&self, package: &str) -> anyhow::Result<bool> {
+        let index = self.index().await?;
+
+        if let Ok(index) = index
+            && (index.contains(package)
+                || index.alias_target(package).is_some()
+                || index.rename_target(package).is_some())
+        {
+            return Ok(true);
+        }
+
+        let json_file_path = self.json_path(package);
+
+        let is_cached = json_file_path.is_file_exists_nofollow().await?;
+
+        Ok(is_cached)
+    }
 }
 
 trait RegistryJsonExt {
@@ -214,22 +454,32 @@ trait RegistryJsonExt {
     async fn save_json(&self, id: &str, bytes: Bytes) -> anyhow::Result<()> {
         let dest_file_path = self.json_path(id);
 
-        let dest_file_base_path = dest_file_path.base()?;
+        write_atomically(&dest_file_path, &bytes, false).await?;
 
-        fs::create_dir_all(dest_file_base_path).await?;
+        Ok(())
+    }
 
-        let json_file = NamedTempFile::new_in(dest_file_base_path)?;
+    async fn load_json(&self, id: &str) -> anyhow::Result<Option<Bytes>> {
+        let json_file_path = self.json_path(id);
 
-        let json_file_path = json_file.path();
+        let bytes = match fs::read(json_file_path).await {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        Ok(Some(Bytes::from(bytes)))
+    }
+}
 
-        let mut async_json_file = File::open_write(json_file_path).await?;
+pub(crate) async fn notify(message: &str) -> anyhow::Result<()> {
+    let mut stderr = io::stderr();
 
-        async_json_file.write_all(&bytes).await?;
+    let message = format!("Warning: {message}\n");
 
-        async_json_file.shutdown().await?;
+    stderr.write_all(message.as_bytes()).await?;
 
-        json_file.persist(dest_file_path)?;
+    stderr.flush().await?;
 
-        Ok(())
-    }
+    Ok(())
 }
@@ -0,0 +1,185 @@
+use std::path::PathBuf;
+
+use anyhow::{Context as _, anyhow};
+use bytes::Bytes;
+use serde_json::Value;
+use tokio::{fs, io, process::Command};
+
+use crate::{context::Context, ext::tokio::path::PathExt as _};
+
+#[derive(Clone, Copy)]
+pub(super) enum TapKind {
+    Formula,
+    Cask,
+}
+
+impl TapKind {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Formula => "Formula",
+            Self::Cask => "Cask",
+        }
+    }
+
+    fn dir_names(self) -> &'static [&'static str] {
+        match self {
+            Self::Formula => &["Formula", "HomebrewFormula", "."],
+            Self::Cask => &["Casks"],
+        }
+    }
+
+    fn brew_info_flag(self) -> &'static str {
+        match self {
+            Self::Formula => "--formula",
+            Self::Cask => "--cask",
+        }
+    }
+
+    fn brew_info_key(self) -> &'static str {
+        match self {
+            Self::Formula => "formulae",
+            Self::Cask => "casks",
+        }
+    }
+}
+
+pub(super) struct Tap<'a> {
+    user: &'a str,
+    repo: &'a str,
+    kind: TapKind,
+}
+
+impl<'a> Tap<'a> {
+    pub(super) fn split(package: &str) -> Option<(&str, &str)> {
+        let (tap, id) = package.rsplit_once('/')?;
+
+        let (user, repo) = tap.split_once('/')?;
+
+        if user.is_empty() || repo.is_empty() || repo.contains('/') || id.is_empty() {
+            return None;
+        }
+
+        Some((tap, id))
+    }
+
+    pub(super) fn new(tap: &'a str, kind: TapKind) -> anyhow::Result<Self> {
+        let (user, repo) = tap
+            .split_once('/')
+            .with_context(|| format!(r#"Tap "{tap}" is invalid"#))?;
+
+        let this = Self {
+            user,
+            repo,
+            kind,
+        };
+
+        Ok(this)
+    }
+
+    pub(super) async fn find_definition(
+        &self,
+        id: &str,
+        context: &Context,
+    ) -> anyhow::Result<Option<PathBuf>> {
+        let tap_dir_path = context.homebrew_dirs.tap_dir(self.user, self.repo);
+
+        if !tap_dir_path.is_dir_exists_nofollow().await? {
+            let (user, repo) = (self.user, self.repo);
+
+            let err = anyhow!(r#"Tap "{user}/{repo}" is not tapped"#);
+
+            return Err(err);
+        }
+
+        let shard = id.chars().next().map(String::from).unwrap_or_default();
+
+        for extension in ["json", "rb"] {
+            let file_name = format!("{id}.{extension}");
+
+            for dir_name in self.kind.dir_names() {
+                let dir_path = tap_dir_path.join(dir_name);
+
+                for file_path in [
+                    dir_path.join(&file_name),
+                    dir_path.join(&shard).join(&file_name),
+                ] {
+                    if file_path.is_file_exists_nofollow().await? {
+                        return Ok(Some(file_path));
+                    }
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    pub(super) async fn fetch_json(&self, id: &str, context: &Context) -> anyhow::Result<Bytes> {
+        let (user, repo) = (self.user, self.repo);
+
+        let kind = self.kind.name();
+
+        let Some(definition_file_path) = self.find_definition(id, context).await? else {
+            let err = anyhow!(r#"{kind} "{user}/{repo}/{id}" not found"#);
+
+            return Err(err);
+        };
+
+        if definition_file_path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            let bytes = match fs::read(&definition_file_path).await {
+                Ok(bytes) => bytes,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                    let err = anyhow!(r#"{kind} "{user}/{repo}/{id}" not found"#);
+
+                    return Err(err);
+                },
+                Err(err) => return Err(err.into()),
+            };
+
+            return Ok(Bytes::from(bytes));
+        }
+
+        let bytes = self.brew_info(id).await?;
+
+        Ok(bytes)
+    }
+
+    async fn brew_info(&self, id: &str) -> anyhow::Result<Bytes> {
+        let (user, repo) = (self.user, self.repo);
+
+        let kind = self.kind.name();
+
+        let full_id = format!("{user}/{repo}/{id}");
+
+        let output = Command::new("brew")
+            .args(["info", "--json=v2", self.kind.brew_info_flag(), &full_id])
+            .env("HOMEBREW_NO_ANALYTICS", "1")
+            .env("HOMEBREW_NO_AUTO_UPDATE", "1")
+            .env("HOMEBREW_NO_ENV_HINTS", "1")
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stderr = stderr.trim();
+
+            let err = anyhow!(r#"{kind} "{full_id}" failed to load: {stderr}"#);
+
+            return Err(err);
+        }
+
+        let mut info: Value = serde_json::from_slice(&output.stdout)?;
+
+        let value = info
+            .get_mut(self.kind.brew_info_key())
+            .and_then(Value::as_array_mut)
+            .and_then(Vec::pop)
+            .with_context(|| format!(r#"{kind} "{full_id}" not found"#))?;
+
+        let bytes = serde_json::to_vec(&value)?;
+
+        Ok(Bytes::from(bytes))
+    }
+}
@@ -0,0 +1,44 @@
+use std::{collections::HashMap, path::Path};
+
+use super::index::Index;
+use crate::context::Context;
+
+pub(super) struct TapMigrations {
+    migrations: HashMap<String, String>,
+}
+
+impl TapMigrations {
+    pub(super) async fn load(
+        jws_json_url: &str,
+        jws_json_file_path: &Path,
+        context: &Context,
+    ) -> anyhow::Result<Option<Self>> {
+        let Ok(jws) = Index::load_jws(jws_json_url, jws_json_file_path, context).await? else {
+            return Ok(None);
+        };
+
+        let migrations = serde_json::from_str(jws.payload())?;
+
+        let this = Self {
+            migrations,
+        };
+
+        Ok(Some(this))
+    }
+
+    pub(super) fn get(&self, id: &str) -> Option<(&str, Option<&str>)> {
+        let new_tap = self.migrations.get(id)?;
+
+        let migration = match new_tap.splitn(3, '/').nth(2) {
+            Some(new_id) => {
+                let new_tap = new_tap.strip_suffix(new_id)?;
+                let new_tap = new_tap.strip_suffix('/')?;
+
+                (new_tap, Some(new_id))
+            },
+            None => (new_tap.as_str(), None),
+        };
+
+        Some(migration)
+    }
+}
@@ -0,0 +1,278 @@
+use std::{
+    os::unix::fs::MetadataExt as _,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::{fs, io};
+
+use crate::ext::{
+    std::path::PathExt as _,
+    tokio::{fs::write_atomically, path::PathExt as _},
+};
+
+const INCOMPLETE_FILE_EXTENSION: &str = "incomplete";
+
+const VERIFIED_FILE_EXTENSION: &str = "verified";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RefKind {
+    Formula,
+    Cask,
+}
+
+impl RefKind {
+    const fn dir_name(self) -> &'static str {
+        match self {
+            Self::Formula => "formula",
+            Self::Cask => "cask",
+        }
+    }
+}
+
+pub(crate) struct Ref {
+    pub(crate) kind: RefKind,
+
+    pub(crate) id: String,
+    pub(crate) version: String,
+
+    pub(crate) link_path: PathBuf,
+}
+
+pub(crate) struct Blob {
+    pub(crate) file_path: PathBuf,
+
+    pub(crate) sha256: String,
+
+    pub(crate) length: u64,
+    pub(crate) modified: SystemTime,
+
+    pub(crate) is_incomplete: bool,
+}
+
+#[derive(PartialEq, Eq, Serialize, Deserialize)]
+struct Fingerprint {
+    inode: u64,
+    length: u64,
+    modified_secs: i64,
+    modified_nsecs: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct VerifiedDigest {
+    sha256: String,
+
+    #[serde(flatten)]
+    fingerprint: Fingerprint,
+}
+
+pub(crate) struct Store {
+    dir_path: PathBuf,
+}
+
+impl Store {
+    pub(crate) fn new(dir_path: PathBuf) -> Self {
+        Self {
+            dir_path,
+        }
+    }
+
+    fn blob_dir(&self) -> PathBuf {
+        self.dir_path.join("blobs").join("sha256")
+    }
+
+    pub(crate) fn blob_file(&self, sha256: &str) -> PathBuf {
+        let blob_dir = self.blob_dir();
+
+        blob_dir.join(sha256)
+    }
+
+    pub(crate) fn incomplete_file(&self, sha256: &str) -> PathBuf {
+        let blob_file = self.blob_file(sha256);
+
+        blob_file.with_added_extension(INCOMPLETE_FILE_EXTENSION)
+    }
+
+    fn ref_dir(&self) -> PathBuf {
+        self.dir_path.join("refs")
+    }
+
+    pub(crate) fn ref_link(&self, kind: RefKind, id: &str, version: &str) -> PathBuf {
+        let ref_dir = self.ref_dir();
+
+        ref_dir.join(kind.dir_name()).join(id).join(version)
+    }
+
+    pub(crate) fn verified_file(blob_file_path: &Path) -> PathBuf {
+        blob_file_path.with_added_extension(VERIFIED_FILE_EXTENSION)
+    }
+
+    pub(crate) async fn is_verified(blob_file_path: &Path, sha256: &str) -> anyhow::Result<bool> {
+        let verified_file_path = Self::verified_file(blob_file_path);
+
+        let content = match fs::read(&verified_file_path).await {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(err) => return Err(err.into()),
+        };
+
+        let Ok(verified_digest) = serde_json::from_slice::<VerifiedDigest>(&content) else {
+            return Ok(false);
+        };
+
+        let Some(fingerprint) = Self::fingerprint(blob_file_path).await? else {
+            return Ok(false);
+        };
+
+        let is_verified =
+            verified_digest.sha256 == sha256 && verified_digest.fingerprint == fingerprint;
+
+        Ok(is_verified)
+    }
+
+    pub(crate) async fn record_verified(blob_file_path: &Path, sha256: &str) -> anyhow::Result<()> {
+        let Some(fingerprint) = Self::fingerprint(blob_file_path).await? else {
+            return Ok(());
+        };
+
+        let verified_digest = VerifiedDigest {
+            sha256: sha256.to_owned(),
+
+            fingerprint,
+        };
+
+        let content = serde_json::to_vec(&verified_digest)?;
+
+        let verified_file_path = Self::verified_file(blob_file_path);
+
+        write_atomically(&verified_file_path, &content, false).await?;
+
+        Ok(())
+    }
+
+    async fn fingerprint(blob_file_path: &Path) -> anyhow::Result<Option<Fingerprint>> {
+        let metadata = match fs::symlink_metadata(blob_file_path).await {
+            Ok(metadata) => metadata,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        if !metadata.is_file() {
+            return Ok(None);
+        }
+
+        let fingerprint = Fingerprint {
+            inode: metadata.ino(),
+            length: metadata.size(),
+            modified_secs: metadata.mtime(),
+            modified_nsecs: metadata.mtime_nsec(),
+        };
+
+        Ok(Some(fingerprint))
+    }
+
+    pub(crate) async fn adopt(&self, file_path: &Path, sha256: &str) -> anyhow::Result<()> {
+        let blob_file_path = self.blob_file(sha256);
+
+        let blob_file_base_path = blob_file_path.base()?;
+
+        fs::create_dir_all(blob_file_base_path).await?;
+
+        if fs::rename(file_path, &blob_file_path).await.is_err() {
+            fs::copy(file_path, &blob_file_path).await?;
+
+            fs::remove_file(file_path).await?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) async fn refs(&self) -> anyhow::Result<Vec<Ref>> {
+        let mut refs = Vec::new();
+
+        for kind in [RefKind::Formula, RefKind::Cask] {
+            let kind_dir_path = self.ref_dir().join(kind.dir_name());
+
+            for id_dir_path in read_dir(&kind_dir_path).await? {
+                for link_path in read_dir(&id_dir_path).await? {
+                    let (Some(id), Some(version)) =
+                        (id_dir_path.file_name(), link_path.file_name())
+                    else {
+                        continue;
+                    };
+
+                    let store_ref = Ref {
+                        kind,
+
+                        id: id.to_string_lossy().into_owned(),
+                        version: version.to_string_lossy().into_owned(),
+
+                        link_path,
+                    };
+
+                    refs.push(store_ref);
+                }
+            }
+        }
+
+        Ok(refs)
+    }
+
+    pub(crate) async fn blobs(&self) -> anyhow::Result<Vec<Blob>> {
+        let mut blobs = Vec::new();
+
+        for file_path in read_dir(&self.blob_dir()).await? {
+            if !file_path.is_file_exists_nofollow().await? {
+                continue;
+            }
+
+            let Some(file_name) = file_path.file_name() else {
+                continue;
+            };
+
+            let file_name = file_name.to_string_lossy();
+
+            let (sha256, is_incomplete) = match file_name.split_once('.') {
+                Some((sha256, INCOMPLETE_FILE_EXTENSION)) => (sha256.to_owned(), true),
+                Some(_) => continue,
+                None => (file_name.into_owned(), false),
+            };
+
+            let metadata = fs::symlink_metadata(&file_path).await?;
+
+            let blob = Blob {
+                file_path,
+
+                sha256,
+
+                length: metadata.len(),
+                modified: metadata.modified()?,
+
+                is_incomplete,
+            };
+
+            blobs.push(blob);
+        }
+
+        Ok(blobs)
+    }
+}
+
+async fn read_dir(dir_path: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let mut read_dir = match fs::read_dir(dir_path).await {
+        Ok(read_dir) => read_dir,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+
+    let mut paths = Vec::new();
+
+    while let Some(entry) = read_dir.next_entry().await? {
+        paths.push(entry.path());
+    }
+
+    paths.sort();
+
+    Ok(paths)
+}
@@ -0,0 +1,78 @@
+use std::{fs::File, hint, path::Path, sync::mpsc, thread};
+
+use base16ct::HexDisplay;
+use memmap2::{Advice, Mmap};
+use sha2::{Digest as _, Sha256};
+
+const CHUNK_LENGTH: usize = 1 << 23;
+
+const PAGE_LENGTH: usize = 1 << 12;
+
+const READER_COUNT: usize = 1 << 2;
+
+pub(crate) fn sha256_file(file_path: &Path) -> anyhow::Result<String> {
+    let file = File::open(file_path)?;
+
+    let file_length = file.metadata()?.len();
+
+    let mut sha256_digest = Sha256::new();
+
+    if file_length > 0 {
+        // SAFETY: Downloads are only ever replaced by renaming a new file over them, never
+        // truncated or rewritten in place, so the mapped pages stay valid while hashing.
+        #[expect(unsafe_code)]
+        let mmap = unsafe { Mmap::map(&file)? };
+
+        mmap.advise(Advice::Sequential)?;
+
+        // SHA-256 can only consume its input in order, so the readers fault chunks in
+        // round-robin ahead of the hasher instead of hashing them separately.
+        thread::scope(|scope| {
+            let receivers = (0..READER_COUNT)
+                .map(|reader_index| {
+                    let (sender, receiver) = mpsc::sync_channel(1);
+
+                    let chunks = mmap
+                        .chunks(CHUNK_LENGTH)
+                        .skip(reader_index)
+                        .step_by(READER_COUNT);
+
+                    scope.spawn(move || {
+                        for chunk in chunks {
+                            fault_in(chunk);
+
+                            if sender.send(chunk).is_err() {
+                                break;
+                            }
+                        }
+                    });
+
+                    receiver
+                })
+                .collect::<Vec<_>>();
+
+            for receiver in receivers.iter().cycle() {
+                let Ok(chunk) = receiver.recv() else {
+                    break;
+                };
+
+                sha256_digest.update(chunk);
+            }
+        });
+    }
+
+    let sha256 = sha256_digest.finalize();
+    let sha256 = HexDisplay(&sha256);
+    let sha256 = format!("{sha256:x}");
+
+    Ok(sha256)
+}
+
+fn fault_in(chunk: &[u8]) {
+    let checksum = chunk
+        .iter()
+        .step_by(PAGE_LENGTH)
+        .fold(0, |checksum, byte| checksum ^ byte);
+
+    hint::black_box(checksum);
+}
@@ -1,30 +1,83 @@
-use std::path::Path;
-
-use tokio::{
-    fs::File,
-    io::{self, AsyncReadExt as _},
-};
-
 pub(crate) struct Elf;
 
 impl Elf {
     const ELF_MAGIC: &[u8; 4] = b"\x7fELF";
+
+    const EI_DATA: usize = 5;
+
+    const ELFDATA2LSB: u8 = 1;
+    const ELFDATA2MSB: u8 = 2;
+
+    const E_MACHINE_OFFSET: usize = 18;
+
+    pub(crate) const EM_386: u16 = 3;
+    pub(crate) const EM_ARM: u16 = 40;
+    pub(crate) const EM_PPC64: u16 = 21;
+    pub(crate) const EM_X86_64: u16 = 62;
+    pub(crate) const EM_AARCH64: u16 = 183;
 }
 
 impl Elf {
-    pub(crate) async fn has_magic(path: &Path) -> anyhow::Result<bool> {
-        let mut file = File::open(path).await?;
+    pub(crate) fn has_magic(peek_bytes: &[u8]) -> bool {
+        peek_bytes.starts_with(Self::ELF_MAGIC)
+    }
 
-        let mut peek_buf = [0_u8; 4];
+    pub(crate) fn machine(bytes: &[u8]) -> Option<u16> {
+        let data = bytes.get(Self::EI_DATA)?;
 
-        match file.read_exact(&mut peek_buf).await {
-            Ok(_) => {},
-            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(false),
-            Err(err) => return Err(err)?,
+        let machine_bytes = bytes.get(Self::E_MACHINE_OFFSET..)?;
+        let machine_bytes = machine_bytes.first_chunk::<2>()?;
+
+        match *data {
+            Self::ELFDATA2LSB => Some(u16::from_le_bytes(*machine_bytes)),
+            Self::ELFDATA2MSB => Some(u16::from_be_bytes(*machine_bytes)),
+            _ => None,
         }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(data: u8, machine_bytes: [u8; 2]) -> Vec<u8> {
+        let mut bytes = vec![0; 20];
+
+        bytes.splice(..4, *Elf::ELF_MAGIC);
+
+        bytes.splice(Elf::EI_DATA..=Elf::EI_DATA, [data]);
+
+        bytes.splice(Elf::E_MACHINE_OFFSET.., machine_bytes);
+
+        bytes
+    }
+
+    #[test]
+    fn reads_little_endian_machine() {
+        let bytes = header(Elf::ELFDATA2LSB, Elf::EM_AARCH64.to_le_bytes());
+
+        assert!(Elf::has_magic(&bytes));
+        assert_eq!(Elf::machine(&bytes), Some(Elf::EM_AARCH64));
+    }
+
+    #[test]
+    fn reads_big_endian_machine() {
+        let bytes = header(Elf::ELFDATA2MSB, Elf::EM_PPC64.to_be_bytes());
+
+        assert_eq!(Elf::machine(&bytes), Some(Elf::EM_PPC64));
+    }
+
+    #[test]
+    fn rejects_unknown_data_encoding() {
+        let bytes = header(0, Elf::EM_X86_64.to_le_bytes());
+
+        assert_eq!(Elf::machine(&bytes), None);
+    }
 
-        let has_magic = &peek_buf == Self::ELF_MAGIC;
+    #[test]
+    fn rejects_truncated_header() {
+        let bytes = header(Elf::ELFDATA2LSB, Elf::EM_X86_64.to_le_bytes());
 
-        Ok(has_magic)
+        assert_eq!(Elf::machine(bytes.get(..19).unwrap_or_default()), None);
     }
 }
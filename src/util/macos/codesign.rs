@@ -1,16 +1,15 @@
 use std::path::Path;
 
 use apple_codesign::{SigningSettings, UnifiedSigner};
-use tokio::task;
-use tokio_util::task::AbortOnDropHandle;
+use tokio_util::task::{AbortOnDropHandle, TaskTracker};
 
 pub(crate) struct Codesign;
 
 impl Codesign {
-    pub(crate) async fn in_place(target_path: &Path) -> anyhow::Result<()> {
+    pub(crate) async fn in_place(target_path: &Path, tasks: &TaskTracker) -> anyhow::Result<()> {
         let target_path = target_path.to_owned();
 
-        let handle = task::spawn_blocking({
+        let handle = tasks.spawn_blocking({
             || {
                 let settings = SigningSettings::default();
 
@@ -1,10 +1,3 @@
-use std::path::Path;
-
-use tokio::{
-    fs::File,
-    io::{self, AsyncReadExt as _},
-};
-
 pub(crate) struct MachO;
 
 impl MachO {
@@ -34,29 +27,23 @@ impl MachO {
 }
 
 impl MachO {
-    pub(crate) async fn has_magic(path: &Path) -> anyhow::Result<bool> {
-        let mut file = File::open(path).await?;
-
-        let mut peek_buf = [0_u8; 4];
-
-        match file.read_exact(&mut peek_buf).await {
-            Ok(_) => {},
-            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(false),
-            Err(err) => return Err(err)?,
-        }
+    pub(crate) fn has_magic(peek_bytes: &[u8]) -> bool {
+        let Some(peek_buf) = peek_bytes.first_chunk::<4>() else {
+            return false;
+        };
 
-        let peek_magic = u32::from_be_bytes(peek_buf);
+        let peek_magic = u32::from_be_bytes(*peek_buf);
 
         if Self::BE_MAGICS.contains(&peek_magic) {
-            return Ok(true);
+            return true;
         }
 
-        let peek_magic = u32::from_le_bytes(peek_buf);
+        let peek_magic = u32::from_le_bytes(*peek_buf);
 
         if Self::LE_MAGICS.contains(&peek_magic) {
-            return Ok(true);
+            return true;
         }
 
-        Ok(false)
+        false
     }
 }
@@ -1,4 +1,5 @@
 pub(crate) mod archive_format;
+pub(crate) mod digest;
 pub(crate) mod linux;
 pub(crate) mod macos;
 mod semver;